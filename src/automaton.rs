@@ -0,0 +1,346 @@
+//! This module provides a driver for cellular automata (such as Conway's Game of Life) over
+//! the Z-arrays. A *CellularAutomaton* owns two buffers of the same size: each step reads the
+//! neighborhood of every cell from the current buffer (with *stencil(...)*), writes the new
+//! states into the other buffer, and then swaps them.
+//!
+//! Steps can either use any rule function, or a *LifeRule* that is parsed from a standard
+//! rule string, such as `B3/S23` (Conway's Game of Life), `B2/S/C3` (Brian's Brain) or the
+//! 3D notation `4/4/5/M` (survival/birth/states/neighborhood).
+//! # Example Usage
+//! A glider in Conway's Game of Life on a toroidal (wrap-around) map:
+//! ```
+//! use zarray::EdgeMode;
+//! use zarray::automaton::{CellularAutomaton, LifeRule};
+//! use zarray::z2d::ZArray2D;
+//! let rule: LifeRule = "B3/S23".parse().unwrap();
+//! let mut map = ZArray2D::<_>::new(16, 16, 0u8);
+//! for (x, y) in [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)] {
+//!   map.set(x, y, 1).unwrap();
+//! }
+//! let mut life = CellularAutomaton::new(map);
+//! for _ in 0..4 {
+//!   life.step_rule(&rule, &EdgeMode::Wrap);
+//! }
+//! // after 4 generations, the glider has moved one cell diagonally
+//! assert_eq!(life.generation(), 4);
+//! assert_eq!(*life.current().get(2, 3).unwrap(), 1);
+//! assert_eq!(life.current().iter().filter(|cell| *cell.value == 1).count(), 5);
+//! ```
+
+use core::error::Error;
+use core::fmt::{Display, Formatter};
+use core::str::FromStr;
+use crate::EdgeMode;
+use crate::znd::{StencilView, ZArrayND};
+
+/// This struct is a driver for cellular automata over a *ZArray2D*, *ZArray3D* or *ZArrayND*.
+/// It owns two buffers (the current generation and the next generation) and swaps them after
+/// each step.
+#[derive(Debug, Clone)]
+pub struct CellularAutomaton<A> {
+	/// current generation
+	current: A,
+	/// buffer for the next generation
+	next: A,
+	/// number of steps that have been applied
+	generation: usize,
+}
+
+impl<A> CellularAutomaton<A> where A: Clone {
+	/// Creates a new cellular automaton, starting from the given array
+	/// # Parameters
+	/// * **initial** - the cells of the first generation (eg a *ZArray2D*)
+	/// # Returns
+	/// Returns a new *CellularAutomaton* at generation 0
+	pub fn new(initial: A) -> CellularAutomaton<A> {
+		return CellularAutomaton { next: initial.clone(), current: initial, generation: 0 };
+	}
+}
+
+impl<A> CellularAutomaton<A> {
+	/// Gets the current generation of cells
+	pub fn current(&self) -> &A {
+		return &self.current;
+	}
+
+	/// Gets the current generation of cells for editing (eg to add cells between steps)
+	pub fn current_mut(&mut self) -> &mut A {
+		return &mut self.current;
+	}
+
+	/// Consumes this cellular automaton and returns the current generation of cells
+	pub fn into_current(self) -> A {
+		return self.current;
+	}
+
+	/// Gets the number of steps that have been applied since this cellular automaton was created
+	pub fn generation(&self) -> usize {
+		return self.generation;
+	}
+
+	/// Advances the cellular automaton by one generation, by applying the rule function to the
+	/// neighborhood of every cell
+	/// # Parameters
+	/// * **radius** - size of the neighborhood in each direction
+	/// * **edge_mode** - how to handle neighbors that are outside of the array (eg
+	/// *EdgeMode::Wrap* for a toroidal map)
+	/// * **rule** - function that takes the view of a cell's neighborhood in the current
+	/// generation and returns the state of the cell in the next generation
+	pub fn step<T, const N: usize, const E: usize>(&mut self, radius: usize, edge_mode: &EdgeMode<T>,
+		rule: impl FnMut(&StencilView<'_, T, N>) -> T)
+		where A: AsRef<ZArrayND<T, N, E>> + AsMut<ZArrayND<T, N, E>> {
+		self.current.as_ref().stencil(self.next.as_mut(), radius, edge_mode, rule);
+		core::mem::swap(&mut self.current, &mut self.next);
+		self.generation += 1;
+	}
+
+	/// Advances the cellular automaton by one generation according to a life-like rule, where
+	/// each cell is a `u8` state (0 is dead, 1 is alive, and higher states are dying cells for
+	/// rules with more than 2 states)
+	/// # Parameters
+	/// * **rule** - the rule to apply (eg `"B3/S23".parse().unwrap()`)
+	/// * **edge_mode** - how to handle neighbors that are outside of the array (eg
+	/// *EdgeMode::Wrap* for a toroidal map)
+	pub fn step_rule<const N: usize, const E: usize>(&mut self, rule: &LifeRule, edge_mode: &EdgeMode<u8>)
+		where A: AsRef<ZArrayND<u8, N, E>> + AsMut<ZArrayND<u8, N, E>> {
+		let von_neumann = rule.neighborhood == RuleNeighborhood::VonNeumann;
+		self.step(1, edge_mode, |view| {
+			let alive = view.neighbors().filter(|(offset, state)| {
+				**state == 1 && (!von_neumann || offset.iter().map(|o| o.unsigned_abs()).sum::<usize>() == 1)
+			}).count();
+			rule.next_state(*view.center(), alive)
+		});
+	}
+}
+
+/// This enum is the neighborhood of a *LifeRule*
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RuleNeighborhood {
+	/// All cells that touch the cell, including diagonally (8 cells in 2D, 26 cells in 3D)
+	Moore,
+	/// All cells that share a face with the cell (4 cells in 2D, 6 cells in 3D)
+	VonNeumann,
+}
+
+/// This struct is a life-like cellular automaton rule, which decides whether a dead cell is
+/// born and whether a live cell survives from the number of live cells around it. Rules with
+/// more than 2 states (aka "Generations" rules) have dying states: a live cell that does not
+/// survive moves through the dying states (which do not count as alive) before it dies.
+///
+/// Rules are parsed from strings (with *parse(...)* or `str::parse()`) in any of these
+/// notations (ignoring case and spaces):
+/// * `B3/S23` - birth/survival, where each digit is a number of live neighbors (this is
+/// Conway's Game of Life), optionally followed by the number of states (eg `B2/S/C3`)
+/// * `23/3` - survival/birth, optionally followed by the number of states (eg `/2/3`)
+/// * `4/4/5/M` - survival/birth/states/neighborhood, the notation for 3D rules, where the
+/// survival and birth counts are comma-separated numbers or ranges (eg `2,6,9/4,6-8/10/M`) and
+/// the neighborhood is `M` (Moore) or `VN` (von Neumann)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LifeRule {
+	/// bit flags of the numbers of live neighbors for which a dead cell is born
+	birth: u128,
+	/// bit flags of the numbers of live neighbors for which a live cell survives
+	survival: u128,
+	/// number of states (2 for rules without dying states)
+	states: u8,
+	neighborhood: RuleNeighborhood,
+}
+
+impl LifeRule {
+	/// Creates a new rule from lists of neighbor counts
+	/// # Parameters
+	/// * **birth** - numbers of live neighbors for which a dead cell is born
+	/// * **survival** - numbers of live neighbors for which a live cell survives
+	/// * **states** - number of states (2 for rules without dying states)
+	/// * **neighborhood** - which neighbors are counted
+	/// # Returns
+	/// Returns a Result type that holds either the new *LifeRule* or a *RuleParseError* if a
+	/// neighbor count is more than 127 or there are fewer than 2 states
+	pub fn new(birth: &[usize], survival: &[usize], states: u8, neighborhood: RuleNeighborhood)
+		-> Result<LifeRule, RuleParseError> {
+		if states < 2 {
+			return Err(RuleParseError { reason: "a rule must have at least 2 states" });
+		}
+		let mut rule = LifeRule { birth: 0, survival: 0, states, neighborhood };
+		for count in birth {
+			rule.birth |= count_flag(*count)?;
+		}
+		for count in survival {
+			rule.survival |= count_flag(*count)?;
+		}
+		return Ok(rule);
+	}
+
+	/// Parses a rule string (see *LifeRule* for the supported notations)
+	/// # Parameters
+	/// * **rule** - the rule string, eg `B3/S23` or `4/4/5/M`
+	/// # Returns
+	/// Returns a Result type that holds either the parsed *LifeRule* or a *RuleParseError*
+	/// explaining why the rule string is not valid
+	pub fn parse(rule: &str) -> Result<LifeRule, RuleParseError> {
+		let mut parts = [""; 4];
+		let mut count = 0;
+		for part in rule.split('/') {
+			if count == parts.len() {
+				return Err(RuleParseError { reason: "too many parts separated by '/'" });
+			}
+			parts[count] = part.trim();
+			count += 1;
+		}
+		let parts = &parts[..count];
+		let mut result = LifeRule { birth: 0, survival: 0, states: 2, neighborhood: RuleNeighborhood::Moore };
+		if parts.iter().any(|p| p.starts_with(['B', 'b', 'S', 's'])) {
+			// B3/S23 notation (in any order), with an optional number of states
+			let (mut birth, mut survival) = (false, false);
+			for part in parts {
+				// split after the first character (which may be more than one byte long)
+				let (prefix, counts) = part.split_at(part.chars().next().map_or(0, char::len_utf8));
+				match prefix {
+					"B" | "b" if !birth => { result.birth = parse_digits(counts)?; birth = true; },
+					"S" | "s" if !survival => { result.survival = parse_digits(counts)?; survival = true; },
+					"C" | "c" | "G" | "g" => result.states = parse_states(counts)?,
+					_ if part.chars().all(|c| c.is_ascii_digit()) && !part.is_empty() => result.states = parse_states(part)?,
+					_ => return Err(RuleParseError { reason: "expected a part starting with 'B', 'S' or 'C'" })
+				}
+			}
+			if !(birth && survival) {
+				return Err(RuleParseError { reason: "rule must have both a birth ('B') and a survival ('S') part" });
+			}
+		} else if parts.len() == 4 {
+			// 3D notation: survival/birth/states/neighborhood
+			result.survival = parse_list(parts[0])?;
+			result.birth = parse_list(parts[1])?;
+			result.states = parse_states(parts[2])?;
+			result.neighborhood = match parts[3] {
+				"M" | "m" => RuleNeighborhood::Moore,
+				"VN" | "vn" | "Vn" | "N" | "n" => RuleNeighborhood::VonNeumann,
+				_ => return Err(RuleParseError { reason: "neighborhood must be 'M' or 'VN'" })
+			};
+		} else if parts.len() >= 2 {
+			// survival/birth notation, with an optional number of states
+			result.survival = parse_digits(parts[0])?;
+			result.birth = parse_digits(parts[1])?;
+			if parts.len() == 3 {
+				result.states = parse_states(parts[2])?;
+			}
+		} else {
+			return Err(RuleParseError { reason: "expected a rule such as B3/S23 or 4/4/5/M" });
+		}
+		return Ok(result);
+	}
+
+	/// Gets whether a dead cell with the given number of live neighbors is born
+	pub fn is_birth(&self, live_neighbors: usize) -> bool {
+		return live_neighbors < 128 && self.birth & (1 << live_neighbors) != 0;
+	}
+
+	/// Gets whether a live cell with the given number of live neighbors survives
+	pub fn is_survival(&self, live_neighbors: usize) -> bool {
+		return live_neighbors < 128 && self.survival & (1 << live_neighbors) != 0;
+	}
+
+	/// Gets the number of states of this rule (2 for rules without dying states)
+	pub fn states(&self) -> u8 {
+		return self.states;
+	}
+
+	/// Gets the neighborhood of this rule
+	pub fn neighborhood(&self) -> RuleNeighborhood {
+		return self.neighborhood;
+	}
+
+	/// Applies this rule to one cell
+	/// # Parameters
+	/// * **state** - current state of the cell (0 is dead, 1 is alive, higher states are dying)
+	/// * **live_neighbors** - number of neighbors in state 1
+	/// # Returns
+	/// Returns the next state of the cell
+	pub fn next_state(&self, state: u8, live_neighbors: usize) -> u8 {
+		return match state {
+			0 if self.is_birth(live_neighbors) => 1,
+			0 => 0,
+			1 if self.is_survival(live_neighbors) => 1,
+			// dying states count up to the number of states, then the cell is dead
+			s => s.checked_add(1).filter(|next| *next < self.states).unwrap_or(0)
+		};
+	}
+}
+
+impl FromStr for LifeRule {
+	type Err = RuleParseError;
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		return LifeRule::parse(s);
+	}
+}
+
+/// private function for converting a neighbor count to its bit flag
+fn count_flag(count: usize) -> Result<u128, RuleParseError> {
+	if count >= 128 {
+		return Err(RuleParseError { reason: "neighbor counts must be less than 128" });
+	}
+	return Ok(1 << count);
+}
+
+/// private function for parsing a list of single-digit neighbor counts, eg "23"
+fn parse_digits(counts: &str) -> Result<u128, RuleParseError> {
+	if counts.contains([',', '-']) {
+		return parse_list(counts);
+	}
+	let mut flags = 0;
+	for c in counts.chars() {
+		match c.to_digit(10) {
+			Some(d) => flags |= count_flag(d as usize)?,
+			None => return Err(RuleParseError { reason: "neighbor counts must be digits" })
+		}
+	}
+	return Ok(flags);
+}
+
+/// private function for parsing a comma-separated list of neighbor counts and ranges of
+/// neighbor counts, eg "2,6,9" or "4-8,10"
+fn parse_list(counts: &str) -> Result<u128, RuleParseError> {
+	let mut flags = 0;
+	for item in counts.split(',').map(str::trim).filter(|item| !item.is_empty()) {
+		let (first, last) = match item.split_once('-') {
+			Some((first, last)) => (parse_count(first)?, parse_count(last)?),
+			None => (parse_count(item)?, parse_count(item)?)
+		};
+		if first > last {
+			return Err(RuleParseError { reason: "a range of neighbor counts must not be backwards" });
+		}
+		for count in first..=last {
+			flags |= count_flag(count)?;
+		}
+	}
+	return Ok(flags);
+}
+
+/// private function for parsing one neighbor count
+fn parse_count(count: &str) -> Result<usize, RuleParseError> {
+	return count.trim().parse().map_err(|_| RuleParseError { reason: "neighbor counts must be numbers" });
+}
+
+/// private function for parsing the number of states
+fn parse_states(states: &str) -> Result<u8, RuleParseError> {
+	return match states.trim().parse::<u8>() {
+		Ok(states) if states >= 2 => Ok(states),
+		_ => Err(RuleParseError { reason: "the number of states must be from 2 to 255" })
+	};
+}
+
+/// This struct is an error type that is returned when a *LifeRule* cannot be parsed. It
+/// implements the Debug and Display traits so that it can be easily printed as an error message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RuleParseError {
+	/// why the rule is not valid
+	reason: &'static str,
+}
+
+impl Display for RuleParseError {
+	// user-facing error message
+	fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+		return write!(f, "Error: invalid cellular automaton rule: {}", self.reason);
+	}
+}
+
+impl Error for RuleParseError{}
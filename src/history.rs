@@ -0,0 +1,227 @@
+//! This module provides an undo/redo history for the Z-arrays, built on snapshots. The first
+//! snapshot copies the values of the array into reference-counted data patches, and each
+//! following snapshot shares the patches that did not change since the one before it, so each
+//! undo step only takes up the memory of the patches that were changed by that step.
+//! # Example Usage
+//! ```
+//! use zarray::history::UndoHistory;
+//! use zarray::z3d::ZArray3D;
+//! let mut history = UndoHistory::new(ZArray3D::<_>::new(64, 64, 64, 0u8));
+//! // each call to edit() records an undo step before the edit
+//! history.edit().set(1, 2, 3, 7).unwrap();
+//! history.edit().bounded_fill(0, 0, 0, 8, 8, 8, 9);
+//! assert_eq!(*history.current().get(1, 2, 3).unwrap(), 9);
+//! history.undo();
+//! assert_eq!(*history.current().get(1, 2, 3).unwrap(), 7);
+//! history.undo();
+//! assert_eq!(*history.current().get(1, 2, 3).unwrap(), 0);
+//! history.redo();
+//! assert_eq!(*history.current().get(1, 2, 3).unwrap(), 7);
+//! ```
+
+use alloc::collections::VecDeque;
+use alloc::vec::Vec;
+use crate::z2d::ZArray2D;
+use crate::z3d::ZArray3D;
+use crate::znd::{ZArrayND, ZArrayNDSnapshot};
+
+/// Trait for arrays that can take cheap snapshots of themselves and be restored from them
+/// (implemented by *ZArray2D*, *ZArray3D* and *ZArrayND*)
+pub trait Undoable {
+	/// the type of the snapshots
+	type Snapshot: Clone;
+	/// Takes a snapshot of this array
+	fn snapshot(&self) -> Self::Snapshot;
+	/// Takes a snapshot of this array that shares the parts that have not changed since a
+	/// previous snapshot of this array
+	fn snapshot_since(&self, previous: &Self::Snapshot) -> Self::Snapshot;
+	/// Sets this array back to the state of a snapshot
+	fn restore(&mut self, snapshot: &Self::Snapshot);
+}
+
+impl<T, const N: usize, const E: usize> Undoable for ZArrayND<T, N, E> where T: Clone {
+	type Snapshot = ZArrayNDSnapshot<T, N, E>;
+	fn snapshot(&self) -> Self::Snapshot {
+		return ZArrayND::snapshot(self);
+	}
+	fn snapshot_since(&self, previous: &Self::Snapshot) -> Self::Snapshot {
+		return ZArrayND::snapshot_since(self, previous);
+	}
+	fn restore(&mut self, snapshot: &Self::Snapshot) {
+		ZArrayND::restore(self, snapshot);
+	}
+}
+
+impl<T, const E: usize> Undoable for ZArray2D<T, E> where T: Clone {
+	type Snapshot = ZArrayNDSnapshot<T, 2, E>;
+	fn snapshot(&self) -> Self::Snapshot {
+		return ZArray2D::snapshot(self);
+	}
+	fn snapshot_since(&self, previous: &Self::Snapshot) -> Self::Snapshot {
+		return ZArray2D::snapshot_since(self, previous);
+	}
+	fn restore(&mut self, snapshot: &Self::Snapshot) {
+		ZArray2D::restore(self, snapshot);
+	}
+}
+
+impl<T, const E: usize> Undoable for ZArray3D<T, E> where T: Clone {
+	type Snapshot = ZArrayNDSnapshot<T, 3, E>;
+	fn snapshot(&self) -> Self::Snapshot {
+		return ZArray3D::snapshot(self);
+	}
+	fn snapshot_since(&self, previous: &Self::Snapshot) -> Self::Snapshot {
+		return ZArray3D::snapshot_since(self, previous);
+	}
+	fn restore(&mut self, snapshot: &Self::Snapshot) {
+		ZArray3D::restore(self, snapshot);
+	}
+}
+
+/// This struct holds the current state of an array plus the snapshots of its previous states
+/// (for undo) and of the states that were undone (for redo). Record an undo step by calling
+/// *edit()* (or *checkpoint()*) before each undoable change.
+#[derive(Debug, Clone)]
+pub struct UndoHistory<A> where A: Undoable {
+	/// current state of the array
+	current: A,
+	/// snapshot of the current state from the last checkpoint, undo or redo, which the next
+	/// snapshot shares its unchanged data patches with
+	latest: Option<A::Snapshot>,
+	/// previous states, the most recent last
+	undo_stack: VecDeque<A::Snapshot>,
+	/// undone states, the most recently undone last
+	redo_stack: Vec<A::Snapshot>,
+	/// maximum number of undo steps to keep
+	limit: usize,
+}
+
+impl<A> UndoHistory<A> where A: Undoable {
+	/// Creates a new history with no undo steps, which keeps any number of undo steps
+	/// # Parameters
+	/// * **initial** - the starting state of the array
+	/// # Returns
+	/// Returns a new *UndoHistory*
+	pub fn new(initial: A) -> UndoHistory<A> {
+		return UndoHistory::with_limit(initial, usize::MAX);
+	}
+
+	/// Creates a new history with no undo steps, which drops the oldest undo step when there
+	/// would be more than *limit* steps
+	/// # Parameters
+	/// * **initial** - the starting state of the array
+	/// * **limit** - maximum number of undo steps to keep
+	/// # Returns
+	/// Returns a new *UndoHistory*
+	pub fn with_limit(initial: A, limit: usize) -> UndoHistory<A> {
+		return UndoHistory { current: initial, latest: None, undo_stack: VecDeque::new(), redo_stack: Vec::new(), limit };
+	}
+
+	/// Records the current state as an undo step (and clears the redo steps, since they no
+	/// longer follow from the current state)
+	pub fn checkpoint(&mut self) {
+		self.redo_stack.clear();
+		if self.limit == 0 {
+			return;
+		}
+		if self.undo_stack.len() >= self.limit {
+			self.undo_stack.pop_front();
+		}
+		let snapshot = self.take_snapshot();
+		self.undo_stack.push_back(snapshot);
+	}
+
+	/// private function for taking a snapshot of the current state, sharing the unchanged
+	/// data patches of the latest snapshot
+	fn take_snapshot(&mut self) -> A::Snapshot {
+		let snapshot = match &self.latest {
+			Some(latest) => self.current.snapshot_since(latest),
+			None => self.current.snapshot()
+		};
+		self.latest = Some(snapshot.clone());
+		return snapshot;
+	}
+
+	/// Records the current state as an undo step (like *checkpoint()*) and then gets the
+	/// current state for editing. All changes made with the returned reference are undone
+	/// together by the next call to *undo()*.
+	/// # Returns
+	/// Returns a mutable reference to the current state
+	pub fn edit(&mut self) -> &mut A {
+		self.checkpoint();
+		return &mut self.current;
+	}
+
+	/// Gets the current state of the array
+	pub fn current(&self) -> &A {
+		return &self.current;
+	}
+
+	/// Consumes this history and returns the current state of the array
+	pub fn into_current(self) -> A {
+		return self.current;
+	}
+
+	/// Goes back to the state of the most recent undo step, keeping the current state as a
+	/// redo step
+	/// # Returns
+	/// Returns true if a step was undone, or false if there are no undo steps
+	pub fn undo(&mut self) -> bool {
+		let Some(previous) = self.undo_stack.pop_back() else {
+			return false;
+		};
+		let snapshot = self.take_snapshot();
+		self.redo_stack.push(snapshot);
+		self.current.restore(&previous);
+		self.latest = Some(previous);
+		return true;
+	}
+
+	/// Goes forward to the state of the most recently undone step, keeping the current state
+	/// as an undo step
+	/// # Returns
+	/// Returns true if a step was redone, or false if there are no redo steps
+	pub fn redo(&mut self) -> bool {
+		let Some(next) = self.redo_stack.pop() else {
+			return false;
+		};
+		let snapshot = self.take_snapshot();
+		self.undo_stack.push_back(snapshot);
+		self.current.restore(&next);
+		self.latest = Some(next);
+		return true;
+	}
+
+	/// Gets the number of undo steps
+	pub fn undo_count(&self) -> usize {
+		return self.undo_stack.len();
+	}
+
+	/// Gets the number of redo steps
+	pub fn redo_count(&self) -> usize {
+		return self.redo_stack.len();
+	}
+
+	/// Gets the maximum number of undo steps that this history keeps
+	pub fn limit(&self) -> usize {
+		return self.limit;
+	}
+
+	/// Changes the maximum number of undo steps, dropping the oldest steps if there are more
+	/// than the new limit
+	/// # Parameters
+	/// * **limit** - maximum number of undo steps to keep
+	pub fn set_limit(&mut self, limit: usize) {
+		self.limit = limit;
+		while self.undo_stack.len() > limit {
+			self.undo_stack.pop_front();
+		}
+	}
+
+	/// Drops all undo and redo steps, keeping only the current state
+	pub fn clear(&mut self) {
+		self.latest = None;
+		self.undo_stack.clear();
+		self.redo_stack.clear();
+	}
+}
@@ -0,0 +1,242 @@
+//! This module implements conversions between *ZArray2D* and the *ImageBuffer* of the *image*
+//! crate, plus PNG loading and saving (enabled with the `image` feature, which requires
+//! `std`). The value type of the array determines the pixel type of the image:
+//!  * `u8`, `u16` and `f32` values are grayscale (*Luma*) pixels
+//!  * `[S; 2]` values are grayscale-with-alpha (*LumaA*) pixels
+//!  * `[S; 3]` values are *Rgb* pixels
+//!  * `[S; 4]` values are *Rgba* pixels
+//!
+//! where `S` is `u8`, `u16` or `f32` (PNG files only support `u8` and `u16` channels). The
+//! value of (x, y) is the pixel at (x, y), so the width of the image is the *xsize* of the
+//! array and the height of the image is the *ysize*. A *ZArray3D* is converted to and from a
+//! stack of images, one XY slice per Z coordinate.
+//! # Example Usage
+//! ```
+//! use image::{ImageBuffer, Rgba};
+//! use zarray::z2d::ZArray2D;
+//! let mut map = ZArray2D::<_>::new(40, 30, [0u8, 0u8, 0u8, 255u8]);
+//! map.bounded_fill(10, 10, 20, 20, [255u8, 0u8, 0u8, 255u8]);
+//! let image = ImageBuffer::<Rgba<u8>, Vec<u8>>::from(&map);
+//! assert_eq!(image.dimensions(), (40, 30));
+//! assert_eq!(image.get_pixel(15, 12), &Rgba([255u8, 0u8, 0u8, 255u8]));
+//! let copy = ZArray2D::<[u8; 4]>::from(&image);
+//! assert_eq!(*copy.get(15, 12).unwrap(), [255u8, 0u8, 0u8, 255u8]);
+//! ```
+
+use std::fs::File;
+use std::io::BufReader;
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::vec::Vec;
+use std::format;
+use image::{DynamicImage, EncodableLayout, ImageBuffer, ImageError, ImageFormat, ImageResult,
+			Luma, LumaA, Pixel, PixelWithColorType, Rgb, Rgba};
+use image::error::{ParameterError, ParameterErrorKind};
+use crate::{BuildError, ErrorCoord};
+use crate::z2d::ZArray2D;
+use crate::z3d::ZArray3D;
+use crate::znd::ZArrayND;
+
+/// Image buffer type for arrays of values of type `V`
+pub type PixelBuffer<V> = ImageBuffer<<V as PixelValue>::Pixel, Vec<<<V as PixelValue>::Pixel as Pixel>::Subpixel>>;
+
+/// Trait for array values that can be stored as the pixels of an image
+pub trait PixelValue: Clone {
+	/// The *image* pixel type for this value type
+	type Pixel: Pixel;
+	/// Converts this value to a pixel
+	fn to_pixel(&self) -> Self::Pixel;
+	/// Converts a pixel to a value
+	fn from_pixel(pixel: &Self::Pixel) -> Self;
+	/// Converts a decoded image to an image buffer of this value type's pixel type (eg
+	/// converting an RGB image to grayscale)
+	fn convert_image(image: &DynamicImage) -> PixelBuffer<Self>;
+}
+
+/// Private macro for implementing *PixelValue* for one channel type
+macro_rules! impl_pixel_value {
+	($s:ty) => {
+		impl PixelValue for $s {
+			type Pixel = Luma<$s>;
+			fn to_pixel(&self) -> Luma<$s> {
+				return Luma([*self]);
+			}
+			fn from_pixel(pixel: &Luma<$s>) -> Self {
+				return pixel.0[0];
+			}
+			fn convert_image(image: &DynamicImage) -> PixelBuffer<Self> {
+				return image.to::<Luma<$s>>();
+			}
+		}
+		impl_pixel_value!($s, 2, LumaA);
+		impl_pixel_value!($s, 3, Rgb);
+		impl_pixel_value!($s, 4, Rgba);
+	};
+	($s:ty, $n:literal, $p:ident) => {
+		impl PixelValue for [$s; $n] {
+			type Pixel = $p<$s>;
+			fn to_pixel(&self) -> $p<$s> {
+				return $p(*self);
+			}
+			fn from_pixel(pixel: &$p<$s>) -> Self {
+				return pixel.0;
+			}
+			fn convert_image(image: &DynamicImage) -> PixelBuffer<Self> {
+				return image.to::<$p<$s>>();
+			}
+		}
+	};
+}
+
+impl_pixel_value!(u8);
+impl_pixel_value!(u16);
+impl_pixel_value!(f32);
+
+/// Private function for decoding a PNG image file
+fn open_png(path: impl AsRef<Path>) -> ImageResult<DynamicImage> {
+	return image::load(BufReader::new(File::open(path)?), ImageFormat::Png);
+}
+
+/// Private function for making the error returned when the images of a stack do not all have
+/// the same size
+fn stack_size_error() -> ImageError {
+	return ImageError::Parameter(ParameterError::from_kind(ParameterErrorKind::DimensionMismatch));
+}
+
+impl<V, C, const E: usize> From<&ImageBuffer<V::Pixel, C>> for ZArray2D<V, E>
+	where V: PixelValue, C: Deref<Target=[<V::Pixel as Pixel>::Subpixel]> {
+	/// Copies an image into a *ZArray2D* with the same width and height, where the pixel at
+	/// (x, y) becomes the value of (x, y)
+	fn from(image: &ImageBuffer<V::Pixel, C>) -> Self {
+		let values: Vec<V> = image.pixels().map(V::from_pixel).collect();
+		return ZArrayND::from_row_major_vec([image.width() as usize, image.height() as usize], values).into();
+	}
+}
+
+impl<V, const E: usize> From<&ZArray2D<V, E>> for ImageBuffer<V::Pixel, Vec<<V::Pixel as Pixel>::Subpixel>>
+	where V: PixelValue {
+	/// Copies a *ZArray2D* into an image with the same width and height, where the value of
+	/// (x, y) becomes the pixel at (x, y)
+	fn from(array: &ZArray2D<V, E>) -> Self {
+		let (width, height) = array.dimensions();
+		let mut image = ImageBuffer::new(width as u32, height as u32);
+		for (pixel, value) in image.pixels_mut().zip(array.to_row_major()) {
+			*pixel = value.to_pixel();
+		}
+		return image;
+	}
+}
+
+impl<V> ZArray2D<V> where V: PixelValue {
+	/// Loads a PNG image file as a new *ZArray2D*, converting the pixels to the pixel type of
+	/// the value type if necessary (eg an RGB image loaded as `u8` values becomes grayscale)
+	/// # Parameters
+	/// * **path** - file path of the PNG image
+	/// # Returns
+	/// Returns a *Result* containing the array, or an *ImageError* if the file could not be
+	/// read or decoded
+	pub fn load_png(path: impl AsRef<Path>) -> ImageResult<ZArray2D<V>> {
+		let image = open_png(path)?;
+		return Ok(ZArray2D::from(&V::convert_image(&image)));
+	}
+}
+
+impl<V, const E: usize> ZArray2D<V, E> where V: PixelValue {
+	/// Copies this array into an image with the same width and height (same as
+	/// `ImageBuffer::from(&array)`)
+	pub fn to_image(&self) -> PixelBuffer<V> {
+		return ImageBuffer::from(self);
+	}
+
+	/// Saves this array as a PNG image file, where the value of (x, y) becomes the pixel at
+	/// (x, y)
+	/// # Parameters
+	/// * **path** - file path of the PNG image
+	/// # Returns
+	/// Returns an *ImageError* if the file could not be written or the value type is not
+	/// supported by the PNG format (only `u8` and `u16` channels are)
+	pub fn save_png(&self, path: impl AsRef<Path>) -> ImageResult<()>
+		where V::Pixel: PixelWithColorType, [<V::Pixel as Pixel>::Subpixel]: EncodableLayout {
+		return self.to_image().save_with_format(path, ImageFormat::Png);
+	}
+}
+
+impl<V> ZArray3D<V> where V: PixelValue {
+	/// Creates a new *ZArray3D* from a stack of images, where image `z` becomes the XY slice
+	/// at `z`
+	/// # Parameters
+	/// * **images** - images of the XY slices, which must all have the same width and height
+	/// # Returns
+	/// Returns a *Result* containing the array, or a *BuildError::DimensionMismatch* if an
+	/// image does not have the same size as the first image
+	pub fn from_image_stack<C>(images: &[ImageBuffer<V::Pixel, C>]) -> Result<ZArray3D<V>, BuildError>
+		where C: Deref<Target=[<V::Pixel as Pixel>::Subpixel]> {
+		let (width, height) = images.first().map(|image| image.dimensions()).unwrap_or((0, 0));
+		let mut values: Vec<V> = Vec::with_capacity(width as usize * height as usize * images.len());
+		for image in images {
+			if image.dimensions() != (width, height) {
+				return Err(BuildError::DimensionMismatch {
+					expected: ErrorCoord::new(&[width as usize, height as usize]),
+					actual: ErrorCoord::new(&[image.width() as usize, image.height() as usize])
+				});
+			}
+			values.extend(image.pixels().map(V::from_pixel));
+		}
+		return Ok(ZArrayND::from_row_major([width as usize, height as usize, images.len()], &values)?.into());
+	}
+
+	/// Loads a stack of PNG image files as a new *ZArray3D*, where file `z` becomes the XY
+	/// slice at `z` (see *load_png(...)* for how the pixels are converted)
+	/// # Parameters
+	/// * **paths** - file paths of the PNG images, in Z order
+	/// # Returns
+	/// Returns a *Result* containing the array, or an *ImageError* if a file could not be read
+	/// or decoded or if the images do not all have the same size
+	pub fn load_png_stack<P: AsRef<Path>>(paths: impl IntoIterator<Item=P>) -> ImageResult<ZArray3D<V>> {
+		let mut images = Vec::new();
+		for path in paths {
+			images.push(V::convert_image(&open_png(path)?));
+		}
+		return ZArray3D::from_image_stack(&images).map_err(|_| stack_size_error());
+	}
+}
+
+impl<V, const E: usize> ZArray3D<V, E> where V: PixelValue {
+	/// Copies this array into a stack of images, one per XY slice, where pixel (x, y) of image
+	/// `z` is the value of (x, y, z). There is always one image per slice, so an array with
+	/// an empty XY plane gives *zsize* empty images.
+	pub fn to_image_stack(&self) -> Vec<PixelBuffer<V>> {
+		let (xsize, ysize, zsize) = self.dimensions();
+		let values = self.to_row_major();
+		let plane = xsize * ysize;
+		return (0..zsize).map(|z| {
+			let mut image = ImageBuffer::new(xsize as u32, ysize as u32);
+			for (pixel, value) in image.pixels_mut().zip(&values[z * plane..(z + 1) * plane]) {
+				*pixel = value.to_pixel();
+			}
+			image
+		}).collect();
+	}
+
+	/// Saves this array as a stack of PNG image files, one per XY slice. The files are named
+	/// with the given prefix followed by the Z coordinate, zero-padded to the same number of
+	/// digits for every slice (eg `slice_00.png` to `slice_11.png` for 12 slices).
+	/// # Parameters
+	/// * **dir** - the directory to save the images in (must already exist)
+	/// * **prefix** - the start of each file name
+	/// # Returns
+	/// Returns a *Result* containing the file paths in Z order, or an *ImageError* if a file
+	/// could not be written or the value type is not supported by the PNG format
+	pub fn save_png_stack(&self, dir: impl AsRef<Path>, prefix: &str) -> ImageResult<Vec<PathBuf>>
+		where V::Pixel: PixelWithColorType, [<V::Pixel as Pixel>::Subpixel]: EncodableLayout {
+		let images = self.to_image_stack();
+		let digits = format!("{}", images.len().saturating_sub(1)).len();
+		let mut paths = Vec::with_capacity(images.len());
+		for (z, image) in images.iter().enumerate() {
+			let path = dir.as_ref().join(format!("{}{:02$}.png", prefix, z, digits));
+			image.save_with_format(&path, ImageFormat::Png)?;
+			paths.push(path);
+		}
+		return Ok(paths);
+	}
+}
@@ -0,0 +1,1021 @@
+//! This module is used for storing unbounded N-dimensional grids, such as the terrain of an
+//! infinite procedural world. A grid has no fixed size: it is addressed with signed (`isize`)
+//! coordinates and it is stored as a map of Z-indexed data patches (called chunks), which are
+//! loaded as cells are written. *ZInfiniteGrid2D* and *ZInfiniteGrid3D* are front-ends for
+//! *ZInfiniteGridND*.
+//!
+//! A new chunk is filled with the fill value of the grid and then passed to the load hook (if
+//! one is set), which can generate or read its contents. Chunks that are no longer needed can be
+//! unloaded with *unload_outside(...)*, which passes each unloaded chunk to the unload hook (if
+//! one is set) so that it can be saved. Reading a cell never loads its chunk, so *get(...)*
+//! returns *None* for cells of chunks that are not loaded (use *load_region(...)* to load the
+//! chunks of a region before reading it).
+//! # Example Usage
+//! ```
+//! use zarray::infinite::ZInfiniteGrid2D;
+//! let mut terrain = ZInfiniteGrid2D::new(0isize).with_load_hook(|chunk| {
+//!   for item in chunk.iter_mut() {
+//!     *item.value = item.coord[0] * item.coord[1];
+//!   }
+//! });
+//! terrain.load_region(-20, -20, 20, 20);
+//! assert_eq!(terrain.get(-7, 3), Some(&-21));
+//! terrain.set(1000, -1000, 5);
+//! assert_eq!(terrain.bounds(), Some(((-24, -1000), (1008, 24))));
+//! assert_eq!(terrain.unload_outside(-100, -100, 100, 100), 1);
+//! assert_eq!(terrain.get(1000, -1000), None);
+//! ```
+
+use alloc::collections::btree_map::{BTreeMap, Range};
+use alloc::sync::Arc;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt::{Debug, Formatter};
+use core::ops::{Bound, RangeBounds};
+use core::slice::IterMut;
+use core::iter::Enumerate;
+use crate::MAX_RANK;
+use crate::znd::{is_empty_box, next_in_box, patch_coord, Patch, PatchShape, DEFAULT_PATCH_EDGE};
+
+/// Private type of the hook that is called when a chunk is loaded
+type LoadHook<T, const N: usize, const E: usize> = Arc<dyn Fn(&mut GridChunk<T, N, E>) + Send + Sync>;
+/// Private type of the hook that is called when a chunk is unloaded
+type UnloadHook<T, const N: usize, const E: usize> = Arc<dyn Fn(GridChunk<T, N, E>) + Send + Sync>;
+
+/// Utility function for converting a range of signed coordinates into (start inclusive, end
+/// exclusive) bounds, where unbounded ends are the limits of `isize`
+fn unbounded_range(range: &impl RangeBounds<isize>) -> (isize, isize) {
+	let start = match range.start_bound() {
+		Bound::Included(&s) => s,
+		Bound::Excluded(&s) => s.saturating_add(1),
+		Bound::Unbounded => isize::MIN
+	};
+	let end = match range.end_bound() {
+		Bound::Included(&e) => e.saturating_add(1),
+		Bound::Excluded(&e) => e,
+		Bound::Unbounded => isize::MAX
+	};
+	return (start, end.max(start));
+}
+
+/// A chunk of a *ZInfiniteGridND*, which is a data patch of E values in each of N dimensions
+/// (eg 8x8 in 2D or 8x8x8 in 3D), stored in Z-order. Chunks are passed to the load and unload
+/// hooks of the grid.
+#[derive(Debug, Clone)]
+pub struct GridChunk<T, const N: usize, const E: usize = DEFAULT_PATCH_EDGE> {
+	chunk_coord: [isize; N],
+	patch: Patch<T>
+}
+
+impl<T, const N: usize, const E: usize> GridChunk<T, N, E> {
+	/// Gets the coordinate of this chunk in the grid of chunks (ie the coordinate of its first
+	/// cell divided by the chunk length, rounding down)
+	/// # Returns
+	/// Returns the chunk coordinate
+	pub fn chunk_coord(&self) -> [isize; N] {
+		return self.chunk_coord;
+	}
+
+	/// Gets the coordinate of the first cell of this chunk (the corner with the lowest
+	/// coordinates)
+	/// # Returns
+	/// Returns the cell coordinate of the first cell
+	pub fn origin(&self) -> [isize; N] {
+		return self.chunk_coord.map(|c| c << PatchShape::<N, E>::BITS);
+	}
+
+	/// Gets the coordinate of the chunk that contains a cell
+	/// # Parameters
+	/// * **coord** - cell coordinate
+	/// # Returns
+	/// Returns the chunk coordinate
+	pub fn chunk_coord_of(coord: [isize; N]) -> [isize; N] {
+		return coord.map(|c| c >> PatchShape::<N, E>::BITS);
+	}
+
+	/// Checks whether a cell is in this chunk
+	/// # Parameters
+	/// * **coord** - cell coordinate
+	/// # Returns
+	/// Returns true if the cell is in this chunk
+	pub fn contains(&self, coord: [isize; N]) -> bool {
+		return Self::chunk_coord_of(coord) == self.chunk_coord;
+	}
+
+	/// Gets a value of this chunk, or *None* if the cell is not in this chunk
+	/// # Parameters
+	/// * **coord** - cell coordinate (in the coordinates of the grid)
+	/// # Returns
+	/// Returns an Option holding a reference to the value
+	pub fn get(&self, coord: [isize; N]) -> Option<&T> {
+		if !self.contains(coord) {
+			return None;
+		}
+		return Some(self.patch.get::<N, E>(&coord.map(|c| c as usize)));
+	}
+
+	/// Gets a mutable reference to a value of this chunk, or *None* if the cell is not in this
+	/// chunk
+	/// # Parameters
+	/// * **coord** - cell coordinate (in the coordinates of the grid)
+	/// # Returns
+	/// Returns an Option holding a mutable reference to the value
+	pub fn get_mut(&mut self, coord: [isize; N]) -> Option<&mut T> {
+		if !self.contains(coord) {
+			return None;
+		}
+		return Some(self.patch.get_mut::<N, E>(&coord.map(|c| c as usize)));
+	}
+
+	/// Iterates through the values of this chunk in Z-order
+	/// # Returns
+	/// Returns an iterator of *ZInfiniteGridNDIteratorItem* structs (coordinate and value)
+	pub fn iter(&self) -> ZInfiniteGridNDIterator<'_, T, N, E> {
+		return ZInfiniteGridNDIterator::new(None, Some(self), None, None);
+	}
+
+	/// Iterates through the values of this chunk in Z-order, allowing them to be changed
+	/// # Returns
+	/// Returns an iterator of *ZInfiniteGridNDIteratorItemMut* structs (coordinate and
+	/// mutable value)
+	pub fn iter_mut(&mut self) -> GridChunkIteratorMut<'_, T, N, E> {
+		let origin = self.origin().map(|c| c as usize);
+		return GridChunkIteratorMut { origin, contents: self.patch.contents_mut().iter_mut().enumerate() };
+	}
+
+	/// private function for getting the coordinate of the value at an index of the patch
+	fn cell_coord(origin: &[isize; N], index: usize) -> [isize; N] {
+		return patch_coord::<N, E>(&origin.map(|c| c as usize), index).map(|c| c as isize);
+	}
+}
+
+/// This is primary struct for unbounded z-indexed N-dimensional grids. Create new instances
+/// with ZInfiniteGridND::new(fill_value)
+///
+/// The optional parameter *E* is the length of the chunks in every dimension, which may
+/// be 4, 8 (the default), or 16 (see [ZArrayND](crate::znd::ZArrayND)). The load and unload
+/// hooks are shared by the clones of a grid.
+#[derive(Clone)]
+pub struct ZInfiniteGridND<T, const N: usize, const E: usize = DEFAULT_PATCH_EDGE> {
+	chunks: BTreeMap<[isize; N], GridChunk<T, N, E>>,
+	fill: T,
+	load_hook: Option<LoadHook<T, N, E>>,
+	unload_hook: Option<UnloadHook<T, N, E>>
+}
+
+impl<T, const N: usize, const E: usize> Debug for ZInfiniteGridND<T, N, E> where T: Debug {
+	fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+		return f.debug_struct("ZInfiniteGridND")
+			.field("chunks", &self.chunks)
+			.field("fill", &self.fill)
+			.field("load_hook", &self.load_hook.is_some())
+			.field("unload_hook", &self.unload_hook.is_some())
+			.finish();
+	}
+}
+
+impl<T, const N: usize, const E: usize> ZInfiniteGridND<T, N, E> {
+	/// compile-time check that the number of dimensions is supported
+	const RANK_CHECK: () = assert!(N > 0 && N <= MAX_RANK, "ZInfiniteGridND supports 1 to 4 dimensions");
+
+	/// Create an empty unbounded N-dimensional grid, which has no chunks loaded
+	/// # Parameters
+	/// * **fill** - the initial value of every cell of a newly loaded chunk (before the load
+	/// hook is called)
+	/// # Returns
+	/// Returns an initialized *ZInfiniteGridND* struct
+	pub fn new(fill: T) -> ZInfiniteGridND<T, N, E> {
+		#[allow(clippy::let_unit_value)]
+		let _ = Self::RANK_CHECK;
+		return ZInfiniteGridND { chunks: BTreeMap::new(), fill, load_hook: None, unload_hook: None };
+	}
+
+	/// Sets the load hook, which is called with every newly loaded chunk (already filled with
+	/// the fill value) so that it can generate or read the contents of the chunk
+	/// # Parameters
+	/// * **hook** - function that initializes a chunk
+	pub fn set_load_hook(&mut self, hook: impl Fn(&mut GridChunk<T, N, E>) + Send + Sync + 'static) {
+		self.load_hook = Some(Arc::new(hook));
+	}
+
+	/// Same as *set_load_hook(hook)*, but consumes and returns this grid so that it can be
+	/// chained with the constructor
+	/// # Parameters
+	/// * **hook** - function that initializes a chunk
+	/// # Returns
+	/// Returns this grid with the load hook
+	pub fn with_load_hook(mut self, hook: impl Fn(&mut GridChunk<T, N, E>) + Send + Sync + 'static) -> ZInfiniteGridND<T, N, E> {
+		self.set_load_hook(hook);
+		return self;
+	}
+
+	/// Sets the unload hook, which is called with every chunk that is unloaded so that it can
+	/// be saved
+	/// # Parameters
+	/// * **hook** - function that receives an unloaded chunk
+	pub fn set_unload_hook(&mut self, hook: impl Fn(GridChunk<T, N, E>) + Send + Sync + 'static) {
+		self.unload_hook = Some(Arc::new(hook));
+	}
+
+	/// Same as *set_unload_hook(hook)*, but consumes and returns this grid so that it can be
+	/// chained with the constructor
+	/// # Parameters
+	/// * **hook** - function that receives an unloaded chunk
+	/// # Returns
+	/// Returns this grid with the unload hook
+	pub fn with_unload_hook(mut self, hook: impl Fn(GridChunk<T, N, E>) + Send + Sync + 'static) -> ZInfiniteGridND<T, N, E> {
+		self.set_unload_hook(hook);
+		return self;
+	}
+
+	/// Gets the fill value, which is the initial value of every cell of a newly loaded chunk
+	/// # Returns
+	/// Returns a reference to the fill value
+	pub fn fill_value(&self) -> &T {
+		return &self.fill;
+	}
+
+	/// Gets the number of loaded chunks
+	/// # Returns
+	/// Returns the number of chunks
+	pub fn chunk_count(&self) -> usize {
+		return self.chunks.len();
+	}
+
+	/// Checks whether the chunk that contains a cell is loaded
+	/// # Parameters
+	/// * **coord** - cell coordinate
+	/// # Returns
+	/// Returns true if the chunk is loaded
+	pub fn is_loaded(&self, coord: [isize; N]) -> bool {
+		return self.chunks.contains_key(&GridChunk::<T, N, E>::chunk_coord_of(coord));
+	}
+
+	/// Gets a loaded chunk
+	/// # Parameters
+	/// * **chunk_coord** - coordinate of the chunk in the grid of chunks (see
+	/// *GridChunk::chunk_coord_of(coord)*)
+	/// # Returns
+	/// Returns an Option holding a reference to the chunk, or *None* if it is not loaded
+	pub fn chunk(&self, chunk_coord: [isize; N]) -> Option<&GridChunk<T, N, E>> {
+		return self.chunks.get(&chunk_coord);
+	}
+
+	/// Iterates through the loaded chunks, ordered by chunk coordinate
+	/// # Returns
+	/// Returns an iterator of references to the chunks
+	pub fn chunks(&self) -> impl Iterator<Item=&GridChunk<T, N, E>> {
+		return self.chunks.values();
+	}
+
+	/// Gets a value from the grid, or *None* if the chunk of the cell is not loaded
+	/// # Parameters
+	/// * **coord** - cell coordinate
+	/// # Returns
+	/// Returns an Option holding a reference to the value
+	pub fn get(&self, coord: [isize; N]) -> Option<&T> {
+		return self.chunks.get(&GridChunk::<T, N, E>::chunk_coord_of(coord))?.get(coord);
+	}
+
+	/// Gets the bounding box of the loaded chunks (at chunk resolution)
+	/// # Returns
+	/// Returns an Option holding the (inclusive) first corner and the (exclusive) second
+	/// corner of the box that contains every loaded cell, or *None* if no chunks are loaded
+	pub fn bounds(&self) -> Option<([isize; N], [isize; N])> {
+		let mut keys = self.chunks.keys();
+		let first = *keys.next()?;
+		let (mut min, mut max) = (first, first);
+		for key in keys {
+			for d in 0..N {
+				min[d] = min[d].min(key[d]);
+				max[d] = max[d].max(key[d]);
+			}
+		}
+		let shift = PatchShape::<N, E>::BITS;
+		return Some((min.map(|c| c << shift), max.map(|c| (c << shift).saturating_add(E as isize))));
+	}
+
+	/// Iterates through the values of all loaded chunks, one chunk at a time (in Z-order
+	/// within each chunk)
+	/// # Returns
+	/// Returns an iterator of *ZInfiniteGridNDIteratorItem* structs (coordinate and value)
+	pub fn iter(&self) -> ZInfiniteGridNDIterator<'_, T, N, E> {
+		return ZInfiniteGridNDIterator::new(Some(self.chunks.range::<[isize; N], _>(..)), None, None, None);
+	}
+
+	/// Iterates through the values in a box-shaped region of the grid, one chunk at a time
+	/// (in Z-order within each chunk), skipping the cells of chunks that are not loaded
+	/// # Parameters
+	/// * **start** - the first corner of the region (inclusive)
+	/// * **end** - the second corner of the region (exclusive)
+	/// # Returns
+	/// Returns an iterator of *ZInfiniteGridNDIteratorItem* structs (coordinate and value)
+	pub fn iter_region(&self, start: [isize; N], end: [isize; N]) -> ZInfiniteGridNDIterator<'_, T, N, E> {
+		let chunk_region = chunk_box::<N, E>(&start, &end);
+		let chunks = chunk_region.map(|(first, last)| {
+			// the chunks are ordered by their first coordinate, so only the chunks in the range
+			// of the region along the first axis are visited
+			let mut range_start = [isize::MIN; N];
+			let mut range_end = [isize::MAX; N];
+			range_start[0] = first[0];
+			range_end[0] = last[0];
+			self.chunks.range(range_start..=range_end)
+		});
+		return ZInfiniteGridNDIterator::new(chunks, None, Some((start, end)), chunk_region);
+	}
+
+	/// Unloads every chunk that does not overlap a box-shaped region, passing each of them to
+	/// the unload hook (if there is one)
+	/// # Parameters
+	/// * **start** - the first corner of the region (inclusive)
+	/// * **end** - the second corner of the region (exclusive)
+	/// # Returns
+	/// Returns the number of unloaded chunks
+	pub fn unload_outside(&mut self, start: [isize; N], end: [isize; N]) -> usize {
+		let keep = chunk_box::<N, E>(&start, &end);
+		let unloaded: Vec<[isize; N]> = self.chunks.keys()
+			.filter(|key| !keep.as_ref().is_some_and(|(first, last)| in_chunk_box(key, first, last)))
+			.copied().collect();
+		for key in &unloaded {
+			let chunk = self.chunks.remove(key).expect("key was just found");
+			if let Some(hook) = &self.unload_hook {
+				hook(chunk);
+			}
+		}
+		return unloaded.len();
+	}
+
+	/// Unloads every chunk, passing each of them to the unload hook (if there is one)
+	/// # Returns
+	/// Returns the number of unloaded chunks
+	pub fn unload_all(&mut self) -> usize {
+		let chunks = core::mem::take(&mut self.chunks);
+		let count = chunks.len();
+		for chunk in chunks.into_values() {
+			if let Some(hook) = &self.unload_hook {
+				hook(chunk);
+			}
+		}
+		return count;
+	}
+}
+
+impl<T, const N: usize, const E: usize> ZInfiniteGridND<T, N, E> where T: Clone {
+	/// private function for getting a chunk, loading it first if necessary
+	fn load_chunk(&mut self, chunk_coord: [isize; N]) -> &mut GridChunk<T, N, E> {
+		return self.chunks.entry(chunk_coord).or_insert_with(|| {
+			let contents = vec![self.fill.clone(); PatchShape::<N, E>::SIZE].into();
+			let mut chunk = GridChunk { chunk_coord, patch: Patch::new(contents) };
+			if let Some(hook) = &self.load_hook {
+				hook(&mut chunk);
+			}
+			chunk
+		});
+	}
+
+	/// Gets a mutable reference to a value in the grid, loading the chunk of the cell if it is
+	/// not already loaded
+	/// # Parameters
+	/// * **coord** - cell coordinate
+	/// # Returns
+	/// Returns a mutable reference to the value
+	pub fn get_mut(&mut self, coord: [isize; N]) -> &mut T {
+		return self.load_chunk(GridChunk::<T, N, E>::chunk_coord_of(coord)).get_mut(coord)
+			.expect("chunk contains the cell");
+	}
+
+	/// Sets a value in the grid, loading the chunk of the cell if it is not already loaded
+	/// (the load hook is called before the new value is stored)
+	/// # Parameters
+	/// * **coord** - cell coordinate
+	/// * **new_val** - value to store in the grid at the coordinate
+	pub fn set(&mut self, coord: [isize; N], new_val: T) {
+		*self.get_mut(coord) = new_val;
+	}
+
+	/// Loads every chunk that overlaps a box-shaped region (chunks that are already loaded are
+	/// left as they are)
+	/// # Parameters
+	/// * **start** - the first corner of the region (inclusive)
+	/// * **end** - the second corner of the region (exclusive)
+	/// # Returns
+	/// Returns the number of newly loaded chunks
+	pub fn load_region(&mut self, start: [isize; N], end: [isize; N]) -> usize {
+		let Some((first, last)) = chunk_box::<N, E>(&start, &end) else {
+			return 0;
+		};
+		let before = self.chunks.len();
+		let stop = last.map(|c| c + 1);
+		let mut chunk_coord = first;
+		loop {
+			self.load_chunk(chunk_coord);
+			if !next_in_box(&mut chunk_coord, &first, &stop) {
+				return self.chunks.len() - before;
+			}
+		}
+	}
+}
+
+/// function for getting the (inclusive) first and last chunk coordinates of the chunks that
+/// overlap a box of cells, or None if the box is empty
+fn chunk_box<const N: usize, const E: usize>(start: &[isize; N], end: &[isize; N]) -> Option<([isize; N], [isize; N])> {
+	if is_empty_box(start, end) {
+		return None;
+	}
+	let shift = PatchShape::<N, E>::BITS;
+	let mut first = [0; N];
+	let mut last = [0; N];
+	for d in 0..N {
+		first[d] = start[d] >> shift;
+		last[d] = (end[d] - 1) >> shift;
+	}
+	return Some((first, last));
+}
+
+/// function for checking whether a chunk coordinate is within the (inclusive) first and last
+/// chunk coordinates of a box
+fn in_chunk_box<const N: usize>(chunk_coord: &[isize; N], first: &[isize; N], last: &[isize; N]) -> bool {
+	return (0..N).all(|d| chunk_coord[d] >= first[d] && chunk_coord[d] <= last[d]);
+}
+
+/// This struct is used by `ZInfiniteGridNDIterator` to present values to the consumer of the
+/// iterator
+#[derive(Debug)]
+pub struct ZInfiniteGridNDIteratorItem<'a, T, const N: usize> {
+	/// coordinate of the value
+	pub coord: [isize; N],
+	/// reference to value at this coordinate
+	pub value: &'a T
+}
+
+/// Iterator that iterates through the cells of a grid or chunk, one chunk at a time
+pub struct ZInfiniteGridNDIterator<'a, T, const N: usize, const E: usize = DEFAULT_PATCH_EDGE> {
+	/// remaining chunks to iterate over
+	chunks: Option<Range<'a, [isize; N], GridChunk<T, N, E>>>,
+	/// current chunk
+	chunk: Option<&'a GridChunk<T, N, E>>,
+	/// coordinate of the first value of the current chunk
+	origin: [isize; N],
+	/// index of the next value in the current chunk
+	index: usize,
+	/// (inclusive, exclusive) corners of the region to iterate over, if any
+	region: Option<([isize; N], [isize; N])>,
+	/// (inclusive) first and last chunk coordinates of the chunks that overlap the region
+	chunk_region: Option<([isize; N], [isize; N])>
+}
+
+impl<'a, T, const N: usize, const E: usize> ZInfiniteGridNDIterator<'a, T, N, E> {
+	/// private constructor
+	fn new(chunks: Option<Range<'a, [isize; N], GridChunk<T, N, E>>>, chunk: Option<&'a GridChunk<T, N, E>>,
+		   region: Option<([isize; N], [isize; N])>, chunk_region: Option<([isize; N], [isize; N])>)
+		   -> ZInfiniteGridNDIterator<'a, T, N, E> {
+		let origin = chunk.map(|c| c.origin()).unwrap_or([0; N]);
+		return ZInfiniteGridNDIterator { chunks, chunk, origin, index: 0, region, chunk_region };
+	}
+
+	/// private function for checking whether a chunk overlaps the region (if any)
+	fn overlaps(&self, chunk_coord: &[isize; N]) -> bool {
+		return match &self.chunk_region {
+			Some((first, last)) => in_chunk_box(chunk_coord, first, last),
+			None => self.region.is_none()
+		};
+	}
+}
+
+impl<'a, T, const N: usize, const E: usize> Iterator for ZInfiniteGridNDIterator<'a, T, N, E> {
+	type Item = ZInfiniteGridNDIteratorItem<'a, T, N>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		loop {
+			match self.chunk {
+				Some(chunk) if self.index < PatchShape::<N, E>::SIZE => {
+					let index = self.index;
+					self.index += 1;
+					let coord = GridChunk::<T, N, E>::cell_coord(&self.origin, index);
+					let in_region = match &self.region {
+						Some((start, end)) => (0..N).all(|d| coord[d] >= start[d] && coord[d] < end[d]),
+						None => true
+					};
+					if in_region {
+						return Some(ZInfiniteGridNDIteratorItem { coord, value: &chunk.patch.contents()[index] });
+					}
+				},
+				_ => {
+					// move on to the next chunk (or finish if there are no more chunks)
+					let (chunk_coord, chunk) = self.chunks.as_mut()?.next()?;
+					if self.overlaps(chunk_coord) {
+						self.chunk = Some(chunk);
+						self.origin = chunk.origin();
+						self.index = 0;
+					}
+				}
+			}
+		}
+	}
+}
+
+/// This struct is used by `GridChunkIteratorMut` to present mutable values to the consumer of
+/// the iterator
+#[derive(Debug)]
+pub struct ZInfiniteGridNDIteratorItemMut<'a, T, const N: usize> {
+	/// coordinate of the value
+	pub coord: [isize; N],
+	/// mutable reference to value at this coordinate
+	pub value: &'a mut T
+}
+
+/// Iterator that iterates through the values of a chunk in Z-order, allowing them to be
+/// changed
+pub struct GridChunkIteratorMut<'a, T, const N: usize, const E: usize = DEFAULT_PATCH_EDGE> {
+	origin: [usize; N],
+	contents: Enumerate<IterMut<'a, T>>
+}
+
+impl<'a, T, const N: usize, const E: usize> Iterator for GridChunkIteratorMut<'a, T, N, E> {
+	type Item = ZInfiniteGridNDIteratorItemMut<'a, T, N>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		let (index, value) = self.contents.next()?;
+		let coord = patch_coord::<N, E>(&self.origin, index).map(|c| c as isize);
+		return Some(ZInfiniteGridNDIteratorItemMut { coord, value });
+	}
+}
+
+/// This is primary struct for unbounded z-indexed 2D grids. Create new instances with
+/// ZInfiniteGrid2D::new(fill_value)
+///
+/// *ZInfiniteGrid2D* is a typed front-end for a 2-dimensional [ZInfiniteGridND], and it can be
+/// converted to and from a *ZInfiniteGridND<T, 2, E>* with `From`/`Into`.
+#[derive(Debug, Clone)]
+pub struct ZInfiniteGrid2D<T, const E: usize = DEFAULT_PATCH_EDGE> {
+	grid: ZInfiniteGridND<T, 2, E>
+}
+
+impl<T, const E: usize> From<ZInfiniteGridND<T, 2, E>> for ZInfiniteGrid2D<T, E> {
+	fn from(grid: ZInfiniteGridND<T, 2, E>) -> Self {
+		return ZInfiniteGrid2D { grid };
+	}
+}
+
+impl<T, const E: usize> From<ZInfiniteGrid2D<T, E>> for ZInfiniteGridND<T, 2, E> {
+	fn from(grid: ZInfiniteGrid2D<T, E>) -> Self {
+		return grid.grid;
+	}
+}
+
+impl<T> ZInfiniteGrid2D<T> {
+	/// Create an empty unbounded 2D grid, which has no chunks loaded
+	/// # Parameters
+	/// * **fill** - the initial value of every cell of a newly loaded chunk (before the load
+	/// hook is called)
+	/// # Returns
+	/// Returns an initialized *ZInfiniteGrid2D* struct
+	pub fn new(fill: T) -> ZInfiniteGrid2D<T> {
+		return ZInfiniteGrid2D::new_with_fill(fill);
+	}
+}
+
+impl<T, const E: usize> ZInfiniteGrid2D<T, E> {
+	/// Create an empty unbounded 2D grid with chunks of any length (*new(fill)* uses the
+	/// default chunk length), eg `ZInfiniteGrid2D::<u8, 16>::new_with_fill(0)`
+	/// # Parameters
+	/// * **fill** - the initial value of every cell of a newly loaded chunk (before the load
+	/// hook is called)
+	/// # Returns
+	/// Returns an initialized *ZInfiniteGrid2D* struct
+	pub fn new_with_fill(fill: T) -> ZInfiniteGrid2D<T, E> {
+		return ZInfiniteGrid2D { grid: ZInfiniteGridND::new(fill) };
+	}
+}
+
+impl<T, const E: usize> ZInfiniteGrid2D<T, E> {
+	/// Gets the 2-dimensional *ZInfiniteGridND* that holds the data of this 2D grid
+	/// # Returns
+	/// Returns a reference to the *ZInfiniteGridND*, where the first dimension is X
+	pub fn as_nd(&self) -> &ZInfiniteGridND<T, 2, E> {
+		return &self.grid;
+	}
+
+	/// Sets the load hook, which is called with every newly loaded chunk (already filled with
+	/// the fill value) so that it can generate or read the contents of the chunk
+	/// # Parameters
+	/// * **hook** - function that initializes a chunk (with `[x, y]` coordinates)
+	pub fn set_load_hook(&mut self, hook: impl Fn(&mut GridChunk<T, 2, E>) + Send + Sync + 'static) {
+		self.grid.set_load_hook(hook);
+	}
+
+	/// Same as *set_load_hook(hook)*, but consumes and returns this grid so that it can be
+	/// chained with the constructor
+	/// # Parameters
+	/// * **hook** - function that initializes a chunk (with `[x, y]` coordinates)
+	/// # Returns
+	/// Returns this grid with the load hook
+	pub fn with_load_hook(mut self, hook: impl Fn(&mut GridChunk<T, 2, E>) + Send + Sync + 'static) -> ZInfiniteGrid2D<T, E> {
+		self.set_load_hook(hook);
+		return self;
+	}
+
+	/// Sets the unload hook, which is called with every chunk that is unloaded so that it can
+	/// be saved
+	/// # Parameters
+	/// * **hook** - function that receives an unloaded chunk
+	pub fn set_unload_hook(&mut self, hook: impl Fn(GridChunk<T, 2, E>) + Send + Sync + 'static) {
+		self.grid.set_unload_hook(hook);
+	}
+
+	/// Same as *set_unload_hook(hook)*, but consumes and returns this grid so that it can be
+	/// chained with the constructor
+	/// # Parameters
+	/// * **hook** - function that receives an unloaded chunk
+	/// # Returns
+	/// Returns this grid with the unload hook
+	pub fn with_unload_hook(mut self, hook: impl Fn(GridChunk<T, 2, E>) + Send + Sync + 'static) -> ZInfiniteGrid2D<T, E> {
+		self.set_unload_hook(hook);
+		return self;
+	}
+
+	/// Gets the number of loaded chunks
+	/// # Returns
+	/// Returns the number of chunks
+	pub fn chunk_count(&self) -> usize {
+		return self.grid.chunk_count();
+	}
+
+	/// Checks whether the chunk that contains a cell is loaded
+	/// # Parameters
+	/// * **x** - x dimension coordinate
+	/// * **y** - y dimension coordinate
+	/// # Returns
+	/// Returns true if the chunk is loaded
+	pub fn is_loaded(&self, x: isize, y: isize) -> bool {
+		return self.grid.is_loaded([x, y]);
+	}
+
+	/// Gets a value from the 2D grid, or *None* if the chunk of the cell is not loaded
+	/// # Parameters
+	/// * **x** - x dimension coordinate
+	/// * **y** - y dimension coordinate
+	/// # Returns
+	/// Returns an Option holding a reference to the value
+	pub fn get(&self, x: isize, y: isize) -> Option<&T> {
+		return self.grid.get([x, y]);
+	}
+
+	/// Gets the bounding box of the loaded chunks (at chunk resolution)
+	/// # Returns
+	/// Returns an Option holding the (inclusive) first corner (x1, y1) and the (exclusive)
+	/// second corner (x2, y2) of the rectangle that contains every loaded cell, or *None* if
+	/// no chunks are loaded
+	pub fn bounds(&self) -> Option<((isize, isize), (isize, isize))> {
+		let ([x1, y1], [x2, y2]) = self.grid.bounds()?;
+		return Some(((x1, y1), (x2, y2)));
+	}
+
+	/// Iterates through the values of all loaded chunks, one chunk at a time
+	/// # Returns
+	/// Returns an iterator of *ZInfiniteGrid2DIteratorItem* structs (x, y, and value)
+	pub fn iter(&self) -> ZInfiniteGrid2DIterator<'_, T, E> {
+		return ZInfiniteGrid2DIterator { iter: self.grid.iter() };
+	}
+
+	/// Iterates through the values in a rectangular area of the 2D grid, one chunk at a time,
+	/// skipping the cells of chunks that are not loaded
+	/// # Parameters
+	/// * **x_range** - range of x dimension coordinates (eg `-4..4`)
+	/// * **y_range** - range of y dimension coordinates (eg `..`)
+	/// # Returns
+	/// Returns an iterator of *ZInfiniteGrid2DIteratorItem* structs (x, y, and value)
+	pub fn iter_region(&self, x_range: impl RangeBounds<isize>, y_range: impl RangeBounds<isize>)
+		-> ZInfiniteGrid2DIterator<'_, T, E> {
+		let (x1, x2) = unbounded_range(&x_range);
+		let (y1, y2) = unbounded_range(&y_range);
+		return ZInfiniteGrid2DIterator { iter: self.grid.iter_region([x1, y1], [x2, y2]) };
+	}
+
+	/// Unloads every chunk that does not overlap a rectangular area, passing each of them to
+	/// the unload hook (if there is one)
+	/// # Parameters
+	/// * **x1** - the first x dimension coordinate (inclusive)
+	/// * **y1** - the first y dimension coordinate (inclusive)
+	/// * **x2** - the second x dimension coordinate (exclusive)
+	/// * **y2** - the second y dimension coordinate (exclusive)
+	/// # Returns
+	/// Returns the number of unloaded chunks
+	pub fn unload_outside(&mut self, x1: isize, y1: isize, x2: isize, y2: isize) -> usize {
+		return self.grid.unload_outside([x1, y1], [x2, y2]);
+	}
+
+	/// Unloads every chunk, passing each of them to the unload hook (if there is one)
+	/// # Returns
+	/// Returns the number of unloaded chunks
+	pub fn unload_all(&mut self) -> usize {
+		return self.grid.unload_all();
+	}
+}
+
+impl<T, const E: usize> ZInfiniteGrid2D<T, E> where T: Clone {
+	/// Gets a mutable reference to a value in the 2D grid, loading the chunk of the cell if it
+	/// is not already loaded
+	/// # Parameters
+	/// * **x** - x dimension coordinate
+	/// * **y** - y dimension coordinate
+	/// # Returns
+	/// Returns a mutable reference to the value
+	pub fn get_mut(&mut self, x: isize, y: isize) -> &mut T {
+		return self.grid.get_mut([x, y]);
+	}
+
+	/// Sets a value in the 2D grid, loading the chunk of the cell if it is not already loaded
+	/// # Parameters
+	/// * **x** - x dimension coordinate
+	/// * **y** - y dimension coordinate
+	/// * **new_val** - value to store in the 2D grid at (x, y)
+	pub fn set(&mut self, x: isize, y: isize, new_val: T) {
+		self.grid.set([x, y], new_val);
+	}
+
+	/// Loads every chunk that overlaps a rectangular area (chunks that are already loaded are
+	/// left as they are)
+	/// # Parameters
+	/// * **x1** - the first x dimension coordinate (inclusive)
+	/// * **y1** - the first y dimension coordinate (inclusive)
+	/// * **x2** - the second x dimension coordinate (exclusive)
+	/// * **y2** - the second y dimension coordinate (exclusive)
+	/// # Returns
+	/// Returns the number of newly loaded chunks
+	pub fn load_region(&mut self, x1: isize, y1: isize, x2: isize, y2: isize) -> usize {
+		return self.grid.load_region([x1, y1], [x2, y2]);
+	}
+}
+
+/// This struct is used by `ZInfiniteGrid2DIterator` to present values to the consumer of the
+/// iterator
+#[derive(Debug)]
+pub struct ZInfiniteGrid2DIteratorItem<'a, T> {
+	/// x-dimension coordinate
+	pub x: isize,
+	/// y-dimension coordinate
+	pub y: isize,
+	/// reference to value at this coordinate
+	pub value: &'a T
+}
+
+/// Iterator that iterates through the cells of a 2D grid, one chunk at a time
+pub struct ZInfiniteGrid2DIterator<'a, T, const E: usize = DEFAULT_PATCH_EDGE> {
+	iter: ZInfiniteGridNDIterator<'a, T, 2, E>
+}
+
+impl<'a, T, const E: usize> Iterator for ZInfiniteGrid2DIterator<'a, T, E> {
+	type Item = ZInfiniteGrid2DIteratorItem<'a, T>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		let item = self.iter.next()?;
+		Some(ZInfiniteGrid2DIteratorItem{x: item.coord[0], y: item.coord[1], value: item.value})
+	}
+}
+
+/// This is primary struct for unbounded z-indexed 3D grids. Create new instances with
+/// ZInfiniteGrid3D::new(fill_value)
+///
+/// *ZInfiniteGrid3D* is a typed front-end for a 3-dimensional [ZInfiniteGridND], and it can be
+/// converted to and from a *ZInfiniteGridND<T, 3, E>* with `From`/`Into`.
+#[derive(Debug, Clone)]
+pub struct ZInfiniteGrid3D<T, const E: usize = DEFAULT_PATCH_EDGE> {
+	grid: ZInfiniteGridND<T, 3, E>
+}
+
+impl<T, const E: usize> From<ZInfiniteGridND<T, 3, E>> for ZInfiniteGrid3D<T, E> {
+	fn from(grid: ZInfiniteGridND<T, 3, E>) -> Self {
+		return ZInfiniteGrid3D { grid };
+	}
+}
+
+impl<T, const E: usize> From<ZInfiniteGrid3D<T, E>> for ZInfiniteGridND<T, 3, E> {
+	fn from(grid: ZInfiniteGrid3D<T, E>) -> Self {
+		return grid.grid;
+	}
+}
+
+impl<T> ZInfiniteGrid3D<T> {
+	/// Create an empty unbounded 3D grid, which has no chunks loaded
+	/// # Parameters
+	/// * **fill** - the initial value of every cell of a newly loaded chunk (before the load
+	/// hook is called)
+	/// # Returns
+	/// Returns an initialized *ZInfiniteGrid3D* struct
+	pub fn new(fill: T) -> ZInfiniteGrid3D<T> {
+		return ZInfiniteGrid3D::new_with_fill(fill);
+	}
+}
+
+impl<T, const E: usize> ZInfiniteGrid3D<T, E> {
+	/// Create an empty unbounded 3D grid with chunks of any length (*new(fill)* uses the
+	/// default chunk length), eg `ZInfiniteGrid3D::<u8, 16>::new_with_fill(0)`
+	/// # Parameters
+	/// * **fill** - the initial value of every cell of a newly loaded chunk (before the load
+	/// hook is called)
+	/// # Returns
+	/// Returns an initialized *ZInfiniteGrid3D* struct
+	pub fn new_with_fill(fill: T) -> ZInfiniteGrid3D<T, E> {
+		return ZInfiniteGrid3D { grid: ZInfiniteGridND::new(fill) };
+	}
+}
+
+impl<T, const E: usize> ZInfiniteGrid3D<T, E> {
+	/// Gets the 3-dimensional *ZInfiniteGridND* that holds the data of this 3D grid
+	/// # Returns
+	/// Returns a reference to the *ZInfiniteGridND*, where the dimensions are X, Y, Z
+	pub fn as_nd(&self) -> &ZInfiniteGridND<T, 3, E> {
+		return &self.grid;
+	}
+
+	/// Sets the load hook, which is called with every newly loaded chunk (already filled with
+	/// the fill value) so that it can generate or read the contents of the chunk
+	/// # Parameters
+	/// * **hook** - function that initializes a chunk (with `[x, y, z]` coordinates)
+	pub fn set_load_hook(&mut self, hook: impl Fn(&mut GridChunk<T, 3, E>) + Send + Sync + 'static) {
+		self.grid.set_load_hook(hook);
+	}
+
+	/// Same as *set_load_hook(hook)*, but consumes and returns this grid so that it can be
+	/// chained with the constructor
+	/// # Parameters
+	/// * **hook** - function that initializes a chunk (with `[x, y, z]` coordinates)
+	/// # Returns
+	/// Returns this grid with the load hook
+	pub fn with_load_hook(mut self, hook: impl Fn(&mut GridChunk<T, 3, E>) + Send + Sync + 'static) -> ZInfiniteGrid3D<T, E> {
+		self.set_load_hook(hook);
+		return self;
+	}
+
+	/// Sets the unload hook, which is called with every chunk that is unloaded so that it can
+	/// be saved
+	/// # Parameters
+	/// * **hook** - function that receives an unloaded chunk
+	pub fn set_unload_hook(&mut self, hook: impl Fn(GridChunk<T, 3, E>) + Send + Sync + 'static) {
+		self.grid.set_unload_hook(hook);
+	}
+
+	/// Same as *set_unload_hook(hook)*, but consumes and returns this grid so that it can be
+	/// chained with the constructor
+	/// # Parameters
+	/// * **hook** - function that receives an unloaded chunk
+	/// # Returns
+	/// Returns this grid with the unload hook
+	pub fn with_unload_hook(mut self, hook: impl Fn(GridChunk<T, 3, E>) + Send + Sync + 'static) -> ZInfiniteGrid3D<T, E> {
+		self.set_unload_hook(hook);
+		return self;
+	}
+
+	/// Gets the number of loaded chunks
+	/// # Returns
+	/// Returns the number of chunks
+	pub fn chunk_count(&self) -> usize {
+		return self.grid.chunk_count();
+	}
+
+	/// Checks whether the chunk that contains a cell is loaded
+	/// # Parameters
+	/// * **x** - x dimension coordinate
+	/// * **y** - y dimension coordinate
+	/// * **z** - z dimension coordinate
+	/// # Returns
+	/// Returns true if the chunk is loaded
+	pub fn is_loaded(&self, x: isize, y: isize, z: isize) -> bool {
+		return self.grid.is_loaded([x, y, z]);
+	}
+
+	/// Gets a value from the 3D grid, or *None* if the chunk of the cell is not loaded
+	/// # Parameters
+	/// * **x** - x dimension coordinate
+	/// * **y** - y dimension coordinate
+	/// * **z** - z dimension coordinate
+	/// # Returns
+	/// Returns an Option holding a reference to the value
+	pub fn get(&self, x: isize, y: isize, z: isize) -> Option<&T> {
+		return self.grid.get([x, y, z]);
+	}
+
+	/// Gets the bounding box of the loaded chunks (at chunk resolution)
+	/// # Returns
+	/// Returns an Option holding the (inclusive) first corner (x1, y1, z1) and the
+	/// (exclusive) second corner (x2, y2, z2) of the box that contains every loaded cell, or
+	/// *None* if no chunks are loaded
+	#[allow(clippy::type_complexity)]
+	pub fn bounds(&self) -> Option<((isize, isize, isize), (isize, isize, isize))> {
+		let ([x1, y1, z1], [x2, y2, z2]) = self.grid.bounds()?;
+		return Some(((x1, y1, z1), (x2, y2, z2)));
+	}
+
+	/// Iterates through the values of all loaded chunks, one chunk at a time
+	/// # Returns
+	/// Returns an iterator of *ZInfiniteGrid3DIteratorItem* structs (x, y, z, and value)
+	pub fn iter(&self) -> ZInfiniteGrid3DIterator<'_, T, E> {
+		return ZInfiniteGrid3DIterator { iter: self.grid.iter() };
+	}
+
+	/// Iterates through the values in a box-shaped region of the 3D grid, one chunk at a time,
+	/// skipping the cells of chunks that are not loaded
+	/// # Parameters
+	/// * **x_range** - range of x dimension coordinates (eg `-4..4`)
+	/// * **y_range** - range of y dimension coordinates (eg `..`)
+	/// * **z_range** - range of z dimension coordinates (eg `0..=10`)
+	/// # Returns
+	/// Returns an iterator of *ZInfiniteGrid3DIteratorItem* structs (x, y, z, and value)
+	pub fn iter_region(&self, x_range: impl RangeBounds<isize>, y_range: impl RangeBounds<isize>,
+					   z_range: impl RangeBounds<isize>) -> ZInfiniteGrid3DIterator<'_, T, E> {
+		let (x1, x2) = unbounded_range(&x_range);
+		let (y1, y2) = unbounded_range(&y_range);
+		let (z1, z2) = unbounded_range(&z_range);
+		return ZInfiniteGrid3DIterator { iter: self.grid.iter_region([x1, y1, z1], [x2, y2, z2]) };
+	}
+
+	/// Unloads every chunk that does not overlap a box-shaped region, passing each of them to
+	/// the unload hook (if there is one)
+	/// # Parameters
+	/// * **x1** - the first x dimension coordinate (inclusive)
+	/// * **y1** - the first y dimension coordinate (inclusive)
+	/// * **z1** - the first z dimension coordinate (inclusive)
+	/// * **x2** - the second x dimension coordinate (exclusive)
+	/// * **y2** - the second y dimension coordinate (exclusive)
+	/// * **z2** - the second z dimension coordinate (exclusive)
+	/// # Returns
+	/// Returns the number of unloaded chunks
+	pub fn unload_outside(&mut self, x1: isize, y1: isize, z1: isize, x2: isize, y2: isize, z2: isize) -> usize {
+		return self.grid.unload_outside([x1, y1, z1], [x2, y2, z2]);
+	}
+
+	/// Unloads every chunk, passing each of them to the unload hook (if there is one)
+	/// # Returns
+	/// Returns the number of unloaded chunks
+	pub fn unload_all(&mut self) -> usize {
+		return self.grid.unload_all();
+	}
+}
+
+impl<T, const E: usize> ZInfiniteGrid3D<T, E> where T: Clone {
+	/// Gets a mutable reference to a value in the 3D grid, loading the chunk of the cell if it
+	/// is not already loaded
+	/// # Parameters
+	/// * **x** - x dimension coordinate
+	/// * **y** - y dimension coordinate
+	/// * **z** - z dimension coordinate
+	/// # Returns
+	/// Returns a mutable reference to the value
+	pub fn get_mut(&mut self, x: isize, y: isize, z: isize) -> &mut T {
+		return self.grid.get_mut([x, y, z]);
+	}
+
+	/// Sets a value in the 3D grid, loading the chunk of the cell if it is not already loaded
+	/// # Parameters
+	/// * **x** - x dimension coordinate
+	/// * **y** - y dimension coordinate
+	/// * **z** - z dimension coordinate
+	/// * **new_val** - value to store in the 3D grid at (x, y, z)
+	pub fn set(&mut self, x: isize, y: isize, z: isize, new_val: T) {
+		self.grid.set([x, y, z], new_val);
+	}
+
+	/// Loads every chunk that overlaps a box-shaped region (chunks that are already loaded are
+	/// left as they are)
+	/// # Parameters
+	/// * **x1** - the first x dimension coordinate (inclusive)
+	/// * **y1** - the first y dimension coordinate (inclusive)
+	/// * **z1** - the first z dimension coordinate (inclusive)
+	/// * **x2** - the second x dimension coordinate (exclusive)
+	/// * **y2** - the second y dimension coordinate (exclusive)
+	/// * **z2** - the second z dimension coordinate (exclusive)
+	/// # Returns
+	/// Returns the number of newly loaded chunks
+	pub fn load_region(&mut self, x1: isize, y1: isize, z1: isize, x2: isize, y2: isize, z2: isize) -> usize {
+		return self.grid.load_region([x1, y1, z1], [x2, y2, z2]);
+	}
+}
+
+/// This struct is used by `ZInfiniteGrid3DIterator` to present values to the consumer of the
+/// iterator
+#[derive(Debug)]
+pub struct ZInfiniteGrid3DIteratorItem<'a, T> {
+	/// x-dimension coordinate
+	pub x: isize,
+	/// y-dimension coordinate
+	pub y: isize,
+	/// z-dimension coordinate
+	pub z: isize,
+	/// reference to value at this coordinate
+	pub value: &'a T
+}
+
+/// Iterator that iterates through the cells of a 3D grid, one chunk at a time
+pub struct ZInfiniteGrid3DIterator<'a, T, const E: usize = DEFAULT_PATCH_EDGE> {
+	iter: ZInfiniteGridNDIterator<'a, T, 3, E>
+}
+
+impl<'a, T, const E: usize> Iterator for ZInfiniteGrid3DIterator<'a, T, E> {
+	type Item = ZInfiniteGrid3DIteratorItem<'a, T>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		let item = self.iter.next()?;
+		Some(ZInfiniteGrid3DIteratorItem{x: item.coord[0], y: item.coord[1], z: item.coord[2], value: item.value})
+	}
+}
@@ -23,54 +23,314 @@ SOFTWARE.
  */
 
  #![ doc = include_str!("../README.md")]
+// the standard library is only needed for the default "std" feature (and for unit tests)
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
 
 pub mod z2d;
 pub mod z3d;
+pub mod znd;
+pub mod automaton;
+pub mod sparse;
+pub mod infinite;
+pub mod history;
+#[cfg(feature = "serde")]
+pub mod serialization;
+#[cfg(feature = "ndarray")]
+pub mod ndarray_interop;
+#[cfg(feature = "image")]
+pub mod image_interop;
 
 use core::error::Error;
 use core::fmt::{Debug, Display, Formatter};
+use core::ops::{Bound, RangeBounds};
+
+/// highest number of dimensions supported by the Z-arrays
+pub(crate) const MAX_RANK: usize = 4;
+
+/// This struct holds a coordinate (or size) of up to 4 dimensions for error reporting, without
+/// needing heap allocation. It can be viewed as a slice with *as_slice()* and implements the
+/// Debug and Display traits.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ErrorCoord<C> {
+	/// coordinate values (only the first *rank* values are used)
+	values: [C; MAX_RANK],
+	/// number of dimensions of the coordinate
+	rank: usize,
+}
+
+impl<C: Copy + Default> ErrorCoord<C> {
+	/// Creates a new *ErrorCoord* from a slice of up to 4 values (any further values are
+	/// left out)
+	pub(crate) fn new(coord: &[C]) -> ErrorCoord<C> {
+		let mut values = [C::default(); MAX_RANK];
+		let rank = coord.len().min(MAX_RANK);
+		values[..rank].copy_from_slice(&coord[..rank]);
+		return ErrorCoord { values, rank };
+	}
+}
+
+impl<C> ErrorCoord<C> {
+	/// Gets the values of this coordinate
+	/// # Returns
+	/// Returns a slice with one value for each dimension
+	pub fn as_slice(&self) -> &[C] {
+		return &self.values[..self.rank];
+	}
+}
+
+impl<C: Display> Debug for ErrorCoord<C> {
+	fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+		return Display::fmt(self, f);
+	}
+}
+
+impl<C: Display> Display for ErrorCoord<C> {
+	// formats the coordinate as a tuple, eg (1, 2, 3)
+	fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+		write!(f, "(")?;
+		for (i, c) in self.as_slice().iter().enumerate() {
+			if i > 0 {
+				write!(f, ", ")?;
+			}
+			write!(f, "{}", c)?;
+		}
+		return write!(f, ")");
+	}
+}
 
 /// This struct is an error type that is returned when attempting to get a value that is outside
 /// the range of the data. It implements the Debug and Display traits so that it can be easily
-/// printed as an error message.
+/// printed as an error message. It does not allocate any heap memory, so it can be built in
+/// `no_std` environments without an allocator failure path.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
 pub struct LookUpError{
 	/// coordinate that was out of bounds
-	coord: Vec<usize>,
+	coord: ErrorCoord<isize>,
 	/// bounds of the ZArray*D that was violated
-	bounds: Vec<usize>,
+	bounds: ErrorCoord<usize>,
 }
 
 impl Debug for LookUpError {
 	// programmer-facing error message
-	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-		return write!(f, "{{ file: {}, line: {}, coord: {}, bounds: {} }}", file!(), line!(), vec_to_string(&self.coord), vec_to_string(&self.bounds));
+	fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+		return write!(f, "{{ file: {}, line: {}, coord: {}, bounds: {} }}", file!(), line!(), self.coord, self.bounds);
 	}
 }
 
 impl Display for LookUpError {
 	// user-facing error message
-	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-		return write!(f, "Error: could not access coordinate {} because it is out of range for size {}", vec_to_string(&self.coord), vec_to_string(&self.bounds));
+	fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+		return write!(f, "Error: could not access coordinate {} because it is out of range for size {}", self.coord, self.bounds);
 	}
 }
 
 impl Error for LookUpError{}
 
 impl LookUpError {
+	/// Creates a new *LookUpError*
+	/// # Parameters
+	/// * **coord** - coordinate that was out of bounds
+	/// * **bounds** - size of the array in each dimension
+	pub(crate) fn new(coord: &[isize], bounds: &[usize]) -> LookUpError {
+		return LookUpError { coord: ErrorCoord::new(coord), bounds: ErrorCoord::new(bounds) };
+	}
+
+	/// Gets the coordinate that was out of bounds
+	/// # Returns
+	/// Returns a slice with one value for each dimension
+	pub fn coord(&self) -> &[isize] {
+		return self.coord.as_slice();
+	}
+
+	/// Gets the size of the array that the coordinate was out of bounds for
+	/// # Returns
+	/// Returns a slice with one value for each dimension
+	pub fn bounds(&self) -> &[usize] {
+		return self.bounds.as_slice();
+	}
+}
+
+/// This enum is an error type that is returned when a ZArray*D cannot be built from the
+/// values provided to it (for example, from an iterator). It implements the Debug and Display
+/// traits so that it can be easily printed as an error message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BuildError {
+	/// The number of provided values does not match the number of cells of the ZArray*D
+	SizeMismatch {
+		/// number of values required by the dimensions of the ZArray*D
+		expected: usize,
+		/// number of values that were provided
+		actual: usize
+	},
+	/// A provided coordinate was out of bounds
+	OutOfBounds(LookUpError),
+	/// A value was provided more than once for the same coordinate
+	DuplicateCoord(ErrorCoord<usize>),
+	/// A nested list of values (eg a *Vec<Vec<T>>*) was ragged: the list at the given indices
+	/// (eg the y index of a row) does not have the same length as the first one
+	Ragged {
+		/// indices of the ragged list in the nested lists (innermost first)
+		index: ErrorCoord<usize>,
+		/// length of the first list at the same depth
+		expected: usize,
+		/// length of the ragged list
+		actual: usize
+	},
+	/// The provided array does not have the required dimensions
+	DimensionMismatch {
+		/// dimensions that were required
+		expected: ErrorCoord<usize>,
+		/// dimensions of the provided array
+		actual: ErrorCoord<usize>
+	},
+}
+
+impl Display for BuildError {
+	// user-facing error message
+	fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+		return match self {
+			BuildError::SizeMismatch { expected, actual } => write!(f, "Error: expected {} values but {} were provided", expected, actual),
+			BuildError::OutOfBounds(e) => Display::fmt(e, f),
+			BuildError::DuplicateCoord(coord) => write!(f, "Error: coordinate {} was provided more than once", coord),
+			BuildError::Ragged { index, expected, actual } => write!(f, "Error: ragged input, the list at {} has {} values but {} were expected", index, actual, expected),
+			BuildError::DimensionMismatch { expected, actual } => write!(f, "Error: expected dimensions {} but got {}", expected, actual),
+		};
+	}
+}
+
+impl Error for BuildError{}
+
+impl From<LookUpError> for BuildError {
+	fn from(e: LookUpError) -> Self {
+		BuildError::OutOfBounds(e)
+	}
+}
 
+/// This enum selects how coordinates that are outside of the bounds of a ZArray*D are handled
+/// by methods that look up values around a given coordinate, such as *sample(...)* and
+/// *neighbors(...)*. The edge mode only applies to the bounded axes of the array, as wrapped
+/// and clamped axes are always handled by the topology of the array (see *AxisTopology*).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EdgeMode<T> {
+	/// Out-of-bounds coordinates cannot be accessed (*sample(...)* returns a *LookUpError*
+	/// and *neighbors(...)* leaves them out, like *bounded_get(...)*)
+	Error,
+	/// Out-of-bounds coordinates read as the given value (writes to them are ignored)
+	Default(T),
+	/// Out-of-bounds coordinates wrap around to the other side of the array (like
+	/// *wrapped_get(...)*), eg -1 becomes size-1
+	Wrap,
+	/// Out-of-bounds coordinates are clamped to the nearest edge of the array, eg -2 becomes 0
+	Clamp,
+	/// Out-of-bounds coordinates are mirrored back into the array, repeating the edge value,
+	/// eg -1 becomes 0 and -2 becomes 1
+	Mirror,
+	/// Out-of-bounds coordinates are reflected back into the array without repeating the
+	/// edge value, eg -1 becomes 1 and -2 becomes 2
+	Reflect,
 }
-/// Utility function for converting Vecs to Strings for the purpose of error reporting and debugging
-fn vec_to_string(v: &Vec<usize>) -> String{
-	let mut sb = String::from("(");
-	let mut not_first = false;
-	for n in v {
-		if not_first {
-			sb += &String::from(", ");
-		} else {
-			not_first = true;
+
+impl<T> EdgeMode<T> {
+	/// Maps a coordinate along one axis into the range 0..size according to this edge mode
+	/// # Parameters
+	/// * **coord** - the coordinate to map
+	/// * **size** - size of the axis
+	/// # Returns
+	/// Returns the in-bounds coordinate, or None if the coordinate is out of bounds and this
+	/// edge mode is *Error* or *Default*
+	pub fn resolve(&self, coord: isize, size: usize) -> Option<usize> {
+		let n = size as isize;
+		if coord >= 0 && coord < n {
+			return Some(coord as usize);
+		}
+		if n == 0 {
+			return None;
 		}
-		sb += &String::from(n.to_string());
+		return match self {
+			EdgeMode::Error | EdgeMode::Default(_) => None,
+			EdgeMode::Wrap => Some(coord.rem_euclid(n) as usize),
+			EdgeMode::Clamp => Some(coord.clamp(0, n - 1) as usize),
+			EdgeMode::Mirror => {
+				let m = coord.rem_euclid(2 * n);
+				Some(if m < n { m } else { 2 * n - 1 - m } as usize)
+			},
+			EdgeMode::Reflect => {
+				if n == 1 {
+					return Some(0);
+				}
+				let m = coord.rem_euclid(2 * n - 2);
+				Some(if m < n { m } else { 2 * n - 2 - m } as usize)
+			},
+		};
 	}
-	sb += &String::from(")");
-	return sb;
+}
+
+/// This enum selects how one axis of a ZArray*D handles coordinates that are outside of its
+/// bounds, allowing for cylindrical and toroidal maps (eg wrapping the X axis while bounding
+/// the Y axis). It is used by *get(...)*, *set(...)*, the *bounded_get(...)* family of
+/// methods, *bounded_iter_region(...)*, and (before the edge mode) by the methods that take
+/// an *EdgeMode*, such as *sample(...)* and *neighbors(...)*.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum AxisTopology {
+	/// Out-of-bounds coordinates cannot be accessed (like *bounded_get(...)*)
+	#[default]
+	Bounded,
+	/// Out-of-bounds coordinates wrap around to the other side of the axis (like
+	/// *wrapped_get(...)*)
+	Wrap,
+	/// Out-of-bounds coordinates are clamped to the nearest edge of the axis
+	Clamp,
+}
+
+impl AxisTopology {
+	/// Maps a coordinate along one axis into the range 0..size according to this topology
+	/// # Parameters
+	/// * **coord** - the coordinate to map
+	/// * **size** - size of the axis
+	/// # Returns
+	/// Returns the in-bounds coordinate, or None if the coordinate is out of bounds and this
+	/// axis is *Bounded*
+	pub fn resolve(&self, coord: isize, size: usize) -> Option<usize> {
+		let mode: EdgeMode<()> = match self {
+			AxisTopology::Bounded => EdgeMode::Error,
+			AxisTopology::Wrap => EdgeMode::Wrap,
+			AxisTopology::Clamp => EdgeMode::Clamp,
+		};
+		return mode.resolve(coord, size);
+	}
+}
+
+/// Utility function for converting a range of signed coordinates into (start inclusive, end
+/// exclusive) bounds, where unbounded ends are the edges of the range 0..size
+fn signed_range(range: &impl RangeBounds<isize>, size: usize) -> (isize, isize) {
+	let start = match range.start_bound() {
+		Bound::Included(&s) => s,
+		Bound::Excluded(&s) => s.saturating_add(1),
+		Bound::Unbounded => 0
+	};
+	let end = match range.end_bound() {
+		Bound::Included(&e) => e.saturating_add(1),
+		Bound::Excluded(&e) => e,
+		Bound::Unbounded => size as isize
+	};
+	return (start, end.max(start));
+}
+
+/// Utility function for converting a range of coordinates into (start inclusive, end exclusive)
+/// bounds, clamped to the range 0..size
+fn clamp_range(range: &impl RangeBounds<usize>, size: usize) -> (usize, usize) {
+	let start = match range.start_bound() {
+		Bound::Included(&s) => s,
+		Bound::Excluded(&s) => s.saturating_add(1),
+		Bound::Unbounded => 0
+	};
+	let end = match range.end_bound() {
+		Bound::Included(&e) => e.saturating_add(1),
+		Bound::Excluded(&e) => e,
+		Bound::Unbounded => size
+	};
+	let end = end.min(size);
+	return (start.min(end), end);
 }
@@ -0,0 +1,110 @@
+//! This module implements conversions between the Z-arrays and the arrays of the *ndarray*
+//! crate (enabled with the `ndarray` feature). The *ndarray* arrays use the standard
+//! (row-major) axis order, where the last index changes fastest, so a *ZArray2D* of size
+//! (xsize, ysize) becomes an *Array2* of shape `(ysize, xsize)` with the value of (x, y) at
+//! `[[y, x]]`, and a *ZArray3D* of size (xsize, ysize, zsize) becomes an *Array3* of shape
+//! `(zsize, ysize, xsize)` with the value of (x, y, z) at `[[z, y, x]]`. This is the same
+//! layout as *to_row_major()* and *to_vec_of_vecs()*.
+//!
+//! The values are copied one data patch at a time, and arrays in standard layout are copied
+//! directly from their memory (other arrays, such as transposed views, are copied in their
+//! logical order).
+//! # Example Usage
+//! ```
+//! use ndarray::Array2;
+//! use zarray::z2d::ZArray2D;
+//! let heights = Array2::from_shape_fn((60, 80), |(y, x)| (x * y) as f32);
+//! let map = ZArray2D::<f32>::from(&heights);
+//! assert_eq!(map.dimensions(), (80, 60));
+//! assert_eq!(*map.get(7, 3).unwrap(), 21f32);
+//! let back = Array2::from(&map);
+//! assert_eq!(back, heights);
+//! ```
+
+use alloc::vec::Vec;
+use ndarray::{Array2, Array3, ArrayBase, Data, Ix2, Ix3};
+use crate::z2d::ZArray2D;
+use crate::z3d::ZArray3D;
+use crate::znd::ZArrayND;
+
+/// Private function for getting the values of an *ndarray* array in row-major order, copying
+/// them only if the array is not in standard layout
+fn with_row_major<T, S, D, R>(array: &ArrayBase<S, D>, build: impl FnOnce(&[T]) -> R) -> R
+	where T: Clone, S: Data<Elem=T>, D: ndarray::Dimension {
+	return match array.as_slice() {
+		Some(values) => build(values),
+		None => build(&array.iter().cloned().collect::<Vec<T>>())
+	};
+}
+
+impl<T, S, const E: usize> From<&ArrayBase<S, Ix2>> for ZArray2D<T, E> where T: Clone, S: Data<Elem=T> {
+	/// Copies an *ndarray* 2D array (or view) of shape `(ysize, xsize)` into a *ZArray2D*,
+	/// where the value at `[[y, x]]` becomes the value of (x, y)
+	fn from(array: &ArrayBase<S, Ix2>) -> Self {
+		let (ysize, xsize) = array.dim();
+		return with_row_major(array, |values| {
+			ZArrayND::from_row_major([xsize, ysize], values).expect("array size matches its shape").into()
+		});
+	}
+}
+
+impl<T, const E: usize> From<Array2<T>> for ZArray2D<T, E> where T: Clone {
+	/// Copies an *ndarray* 2D array of shape `(ysize, xsize)` into a *ZArray2D*, where the
+	/// value at `[[y, x]]` becomes the value of (x, y)
+	fn from(array: Array2<T>) -> Self {
+		return ZArray2D::from(&array);
+	}
+}
+
+impl<T, const E: usize> From<&ZArray2D<T, E>> for Array2<T> where T: Clone {
+	/// Copies a *ZArray2D* into an *ndarray* 2D array of shape `(ysize, xsize)`, where the
+	/// value of (x, y) is at `[[y, x]]`
+	fn from(array: &ZArray2D<T, E>) -> Self {
+		let (xsize, ysize) = array.dimensions();
+		return Array2::from_shape_vec((ysize, xsize), array.to_row_major()).expect("array size matches its shape");
+	}
+}
+
+impl<T, const E: usize> From<ZArray2D<T, E>> for Array2<T> where T: Clone {
+	/// Copies a *ZArray2D* into an *ndarray* 2D array of shape `(ysize, xsize)`, where the
+	/// value of (x, y) is at `[[y, x]]`
+	fn from(array: ZArray2D<T, E>) -> Self {
+		return Array2::from(&array);
+	}
+}
+
+impl<T, S, const E: usize> From<&ArrayBase<S, Ix3>> for ZArray3D<T, E> where T: Clone, S: Data<Elem=T> {
+	/// Copies an *ndarray* 3D array (or view) of shape `(zsize, ysize, xsize)` into a
+	/// *ZArray3D*, where the value at `[[z, y, x]]` becomes the value of (x, y, z)
+	fn from(array: &ArrayBase<S, Ix3>) -> Self {
+		let (zsize, ysize, xsize) = array.dim();
+		return with_row_major(array, |values| {
+			ZArrayND::from_row_major([xsize, ysize, zsize], values).expect("array size matches its shape").into()
+		});
+	}
+}
+
+impl<T, const E: usize> From<Array3<T>> for ZArray3D<T, E> where T: Clone {
+	/// Copies an *ndarray* 3D array of shape `(zsize, ysize, xsize)` into a *ZArray3D*, where
+	/// the value at `[[z, y, x]]` becomes the value of (x, y, z)
+	fn from(array: Array3<T>) -> Self {
+		return ZArray3D::from(&array);
+	}
+}
+
+impl<T, const E: usize> From<&ZArray3D<T, E>> for Array3<T> where T: Clone {
+	/// Copies a *ZArray3D* into an *ndarray* 3D array of shape `(zsize, ysize, xsize)`, where
+	/// the value of (x, y, z) is at `[[z, y, x]]`
+	fn from(array: &ZArray3D<T, E>) -> Self {
+		let (xsize, ysize, zsize) = array.dimensions();
+		return Array3::from_shape_vec((zsize, ysize, xsize), array.to_row_major()).expect("array size matches its shape");
+	}
+}
+
+impl<T, const E: usize> From<ZArray3D<T, E>> for Array3<T> where T: Clone {
+	/// Copies a *ZArray3D* into an *ndarray* 3D array of shape `(zsize, ysize, xsize)`, where
+	/// the value of (x, y, z) is at `[[z, y, x]]`
+	fn from(array: ZArray3D<T, E>) -> Self {
+		return Array3::from(&array);
+	}
+}
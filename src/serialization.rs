@@ -0,0 +1,248 @@
+//! This module implements the *serde* traits for the Z-arrays (enabled with the `serde`
+//! feature). Arrays are serialized as a struct of the array dimensions (`dims`), a dense
+//! sequence of the values in raster order (`data`, where the first dimension changes
+//! fastest) and the topology of each axis (`topology`), so the wire format does not depend
+//! on the internal patch layout. When deserializing, the number of values is checked against
+//! the dimensions, so malformed data results in an error instead of a broken array. The
+//! `topology` field is optional when deserializing (all axes are *AxisTopology::Bounded* if
+//! it is missing).
+//!
+//! For example, a 3x2 *ZArray2D* of `u8` values is serialized to JSON as
+//! `{"dims":[3,2],"data":[0,1,2,3,4,5],"topology":["Bounded","Bounded"]}`
+
+use alloc::format;
+use alloc::vec::Vec;
+use core::fmt::Formatter;
+use core::marker::PhantomData;
+use serde::de::{self, Deserialize, Deserializer, EnumAccess, MapAccess, SeqAccess, VariantAccess, Visitor};
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+use crate::z2d::ZArray2D;
+use crate::z3d::ZArray3D;
+use crate::znd::{checked_data_len, ZArrayND};
+use crate::AxisTopology;
+
+/// names of the serialized fields
+const FIELDS: &[&str] = &["dims", "data", "topology"];
+
+/// names of the serialized axis topologies
+const TOPOLOGIES: &[&str] = &["Bounded", "Wrap", "Clamp"];
+
+impl Serialize for AxisTopology {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		let index = match self {
+			AxisTopology::Bounded => 0,
+			AxisTopology::Wrap => 1,
+			AxisTopology::Clamp => 2
+		};
+		return serializer.serialize_unit_variant("AxisTopology", index, TOPOLOGIES[index as usize]);
+	}
+}
+
+/// Private struct for deserializing the name (or index) of an axis topology
+struct TopologyName(AxisTopology);
+
+impl<'de> Deserialize<'de> for TopologyName {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		struct TopologyNameVisitor;
+		impl<'de> Visitor<'de> for TopologyNameVisitor {
+			type Value = TopologyName;
+			fn expecting(&self, f: &mut Formatter) -> core::fmt::Result {
+				return f.write_str("`Bounded`, `Wrap` or `Clamp`");
+			}
+			fn visit_str<Er: de::Error>(self, value: &str) -> Result<TopologyName, Er> {
+				return match value {
+					"Bounded" => Ok(TopologyName(AxisTopology::Bounded)),
+					"Wrap" => Ok(TopologyName(AxisTopology::Wrap)),
+					"Clamp" => Ok(TopologyName(AxisTopology::Clamp)),
+					_ => Err(de::Error::unknown_variant(value, TOPOLOGIES))
+				};
+			}
+			fn visit_u64<Er: de::Error>(self, value: u64) -> Result<TopologyName, Er> {
+				return match value {
+					0 => Ok(TopologyName(AxisTopology::Bounded)),
+					1 => Ok(TopologyName(AxisTopology::Wrap)),
+					2 => Ok(TopologyName(AxisTopology::Clamp)),
+					_ => Err(de::Error::invalid_value(de::Unexpected::Unsigned(value), &self))
+				};
+			}
+		}
+		return deserializer.deserialize_identifier(TopologyNameVisitor);
+	}
+}
+
+impl<'de> Deserialize<'de> for AxisTopology {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		struct TopologyVisitor;
+		impl<'de> Visitor<'de> for TopologyVisitor {
+			type Value = AxisTopology;
+			fn expecting(&self, f: &mut Formatter) -> core::fmt::Result {
+				return f.write_str("an axis topology");
+			}
+			fn visit_enum<A: EnumAccess<'de>>(self, data: A) -> Result<AxisTopology, A::Error> {
+				let (TopologyName(topology), variant) = data.variant()?;
+				variant.unit_variant()?;
+				return Ok(topology);
+			}
+		}
+		return deserializer.deserialize_enum("AxisTopology", TOPOLOGIES, TopologyVisitor);
+	}
+}
+
+/// Private struct for serializing the values of an array as a sequence in raster order
+struct RasterValues<'a, T, const N: usize, const E: usize>(&'a ZArrayND<T, N, E>);
+
+impl<'a, T, const N: usize, const E: usize> Serialize for RasterValues<'a, T, N, E> where T: Serialize {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		return serializer.collect_seq(self.0.iter_raster().map(|item| item.value));
+	}
+}
+
+impl<T, const N: usize, const E: usize> Serialize for ZArrayND<T, N, E> where T: Serialize {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		let mut state = serializer.serialize_struct("ZArrayND", 3)?;
+		state.serialize_field("dims", &self.dimensions()[..])?;
+		state.serialize_field("data", &RasterValues(self))?;
+		state.serialize_field("topology", &self.topology()[..])?;
+		return state.end();
+	}
+}
+
+/// Private enum for identifying the serialized fields
+enum Field {
+	Dims,
+	Data,
+	Topology
+}
+
+impl<'de> Deserialize<'de> for Field {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		struct FieldVisitor;
+		impl<'de> Visitor<'de> for FieldVisitor {
+			type Value = Field;
+			fn expecting(&self, f: &mut Formatter) -> core::fmt::Result {
+				return f.write_str("`dims`, `data` or `topology`");
+			}
+			fn visit_str<Er: de::Error>(self, value: &str) -> Result<Field, Er> {
+				return match value {
+					"dims" => Ok(Field::Dims),
+					"data" => Ok(Field::Data),
+					"topology" => Ok(Field::Topology),
+					_ => Err(de::Error::unknown_field(value, FIELDS))
+				};
+			}
+		}
+		return deserializer.deserialize_identifier(FieldVisitor);
+	}
+}
+
+/// Private visitor that rebuilds a *ZArrayND* from its dimensions and raster-ordered values
+struct ZArrayNDVisitor<T, const N: usize, const E: usize> {
+	marker: PhantomData<T>
+}
+
+impl<T, const N: usize, const E: usize> ZArrayNDVisitor<T, N, E> where T: Clone {
+	/// checks the deserialized dimensions, values and topology and builds the array from them
+	fn build<Er: de::Error>(dims: Vec<usize>, data: Vec<T>, topology: Option<Vec<AxisTopology>>)
+		-> Result<ZArrayND<T, N, E>, Er> {
+		let dims: [usize; N] = match dims.try_into() {
+			Ok(dims) => dims,
+			Err(dims) => return Err(de::Error::invalid_length(dims.len(), &format!("{} dimensions", N).as_str()))
+		};
+		let too_large = || de::Error::custom("array dimensions are too large");
+		let expected = dims.iter().try_fold(1usize, |n, d| n.checked_mul(*d)).ok_or_else(too_large)?;
+		// the dimensions are padded to whole data patches, which must not overflow either
+		checked_data_len::<N, E>(&dims).ok_or_else(too_large)?;
+		if data.len() != expected {
+			return Err(de::Error::invalid_length(data.len(), &format!("{} values", expected).as_str()));
+		}
+		let topology: [AxisTopology; N] = match topology {
+			None => [AxisTopology::default(); N],
+			Some(topology) => match topology.try_into() {
+				Ok(topology) => topology,
+				Err(topology) => return Err(de::Error::invalid_length(topology.len(),
+					&format!("{} axis topologies", N).as_str()))
+			}
+		};
+		return Ok(ZArrayND::from_row_major_vec(dims, data).with_topology(topology));
+	}
+}
+
+impl<'de, T, const N: usize, const E: usize> Visitor<'de> for ZArrayNDVisitor<T, N, E>
+	where T: Deserialize<'de> + Clone {
+	type Value = ZArrayND<T, N, E>;
+
+	fn expecting(&self, f: &mut Formatter) -> core::fmt::Result {
+		return write!(f, "a {}-dimensional Z-array", N);
+	}
+
+	fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+		let dims: Vec<usize> = seq.next_element()?.ok_or_else(|| de::Error::invalid_length(0, &self))?;
+		let data: Vec<T> = seq.next_element()?.ok_or_else(|| de::Error::invalid_length(1, &self))?;
+		let topology: Option<Vec<AxisTopology>> = seq.next_element()?;
+		return Self::build(dims, data, topology);
+	}
+
+	fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+		let mut dims: Option<Vec<usize>> = None;
+		let mut data: Option<Vec<T>> = None;
+		let mut topology: Option<Vec<AxisTopology>> = None;
+		while let Some(key) = map.next_key()? {
+			match key {
+				Field::Dims => {
+					if dims.is_some() {
+						return Err(de::Error::duplicate_field("dims"));
+					}
+					dims = Some(map.next_value()?);
+				},
+				Field::Data => {
+					if data.is_some() {
+						return Err(de::Error::duplicate_field("data"));
+					}
+					data = Some(map.next_value()?);
+				},
+				Field::Topology => {
+					if topology.is_some() {
+						return Err(de::Error::duplicate_field("topology"));
+					}
+					topology = Some(map.next_value()?);
+				}
+			}
+		}
+		let dims = dims.ok_or_else(|| de::Error::missing_field("dims"))?;
+		let data = data.ok_or_else(|| de::Error::missing_field("data"))?;
+		return Self::build(dims, data, topology);
+	}
+}
+
+/// Deserializing requires *Clone* because the padding of the data patches (past the end of
+/// each dimension) is filled with copies of the nearest values
+impl<'de, T, const N: usize, const E: usize> Deserialize<'de> for ZArrayND<T, N, E>
+	where T: Deserialize<'de> + Clone {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		return deserializer.deserialize_struct("ZArrayND", FIELDS, ZArrayNDVisitor{marker: PhantomData});
+	}
+}
+
+impl<T, const E: usize> Serialize for ZArray2D<T, E> where T: Serialize {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		return self.as_nd().serialize(serializer);
+	}
+}
+
+impl<'de, T, const E: usize> Deserialize<'de> for ZArray2D<T, E> where T: Deserialize<'de> + Clone {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		return ZArrayND::<T, 2, E>::deserialize(deserializer).map(ZArray2D::from);
+	}
+}
+
+impl<T, const E: usize> Serialize for ZArray3D<T, E> where T: Serialize {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		return self.as_nd().serialize(serializer);
+	}
+}
+
+impl<'de, T, const E: usize> Deserialize<'de> for ZArray3D<T, E> where T: Deserialize<'de> + Clone {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		return ZArrayND::<T, 3, E>::deserialize(deserializer).map(ZArray3D::from);
+	}
+}
@@ -0,0 +1,1072 @@
+//! This module is used for storing sparse N-dimensional data arrays, where most of the values
+//! are the same background value (eg the air in a voxel world). Like the dense Z-arrays, they
+//! are stored as Z-indexed data patches, but a data patch is only allocated by the first write
+//! of a value that differs from the rest of the data patch, and it is freed again as soon as
+//! all of its values are the same. A data patch that is uniformly filled with a value other
+//! than the background value (eg the solid rock under a voxel world) only stores that one
+//! value. *ZSparseArray2D* and *ZSparseArray3D* are front-ends for *ZSparseArrayND*, with the
+//! same coordinate conventions as *ZArray2D* and *ZArray3D*.
+//!
+//! Because the number of matching values in each data patch must stay up to date, the values
+//! of a sparse array can only be changed with the *set(...)* and *fill(...)* families of
+//! methods (there are no *get_mut(...)* or *iter_mut()* methods). The *iter()* method visits
+//! every value of the array (the unallocated data patches yield their uniform value), while
+//! *iter_non_background()* skips the data patches that only hold the background value (but
+//! visits the data patches that are uniformly filled with another value).
+//! # Example Usage
+//! ```
+//! use zarray::sparse::ZSparseArray3D;
+//! let mut world = ZSparseArray3D::new(512, 128, 512, 0u8);
+//! assert_eq!(world.allocated_patch_count(), 0);
+//! world.fill(500, 0, 500, 510, 64, 510, 1u8).unwrap();
+//! assert_eq!(*world.get(505, 10, 505).unwrap(), 1u8);
+//! assert_eq!(*world.get(505, 100, 505).unwrap(), 0u8);
+//! assert_eq!(world.allocated_patch_count(), 2 * 8 * 2);
+//! assert_eq!(world.iter_non_background().filter(|item| *item.value == 1u8).count(), 10 * 64 * 10);
+//! world.fill(500, 0, 500, 510, 64, 510, 0u8).unwrap();
+//! assert_eq!(world.allocated_patch_count(), 0);
+//! // a data patch that is filled with one value is freed as well
+//! world.fill(0, 0, 0, 8, 8, 8, 2u8).unwrap();
+//! assert_eq!(world.allocated_patch_count(), 0);
+//! assert_eq!(*world.get(4, 4, 4).unwrap(), 2u8);
+//! ```
+
+use alloc::vec;
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+use core::iter::Enumerate;
+use core::slice::Iter;
+use crate::{LookUpError, MAX_RANK};
+use crate::z2d::{ZArray2D, ZArray2DIteratorItem};
+use crate::z3d::{ZArray3D, ZArray3DIteratorItem};
+use crate::znd::{is_empty_box, next_in_box, patch_coord, patch_dims, patch_index, patch_origin, zorder_index,
+	Patch, PatchShape, ZArrayND, ZArrayNDIteratorItem, DEFAULT_PATCH_EDGE};
+
+/// Private enum for a data patch that does not only hold the background value
+#[derive(Debug, Clone)]
+enum SparsePatch<T> {
+	/// every value of the data patch is the same value (which is not the background value)
+	Uniform(T),
+	/// an allocated data patch and the number of its in-bounds values that are equal to its
+	/// first value (so that it is uniform when all of them are)
+	Allocated(Patch<T>, usize)
+}
+
+/// This is primary struct for sparse z-indexed N-dimensional arrays. Create new instances with
+/// ZSparseArrayND::new([x_size, y_size, ...], background_value)
+///
+/// The optional parameter *E* is the length of the data patches in every dimension, which may
+/// be 4, 8 (the default), or 16 (see [ZArrayND]).
+#[derive(Debug, Clone)]
+pub struct ZSparseArrayND<T, const N: usize, const E: usize = DEFAULT_PATCH_EDGE> {
+	dims: [usize; N],
+	pdims: [usize; N],
+	patches: Vec<Option<SparsePatch<T>>>,
+	background: T
+}
+
+impl<T, const N: usize, const E: usize> ZSparseArrayND<T, N, E> {
+	/// compile-time check that the number of dimensions is supported
+	const RANK_CHECK: () = assert!(N > 0 && N <= MAX_RANK, "ZSparseArrayND supports 1 to 4 dimensions");
+
+	/// Create a sparse Z-index N-dimensional array in which every value is the provided
+	/// background value. No data patches are allocated until a different value is set.
+	/// # Parameters
+	/// * **dims** - size of this array in each dimension
+	/// * **background** - the value of every coordinate that has not been set to something
+	/// else
+	/// # Returns
+	/// Returns an initialized *ZSparseArrayND* struct
+	pub fn new(dims: [usize; N], background: T) -> ZSparseArrayND<T, N, E> {
+		#[allow(clippy::let_unit_value)]
+		let _ = Self::RANK_CHECK;
+		let pdims = patch_dims::<N, E>(&dims);
+		let patch_count = pdims.iter().product();
+		let mut patches = Vec::with_capacity(patch_count);
+		patches.resize_with(patch_count, || None);
+		return ZSparseArrayND { dims, pdims, patches, background };
+	}
+
+	/// Gets the size of this array in each dimension
+	/// # Returns
+	/// Returns an array of the size of each dimension
+	pub fn dimensions(&self) -> [usize; N] {
+		return self.dims;
+	}
+
+	/// Gets the background value, which is the value of every coordinate that has not been set
+	/// to something else
+	/// # Returns
+	/// Returns a reference to the background value
+	pub fn background(&self) -> &T {
+		return &self.background;
+	}
+
+	/// Gets the total number of data patches (allocated or not) that cover this array
+	/// # Returns
+	/// Returns the number of data patches
+	pub fn patch_count(&self) -> usize {
+		return self.patches.len();
+	}
+
+	/// Gets the number of data patches that are currently allocated (ie that contain at least
+	/// two different values)
+	/// # Returns
+	/// Returns the number of allocated data patches
+	pub fn allocated_patch_count(&self) -> usize {
+		return self.patches.iter().filter(|patch| matches!(patch, Some(SparsePatch::Allocated(..)))).count();
+	}
+
+	/// private function for getting the number of in-bounds values of a data patch
+	fn patch_volume(&self, origin: &[usize; N]) -> usize {
+		return (0..N).map(|d| (self.dims[d] - origin[d]).min(E)).product();
+	}
+
+	/// private function for checking that a coordinate is in bounds
+	fn in_bounds(&self, coord: &[usize; N]) -> bool {
+		return coord.iter().zip(&self.dims).all(|(c, d)| c < d);
+	}
+
+	/// private function for getting a value without bounds checking
+	fn value_at(&self, coord: [usize; N]) -> &T {
+		return match &self.patches[patch_index::<N, E>(&coord, &self.pdims)] {
+			Some(SparsePatch::Allocated(patch, _)) => patch.get::<N, E>(&coord),
+			Some(SparsePatch::Uniform(value)) => value,
+			None => &self.background
+		};
+	}
+
+	/// private function for wrapping a coordinate into the bounds of this array
+	fn wrap_coord(&self, coord: [isize; N]) -> [usize; N] {
+		let mut wrapped = [0; N];
+		for (d, w) in wrapped.iter_mut().enumerate() {
+			*w = coord[d].rem_euclid(self.dims[d] as isize) as usize;
+		}
+		return wrapped;
+	}
+
+	/// private function for converting a signed coordinate to an unsigned coordinate, if it is
+	/// in bounds
+	fn bounded_coord(&self, coord: [isize; N]) -> Option<[usize; N]> {
+		let mut bounded = [0; N];
+		for (d, b) in bounded.iter_mut().enumerate() {
+			if coord[d] < 0 || coord[d] >= self.dims[d] as isize {
+				return None;
+			}
+			*b = coord[d] as usize;
+		}
+		return Some(bounded);
+	}
+
+	/// Gets a value from the array, or returns a *LookUpError* if the provided coordinate
+	/// is out of bounds. If you are using a default value for out-of-bounds coordinates,
+	/// then you should use the *bounded_get(coord)* method instead. If you want access to
+	/// wrap-around, then use the *wrapped_get(coord)* method.
+	/// # Parameters
+	/// * **coord** - coordinate of the value
+	/// # Returns
+	/// Returns a Result type that holds either the returned data value (as a reference) from
+	/// the array, or a *LookUpError* signalling that the coordinate is out of bounds
+	pub fn get(&self, coord: [usize; N]) -> Result<&T, LookUpError> {
+		if self.in_bounds(&coord) {
+			Ok(self.value_at(coord))
+		} else {
+			Err(LookUpError::new(&coord.map(|c| c as isize), &self.dims))
+		}
+	}
+
+	/// Gets a value from the array, wrapping around each axis when the coordinates are negative
+	/// or outside the size of this array. Good for when you want tiling behavior.
+	/// # Parameters
+	/// * **coord** - coordinate of the value
+	/// # Returns
+	/// Returns a reference to the data stored at the provided coordinate (wrapping all
+	/// dimensions)
+	pub fn wrapped_get(&self, coord: [isize; N]) -> &T {
+		return self.value_at(self.wrap_coord(coord));
+	}
+
+	/// Gets a value from the array as an Option that is None if the coordinate is out of
+	/// bounds.
+	/// # Parameters
+	/// * **coord** - coordinate of the value
+	/// # Returns
+	/// Returns an Option type that holds either the returned data value (as a reference) from
+	/// the array, or *None* signalling that the coordinate is out of bounds (which can be
+	/// combined with .unwrap_or(default_value) to implement an out-of-bounds default)
+	pub fn bounded_get(&self, coord: [isize; N]) -> Option<&T> {
+		return Some(self.value_at(self.bounded_coord(coord)?));
+	}
+
+	/// Iterates through all values of this array in Z-order, one data patch at a time. The
+	/// values of the unallocated data patches are their uniform value (usually the background
+	/// value).
+	/// # Returns
+	/// Returns an iterator of *ZArrayNDIteratorItem* structs (coordinate and value)
+	pub fn iter(&self) -> ZSparseArrayNDIterator<'_, T, N, E> {
+		return ZSparseArrayNDIterator::new(self, false);
+	}
+
+	/// Iterates through the values of this array in Z-order, skipping the data patches that
+	/// only hold the background value. The data patches that are uniformly filled with
+	/// another value are visited even though they are not allocated, and the allocated data
+	/// patches may still contain some background values.
+	/// # Returns
+	/// Returns an iterator of *ZArrayNDIteratorItem* structs (coordinate and value)
+	pub fn iter_non_background(&self) -> ZSparseArrayNDIterator<'_, T, N, E> {
+		return ZSparseArrayNDIterator::new(self, true);
+	}
+}
+
+impl<T, const N: usize, const E: usize> ZSparseArrayND<T, N, E> where T: Clone + PartialEq {
+	/// private function for setting a value without bounds checking, which allocates or frees
+	/// the data patch as needed
+	fn set_value(&mut self, coord: [usize; N], new_val: T) {
+		let pindex = patch_index::<N, E>(&coord, &self.pdims);
+		let origin = patch_origin::<N, E>(pindex, &self.pdims);
+		let volume = self.patch_volume(&origin);
+		let index = zorder_index::<N, E>(&coord);
+		let matching = match &mut self.patches[pindex] {
+			Some(SparsePatch::Allocated(patch, matching)) => {
+				let contents = patch.contents_mut();
+				if index == 0 {
+					// the other values are compared to the first value, so count them again
+					contents[0] = new_val;
+					*matching = contents.iter().enumerate().filter(|(i, v)| {
+						patch_coord::<N, E>(&origin, *i).iter().zip(&self.dims).all(|(c, d)| c < d)
+							&& **v == contents[0]
+					}).count();
+				} else {
+					let was_matching = contents[index] == contents[0];
+					let is_matching = new_val == contents[0];
+					contents[index] = new_val;
+					match (was_matching, is_matching) {
+						(true, false) => *matching -= 1,
+						(false, true) => *matching += 1,
+						_ => {}
+					}
+				}
+				*matching
+			},
+			uniform => {
+				let value = match uniform {
+					Some(SparsePatch::Uniform(value)) => value,
+					_ => &self.background
+				};
+				if new_val == *value {
+					return;
+				}
+				// only the new value differs from the others, and the first value may be the new one
+				let mut contents = vec![value.clone(); PatchShape::<N, E>::SIZE];
+				contents[index] = new_val;
+				let matching = if index == 0 { 1 } else { volume - 1 };
+				*uniform = Some(SparsePatch::Allocated(Patch::new(contents.into()), matching));
+				matching
+			}
+		};
+		if matching == volume {
+			// every value of the data patch is the same, so free it
+			let value = match &self.patches[pindex] {
+				Some(SparsePatch::Allocated(patch, _)) => patch.contents()[0].clone(),
+				_ => return
+			};
+			self.patches[pindex] = if value == self.background { None } else { Some(SparsePatch::Uniform(value)) };
+		}
+	}
+
+	/// Sets a value in the array, or returns a *LookUpError* if the provided coordinate
+	/// is out of bounds. Setting a value that differs from the rest of its data patch
+	/// allocates the data patch (if it was not already), and setting the last differing value
+	/// of a data patch so that all of its values are the same frees the data patch.
+	/// # Parameters
+	/// * **coord** - coordinate of the value
+	/// * **new_val** - value to store in the array at the coordinate
+	/// # Returns
+	/// Returns a Result type that is either empty or a *LookUpError* signalling that the
+	/// coordinate is out of bounds
+	pub fn set(&mut self, coord: [usize; N], new_val: T) -> Result<(), LookUpError> {
+		if self.in_bounds(&coord) {
+			self.set_value(coord, new_val);
+			Ok(())
+		} else {
+			Err(LookUpError::new(&coord.map(|c| c as isize), &self.dims))
+		}
+	}
+
+	/// Sets a value in the array at the provided coordinate, wrapping each axis if the
+	/// coordinate is negative or out of bounds.
+	/// # Parameters
+	/// * **coord** - coordinate of the value
+	/// * **new_val** - value to store in the array at the coordinate, wrapping around all
+	/// dimensions
+	pub fn wrapped_set(&mut self, coord: [isize; N], new_val: T) {
+		self.set_value(self.wrap_coord(coord), new_val);
+	}
+
+	/// Sets a value in the array if and only if the provided coordinate is in bounds.
+	/// Otherwise this method does nothing if the coordiante is out of bounds.
+	/// # Parameters
+	/// * **coord** - coordinate of the value
+	/// * **new_val** - value to store in the array at the coordinate
+	pub fn bounded_set(&mut self, coord: [isize; N], new_val: T) {
+		if let Some(coord) = self.bounded_coord(coord) {
+			self.set_value(coord, new_val);
+		}
+	}
+
+	/// Fills a box-shaped region of this array with a given value, or returns a *LookUpError*
+	/// if the provided coordinates go out of bounds. If you just want to ignore any
+	/// out-of-bounds coordinates, then you should use the *bounded_fill(start, end)* method
+	/// instead.
+	/// # Parameters
+	/// * **start** - the first corner of the region (inclusive)
+	/// * **end** - the second corner of the region (exclusive)
+	/// * **new_val** - value to store in the array in the bounding box defined by
+	/// start -> end
+	/// # Returns
+	/// Returns a Result type that is either empty or a *LookUpError* signalling that a
+	/// coordinate is out of bounds (in which case this array is unchanged)
+	pub fn fill(&mut self, start: [usize; N], end: [usize; N], new_val: impl Borrow<T>)
+				-> Result<(), LookUpError> {
+		if is_empty_box(&start, &end) {
+			return Ok(());
+		}
+		if (0..N).any(|d| end[d] > self.dims[d]) {
+			let last = end.map(|e| (e - 1) as isize);
+			return Err(LookUpError::new(&last, &self.dims));
+		}
+		self.fill_box(start, end, new_val.borrow());
+		return Ok(());
+	}
+
+	/// private function for filling a box-shaped region (inclusive start, exclusive end, must
+	/// be in bounds), which makes the data patches that the region fully covers uniform
+	/// instead of setting their values one at a time
+	fn fill_box(&mut self, start: [usize; N], end: [usize; N], new_val: &T) {
+		if is_empty_box(&start, &end) {
+			return;
+		}
+		let pstart = start.map(|s| s >> PatchShape::<N, E>::BITS);
+		let pend = end.map(|e| ((e - 1) >> PatchShape::<N, E>::BITS) + 1);
+		let mut pcoord = pstart;
+		loop {
+			let origin = pcoord.map(|p| p << PatchShape::<N, E>::BITS);
+			// the part of the region that is in this data patch
+			let cell_start: [usize; N] = core::array::from_fn(|d| start[d].max(origin[d]));
+			let cell_end: [usize; N] = core::array::from_fn(|d| end[d].min(origin[d] + E));
+			if (0..N).all(|d| cell_start[d] == origin[d] && cell_end[d] == (origin[d] + E).min(self.dims[d])) {
+				let pindex = patch_index::<N, E>(&origin, &self.pdims);
+				self.patches[pindex] = if *new_val == self.background {
+					None
+				} else {
+					Some(SparsePatch::Uniform(new_val.clone()))
+				};
+			} else {
+				let mut coord = cell_start;
+				loop {
+					self.set_value(coord, new_val.clone());
+					if !next_in_box(&mut coord, &cell_start, &cell_end) {
+						break;
+					}
+				}
+			}
+			if !next_in_box(&mut pcoord, &pstart, &pend) {
+				return;
+			}
+		}
+	}
+
+	/// Fills a box-shaped region of this array with a given value, wrapping the axese when
+	/// coordinates go out of bounds.
+	/// # Parameters
+	/// * **start** - the first corner of the region (inclusive)
+	/// * **end** - the second corner of the region (exclusive)
+	/// * **new_val** - value to store in the array in the bounding box defined by
+	/// start -> end with wrapped axese
+	pub fn wrapped_fill(&mut self, start: [isize; N], end: [isize; N], new_val: impl Borrow<T>) {
+		if is_empty_box(&start, &end) || self.dims.contains(&0) {
+			return;
+		}
+		// each axis of the region wraps into at most two in-bounds ranges
+		let ranges: [[(usize, usize); 2]; N] = core::array::from_fn(|d| {
+			let (size, len) = (self.dims[d], end[d].abs_diff(start[d]));
+			if len >= size {
+				return [(0, size), (0, 0)];
+			}
+			let first = start[d].rem_euclid(size as isize) as usize;
+			if first + len <= size {
+				[(first, first + len), (0, 0)]
+			} else {
+				[(first, size), (0, first + len - size)]
+			}
+		});
+		// fill the boxes of every combination of the ranges
+		let mut choice = [0; N];
+		loop {
+			let box_start = core::array::from_fn(|d| ranges[d][choice[d]].0);
+			let box_end = core::array::from_fn(|d| ranges[d][choice[d]].1);
+			self.fill_box(box_start, box_end, new_val.borrow());
+			if !next_in_box(&mut choice, &[0; N], &[2; N]) {
+				return;
+			}
+		}
+	}
+
+	/// Fills a box-shaped region of this array with a given value, ignoring any coordinates
+	/// that go out of bounds.
+	/// # Parameters
+	/// * **start** - the first corner of the region (inclusive)
+	/// * **end** - the second corner of the region (exclusive)
+	/// * **new_val** - value to store in the array in the bounding box defined by
+	/// start -> end
+	pub fn bounded_fill(&mut self, start: [isize; N], end: [isize; N], new_val: impl Borrow<T>) {
+		let mut clipped_start = [0; N];
+		let mut clipped_end = [0; N];
+		for d in 0..N {
+			clipped_start[d] = start[d].clamp(0, self.dims[d] as isize) as usize;
+			clipped_end[d] = end[d].clamp(0, self.dims[d] as isize) as usize;
+		}
+		self.fill(clipped_start, clipped_end, new_val).expect("clipped region is in bounds");
+	}
+
+	/// Creates a new sparse array with the same size and values as a dense array, allocating
+	/// only the data patches that contain more than one value
+	/// # Parameters
+	/// * **array** - the dense array to copy
+	/// * **background** - the background value of the new sparse array
+	/// # Returns
+	/// Returns a new *ZSparseArrayND* with the values of the dense array
+	pub fn from_dense(array: &ZArrayND<T, N, E>, background: T) -> ZSparseArrayND<T, N, E> {
+		let mut sparse = ZSparseArrayND::new(array.dimensions(), background);
+		for item in array.iter() {
+			sparse.set_value(item.coord, item.value.clone());
+		}
+		return sparse;
+	}
+
+	/// Copies this sparse array into a dense array of the same size
+	/// # Returns
+	/// Returns a new *ZArrayND* with the values of this array
+	pub fn to_dense(&self) -> ZArrayND<T, N, E> {
+		return ZArrayND::from_patch_fn(self.dims, |pindex| match &self.patches[pindex] {
+			Some(SparsePatch::Allocated(patch, _)) => patch.contents().to_vec(),
+			Some(SparsePatch::Uniform(value)) => vec![value.clone(); PatchShape::<N, E>::SIZE],
+			None => vec![self.background.clone(); PatchShape::<N, E>::SIZE]
+		});
+	}
+}
+
+/// Iterator that iterates through a sparse array in Z-order, one data patch at a time
+pub struct ZSparseArrayNDIterator<'a, T, const N: usize, const E: usize = DEFAULT_PATCH_EDGE> {
+	dims: [usize; N],
+	pdims: [usize; N],
+	background: &'a T,
+	/// whether to skip the patches that only hold the background value
+	skip_empty: bool,
+	/// remaining patches to iterate over
+	patches: Enumerate<Iter<'a, Option<SparsePatch<T>>>>,
+	/// coordinate of the first value of the current patch
+	origin: [usize; N],
+	/// values of the current patch (None if the patch is not allocated)
+	contents: Option<&'a [T]>,
+	/// value of every coordinate of the current patch if it is not allocated
+	uniform: &'a T,
+	/// index of the next value in the current patch
+	index: usize
+}
+
+impl<'a, T, const N: usize, const E: usize> ZSparseArrayNDIterator<'a, T, N, E> {
+	/// private constructor
+	fn new(array: &'a ZSparseArrayND<T, N, E>, skip_empty: bool) -> ZSparseArrayNDIterator<'a, T, N, E> {
+		return ZSparseArrayNDIterator {
+			dims: array.dims,
+			pdims: array.pdims,
+			background: &array.background,
+			skip_empty,
+			patches: array.patches.iter().enumerate(),
+			origin: [0; N],
+			contents: None,
+			uniform: &array.background,
+			index: PatchShape::<N, E>::SIZE
+		};
+	}
+}
+
+impl<'a, T, const N: usize, const E: usize> Iterator for ZSparseArrayNDIterator<'a, T, N, E> {
+	type Item = ZArrayNDIteratorItem<'a, T, N>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		loop {
+			if self.index < PatchShape::<N, E>::SIZE {
+				let index = self.index;
+				self.index += 1;
+				let coord = patch_coord::<N, E>(&self.origin, index);
+				if coord.iter().zip(&self.dims).all(|(c, d)| c < d) {
+					let value = match self.contents {
+						Some(contents) => &contents[index],
+						None => self.uniform
+					};
+					return Some(ZArrayNDIteratorItem{coord, value});
+				}
+			} else {
+				// move on to the next patch (or finish if there are no more patches)
+				let (pindex, patch) = self.patches.next()?;
+				if patch.is_none() && self.skip_empty {
+					continue;
+				}
+				self.origin = patch_origin::<N, E>(pindex, &self.pdims);
+				(self.contents, self.uniform) = match patch {
+					Some(SparsePatch::Allocated(patch, _)) => (Some(patch.contents()), self.background),
+					Some(SparsePatch::Uniform(value)) => (None, value),
+					None => (None, self.background)
+				};
+				self.index = 0;
+			}
+		}
+	}
+}
+
+/// This is primary struct for sparse z-indexed 2D arrays. Create new instances with
+/// ZSparseArray2D::new(x_size, y_size, background_value)
+///
+/// *ZSparseArray2D* is a typed front-end for a 2-dimensional [ZSparseArrayND], and it can be
+/// converted to and from a *ZSparseArrayND<T, 2, E>* with `From`/`Into`.
+#[derive(Debug, Clone)]
+pub struct ZSparseArray2D<T, const E: usize = DEFAULT_PATCH_EDGE> {
+	array: ZSparseArrayND<T, 2, E>
+}
+
+impl<T, const E: usize> From<ZSparseArrayND<T, 2, E>> for ZSparseArray2D<T, E> {
+	fn from(array: ZSparseArrayND<T, 2, E>) -> Self {
+		return ZSparseArray2D { array };
+	}
+}
+
+impl<T, const E: usize> From<ZSparseArray2D<T, E>> for ZSparseArrayND<T, 2, E> {
+	fn from(array: ZSparseArray2D<T, E>) -> Self {
+		return array.array;
+	}
+}
+
+impl<T> ZSparseArray2D<T> {
+	/// Create a sparse Z-index 2D array in which every value is the provided background value.
+	/// No data patches are allocated until a different value is set.
+	/// # Parameters
+	/// * **width** - size of this 2D array in the X dimension
+	/// * **height** - size of this 2D array in the Y dimension
+	/// * **background** - the value of every coordinate that has not been set to something
+	/// else
+	/// # Returns
+	/// Returns an initialized *ZSparseArray2D* struct
+	pub fn new(width: usize, height: usize, background: T) -> ZSparseArray2D<T> {
+		return ZSparseArray2D { array: ZSparseArrayND::new([width, height], background) };
+	}
+}
+
+impl<T, const E: usize> ZSparseArray2D<T, E> {
+	/// Gets the 2-dimensional *ZSparseArrayND* that holds the data of this 2D array
+	/// # Returns
+	/// Returns a reference to the *ZSparseArrayND*, where the first dimension is X
+	pub fn as_nd(&self) -> &ZSparseArrayND<T, 2, E> {
+		return &self.array;
+	}
+
+	/// Gets the (x, y) size of this 2D array
+	/// # Returns
+	/// Returns a tuple of (width, height) for this 2D array
+	pub fn dimensions(&self) -> (usize, usize) {
+		let [width, height] = self.array.dimensions();
+		return (width, height);
+	}
+
+	/// Gets the X-dimension size (aka width) of this 2D array
+	/// # Returns
+	/// Returns the size in the X dimension
+	pub fn xsize(&self) -> usize {
+		return self.array.dimensions()[0];
+	}
+
+	/// Alias for `xsize()`
+	/// # Returns
+	/// Returns the size in the X dimension
+	pub fn width(&self) -> usize {
+		return self.xsize();
+	}
+
+	/// Gets the Y-dimension size (aka height) of this 2D array
+	/// # Returns
+	/// Returns the size in the Y dimension
+	pub fn ysize(&self) -> usize {
+		return self.array.dimensions()[1];
+	}
+
+	/// Alias for `ysize()`
+	/// # Returns
+	/// Returns the size in the Y dimension
+	pub fn height(&self) -> usize {
+		return self.ysize();
+	}
+
+	/// Gets the background value, which is the value of every coordinate that has not been set
+	/// to something else
+	/// # Returns
+	/// Returns a reference to the background value
+	pub fn background(&self) -> &T {
+		return self.array.background();
+	}
+
+	/// Gets the number of data patches that are currently allocated (ie that contain at least
+	/// two different values)
+	/// # Returns
+	/// Returns the number of allocated data patches
+	pub fn allocated_patch_count(&self) -> usize {
+		return self.array.allocated_patch_count();
+	}
+
+	/// Gets a value from the 2D array, or returns a *LookUpError* if the provided coordinate
+	/// is out of bounds. If you are using a default value for out-of-bounds coordinates,
+	/// then you should use the *bounded_get(x, y)* method instead. If you want access to
+	/// wrap-around (eg (-2, 0) equivalent to (width-2,0)), then use the *wrapped_get(x, y)*
+	/// method.
+	/// # Parameters
+	/// * **x** - x dimension coordinate
+	/// * **y** - y dimension coordinate
+	/// # Returns
+	/// Returns a Result type that holds either the returned data value (as a reference) from
+	/// the 2D array, or a *LookUpError* signalling that the coordinate is out of bounds
+	pub fn get(&self, x: usize, y: usize) -> Result<&T, LookUpError> {
+		return self.array.get([x, y]);
+	}
+
+	/// Gets a value from the 2D array, wrapping around the X and Y axese when the coordinates
+	/// are negative or outside the size of this 2D array. Good for when you want tiling
+	/// behavior.
+	/// # Parameters
+	/// * **x** - x dimension coordinate
+	/// * **y** - y dimension coordinate
+	/// # Returns
+	/// Returns a reference to the data stored at the provided coordinate (wrapping both x
+	/// and y dimensions)
+	pub fn wrapped_get(&self, x: isize, y: isize) -> &T {
+		return self.array.wrapped_get([x, y]);
+	}
+
+	/// Gets a value from the 2D array as an Option that is None if the coordinate
+	/// is out of bounds.
+	/// # Parameters
+	/// * **x** - x dimension coordinate
+	/// * **y** - y dimension coordinate
+	/// # Returns
+	/// Returns an Option type that holds either the returned data value (as a reference) from
+	/// the 2D array, or *None* signalling that the coordinate is out of bounds (which can be
+	/// combined with .unwrap_or(default_value) to implement an out-of-bounds default)
+	pub fn bounded_get(&self, x: isize, y: isize) -> Option<&T> {
+		return self.array.bounded_get([x, y]);
+	}
+
+	/// Iterates through all values of this 2D array, one data patch at a time. The values of
+	/// the unallocated data patches are their uniform value (usually the background value).
+	/// # Returns
+	/// Returns an iterator of *ZArray2DIteratorItem* structs (x, y, and value)
+	pub fn iter(&self) -> ZSparseArray2DIterator<'_, T, E> {
+		return ZSparseArray2DIterator { iter: self.array.iter() };
+	}
+
+	/// Iterates through the values of this 2D array, skipping the data patches that only
+	/// hold the background value (see [ZSparseArrayND::iter_non_background()])
+	/// # Returns
+	/// Returns an iterator of *ZArray2DIteratorItem* structs (x, y, and value)
+	pub fn iter_non_background(&self) -> ZSparseArray2DIterator<'_, T, E> {
+		return ZSparseArray2DIterator { iter: self.array.iter_non_background() };
+	}
+}
+
+impl<T, const E: usize> ZSparseArray2D<T, E> where T: Clone + PartialEq {
+	/// Sets a value in the 2D array, or returns a *LookUpError* if the provided coordinate
+	/// is out of bounds. If you want out-of-bound coordinates to result in a no-op, then use
+	/// the *bounded_set(x, y, val)* method instead. If you want access to wrap-around (eg
+	/// (-2, 0) equivalent to (width-2,0)), then use the *wrapped_set(x, y, val)* method.
+	/// # Parameters
+	/// * **x** - x dimension coordinate
+	/// * **y** - y dimension coordinate
+	/// * **new_val** - value to store in the 2D array at (x, y)
+	/// # Returns
+	/// Returns a Result type that is either empty or a *LookUpError* signalling that the
+	/// coordinate is out of bounds
+	pub fn set(&mut self, x: usize, y: usize, new_val: T) -> Result<(), LookUpError> {
+		return self.array.set([x, y], new_val);
+	}
+
+	/// Sets a value in the 2D array at the provided coordinate, wrapping the X and Y axese
+	/// if the coordinate is negative or out of bounds.
+	/// # Parameters
+	/// * **x** - x dimension coordinate
+	/// * **y** - y dimension coordinate
+	/// * **new_val** - value to store in the 2D array at (x, y), wrapping around both the x
+	/// and y dimensions
+	pub fn wrapped_set(&mut self, x: isize, y: isize, new_val: T) {
+		self.array.wrapped_set([x, y], new_val);
+	}
+
+	/// Sets a value in the 2D array if and only if the provided coordinate is in bounds.
+	/// Otherwise this method does nothing if the coordiante is out of bounds.
+	/// # Parameters
+	/// * **x** - x dimension coordinate
+	/// * **y** - y dimension coordinate
+	/// * **new_val** - value to store in the 2D array at (x, y)
+	pub fn bounded_set(&mut self, x: isize, y: isize, new_val: T) {
+		self.array.bounded_set([x, y], new_val);
+	}
+
+	/// Fills a rectangular area of this 2D array with a given value, or returns a
+	/// *LookUpError* if the provided coordinates go out of bounds.
+	/// # Parameters
+	/// * **x1** - the first x dimension coordinate (inclusive)
+	/// * **y1** - the first y dimension coordinate (inclusive)
+	/// * **x2** - the second x dimension coordinate (exclusive)
+	/// * **y2** - the second y dimension coordinate (exclusive)
+	/// * **new_val** - value to store in the 2D array in the bounding box defined by
+	/// (x1, y1) -> (x2, y2)
+	/// # Returns
+	/// Returns a Result type that is either empty or a *LookUpError* signalling that a
+	/// coordinate is out of bounds
+	pub fn fill(&mut self, x1: usize, y1: usize, x2: usize, y2: usize, new_val: impl Borrow<T>)
+				-> Result<(), LookUpError> {
+		return self.array.fill([x1, y1], [x2, y2], new_val);
+	}
+
+	/// Fills a rectangular area of this 2D array with a given value, wrapping the axese when
+	/// coordinates go out of bounds.
+	/// # Parameters
+	/// * **x1** - the first x dimension coordinate (inclusive)
+	/// * **y1** - the first y dimension coordinate (inclusive)
+	/// * **x2** - the second x dimension coordinate (exclusive)
+	/// * **y2** - the second y dimension coordinate (exclusive)
+	/// * **new_val** - value to store in the 2D array in the bounding box defined by
+	/// (x1, y1) -> (x2, y2) with wrapped axese
+	pub fn wrapped_fill(&mut self, x1: isize, y1: isize, x2: isize, y2: isize, new_val: impl Borrow<T>) {
+		self.array.wrapped_fill([x1, y1], [x2, y2], new_val);
+	}
+
+	/// Fills a rectangular area of this 2D array with a given value, ignoring any coordinates
+	/// that go out of bounds.
+	/// # Parameters
+	/// * **x1** - the first x dimension coordinate (inclusive)
+	/// * **y1** - the first y dimension coordinate (inclusive)
+	/// * **x2** - the second x dimension coordinate (exclusive)
+	/// * **y2** - the second y dimension coordinate (exclusive)
+	/// * **new_val** - value to store in the 2D array in the bounding box defined by
+	/// (x1, y1) -> (x2, y2)
+	pub fn bounded_fill(&mut self, x1: isize, y1: isize, x2: isize, y2: isize, new_val: impl Borrow<T>) {
+		self.array.bounded_fill([x1, y1], [x2, y2], new_val);
+	}
+
+	/// Creates a new sparse 2D array with the same size and values as a dense *ZArray2D*,
+	/// allocating only the data patches that contain more than one value
+	/// # Parameters
+	/// * **array** - the dense array to copy
+	/// * **background** - the background value of the new sparse array
+	/// # Returns
+	/// Returns a new *ZSparseArray2D* with the values of the dense array
+	pub fn from_dense(array: &ZArray2D<T, E>, background: T) -> ZSparseArray2D<T, E> {
+		return ZSparseArray2D { array: ZSparseArrayND::from_dense(array.as_nd(), background) };
+	}
+
+	/// Copies this sparse 2D array into a dense *ZArray2D* of the same size
+	/// # Returns
+	/// Returns a new *ZArray2D* with the values of this array
+	pub fn to_dense(&self) -> ZArray2D<T, E> {
+		return self.array.to_dense().into();
+	}
+}
+
+/// Iterator that iterates through a sparse 2D array, one data patch at a time
+pub struct ZSparseArray2DIterator<'a, T, const E: usize = DEFAULT_PATCH_EDGE> {
+	iter: ZSparseArrayNDIterator<'a, T, 2, E>
+}
+
+impl<'a, T, const E: usize> Iterator for ZSparseArray2DIterator<'a, T, E> {
+	type Item = ZArray2DIteratorItem<'a, T>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		let item = self.iter.next()?;
+		Some(ZArray2DIteratorItem{x: item.coord[0], y: item.coord[1], value: item.value})
+	}
+}
+
+/// This is primary struct for sparse z-indexed 3D arrays. Create new instances with
+/// ZSparseArray3D::new(x_size, y_size, z_size, background_value)
+///
+/// *ZSparseArray3D* is a typed front-end for a 3-dimensional [ZSparseArrayND], and it can be
+/// converted to and from a *ZSparseArrayND<T, 3, E>* with `From`/`Into`.
+#[derive(Debug, Clone)]
+pub struct ZSparseArray3D<T, const E: usize = DEFAULT_PATCH_EDGE> {
+	array: ZSparseArrayND<T, 3, E>
+}
+
+impl<T, const E: usize> From<ZSparseArrayND<T, 3, E>> for ZSparseArray3D<T, E> {
+	fn from(array: ZSparseArrayND<T, 3, E>) -> Self {
+		return ZSparseArray3D { array };
+	}
+}
+
+impl<T, const E: usize> From<ZSparseArray3D<T, E>> for ZSparseArrayND<T, 3, E> {
+	fn from(array: ZSparseArray3D<T, E>) -> Self {
+		return array.array;
+	}
+}
+
+impl<T> ZSparseArray3D<T> {
+	/// Create a sparse Z-index 3D array in which every value is the provided background value.
+	/// No data patches are allocated until a different value is set.
+	/// # Parameters
+	/// * **xsize** - size of this 3D array in the X dimension
+	/// * **ysize** - size of this 3D array in the Y dimension
+	/// * **zsize** - size of this 3D array in the Z dimension
+	/// * **background** - the value of every coordinate that has not been set to something
+	/// else
+	/// # Returns
+	/// Returns an initialized *ZSparseArray3D* struct
+	pub fn new(xsize: usize, ysize: usize, zsize: usize, background: T) -> ZSparseArray3D<T> {
+		return ZSparseArray3D { array: ZSparseArrayND::new([xsize, ysize, zsize], background) };
+	}
+}
+
+impl<T, const E: usize> ZSparseArray3D<T, E> {
+	/// Gets the 3-dimensional *ZSparseArrayND* that holds the data of this 3D array
+	/// # Returns
+	/// Returns a reference to the *ZSparseArrayND*, where the dimensions are X, Y, Z
+	pub fn as_nd(&self) -> &ZSparseArrayND<T, 3, E> {
+		return &self.array;
+	}
+
+	/// Gets the (x, y, z) size of this 3D array
+	/// # Returns
+	/// Returns a tuple of (xsize, ysize, zsize) for this 3D array
+	pub fn dimensions(&self) -> (usize, usize, usize) {
+		let [xsize, ysize, zsize] = self.array.dimensions();
+		return (xsize, ysize, zsize);
+	}
+
+	/// Gets the X-dimension size of this 3D array
+	/// # Returns
+	/// Returns the size in the X dimension
+	pub fn xsize(&self) -> usize {
+		return self.array.dimensions()[0];
+	}
+
+	/// Gets the Y-dimension size of this 3D array
+	/// # Returns
+	/// Returns the size in the Y dimension
+	pub fn ysize(&self) -> usize {
+		return self.array.dimensions()[1];
+	}
+
+	/// Gets the Z-dimension size of this 3D array
+	/// # Returns
+	/// Returns the size in the Z dimension
+	pub fn zsize(&self) -> usize {
+		return self.array.dimensions()[2];
+	}
+
+	/// Gets the background value, which is the value of every coordinate that has not been set
+	/// to something else
+	/// # Returns
+	/// Returns a reference to the background value
+	pub fn background(&self) -> &T {
+		return self.array.background();
+	}
+
+	/// Gets the number of data patches that are currently allocated (ie that contain at least
+	/// two different values)
+	/// # Returns
+	/// Returns the number of allocated data patches
+	pub fn allocated_patch_count(&self) -> usize {
+		return self.array.allocated_patch_count();
+	}
+
+	/// Gets a value from the 3D array, or returns a *LookUpError* if the provided coordinate
+	/// is out of bounds. If you are using a default value for out-of-bounds coordinates,
+	/// then you should use the *bounded_get(x, y, z)* method instead. If you want access to
+	/// wrap-around (eg (-2, 0, 0) equivalent to (xsize-2,0,0)), then use the
+	/// *wrapped_get(x, y, z)* method.
+	/// # Parameters
+	/// * **x** - x dimension coordinate
+	/// * **y** - y dimension coordinate
+	/// * **z** - z dimension coordinate
+	/// # Returns
+	/// Returns a Result type that holds either the returned data value (as a reference) from
+	/// the 3D array, or a *LookUpError* signalling that the coordinate is out of bounds
+	pub fn get(&self, x: usize, y: usize, z: usize) -> Result<&T, LookUpError> {
+		return self.array.get([x, y, z]);
+	}
+
+	/// Gets a value from the 3D array, wrapping around the X, Y, and Z axese when the
+	/// coordinates are negative or outside the size of this 3D array. Good for when you want
+	/// tiling behavior.
+	/// # Parameters
+	/// * **x** - x dimension coordinate
+	/// * **y** - y dimension coordinate
+	/// * **z** - z dimension coordinate
+	/// # Returns
+	/// Returns a reference to the data stored at the provided coordinate (wrapping the x, y,
+	/// and z dimensions)
+	pub fn wrapped_get(&self, x: isize, y: isize, z: isize) -> &T {
+		return self.array.wrapped_get([x, y, z]);
+	}
+
+	/// Gets a value from the 3D array as an Option that is None if the coordinate
+	/// is out of bounds.
+	/// # Parameters
+	/// * **x** - x dimension coordinate
+	/// * **y** - y dimension coordinate
+	/// * **z** - z dimension coordinate
+	/// # Returns
+	/// Returns an Option type that holds either the returned data value (as a reference) from
+	/// the 3D array, or *None* signalling that the coordinate is out of bounds (which can be
+	/// combined with .unwrap_or(default_value) to implement an out-of-bounds default)
+	pub fn bounded_get(&self, x: isize, y: isize, z: isize) -> Option<&T> {
+		return self.array.bounded_get([x, y, z]);
+	}
+
+	/// Iterates through all values of this 3D array, one data patch at a time. The values of
+	/// the unallocated data patches are their uniform value (usually the background value).
+	/// # Returns
+	/// Returns an iterator of *ZArray3DIteratorItem* structs (x, y, z, and value)
+	pub fn iter(&self) -> ZSparseArray3DIterator<'_, T, E> {
+		return ZSparseArray3DIterator { iter: self.array.iter() };
+	}
+
+	/// Iterates through the values of this 3D array, skipping the data patches that only
+	/// hold the background value (see [ZSparseArrayND::iter_non_background()])
+	/// # Returns
+	/// Returns an iterator of *ZArray3DIteratorItem* structs (x, y, z, and value)
+	pub fn iter_non_background(&self) -> ZSparseArray3DIterator<'_, T, E> {
+		return ZSparseArray3DIterator { iter: self.array.iter_non_background() };
+	}
+}
+
+impl<T, const E: usize> ZSparseArray3D<T, E> where T: Clone + PartialEq {
+	/// Sets a value in the 3D array, or returns a *LookUpError* if the provided coordinate
+	/// is out of bounds. If you want out-of-bound coordinates to result in a no-op, then use
+	/// the *bounded_set(x, y, z, val)* method instead. If you want access to wrap-around (eg
+	/// (-2, 0, 0) equivalent to (xsize-2,0,0)), then use the *wrapped_set(x, y, z, val)*
+	/// method.
+	/// # Parameters
+	/// * **x** - x dimension coordinate
+	/// * **y** - y dimension coordinate
+	/// * **z** - z dimension coordinate
+	/// * **new_val** - value to store in the 3D array at (x, y, z)
+	/// # Returns
+	/// Returns a Result type that is either empty or a *LookUpError* signalling that the
+	/// coordinate is out of bounds
+	pub fn set(&mut self, x: usize, y: usize, z: usize, new_val: T) -> Result<(), LookUpError> {
+		return self.array.set([x, y, z], new_val);
+	}
+
+	/// Sets a value in the 3D array at the provided coordinate, wrapping the X, Y, and Z axese
+	/// if the coordinate is negative or out of bounds.
+	/// # Parameters
+	/// * **x** - x dimension coordinate
+	/// * **y** - y dimension coordinate
+	/// * **z** - z dimension coordinate
+	/// * **new_val** - value to store in the 3D array at (x, y, z), wrapping around the x, y,
+	/// and z dimensions
+	pub fn wrapped_set(&mut self, x: isize, y: isize, z: isize, new_val: T) {
+		self.array.wrapped_set([x, y, z], new_val);
+	}
+
+	/// Sets a value in the 3D array if and only if the provided coordinate is in bounds.
+	/// Otherwise this method does nothing if the coordiante is out of bounds.
+	/// # Parameters
+	/// * **x** - x dimension coordinate
+	/// * **y** - y dimension coordinate
+	/// * **z** - z dimension coordinate
+	/// * **new_val** - value to store in the 3D array at (x, y, z)
+	pub fn bounded_set(&mut self, x: isize, y: isize, z: isize, new_val: T) {
+		self.array.bounded_set([x, y, z], new_val);
+	}
+
+	/// Fills a box-shaped region of this 3D array with a given value, or returns a
+	/// *LookUpError* if the provided coordinates go out of bounds.
+	/// # Parameters
+	/// * **x1** - the first x dimension coordinate (inclusive)
+	/// * **y1** - the first y dimension coordinate (inclusive)
+	/// * **z1** - the first z dimension coordinate (inclusive)
+	/// * **x2** - the second x dimension coordinate (exclusive)
+	/// * **y2** - the second y dimension coordinate (exclusive)
+	/// * **z2** - the second z dimension coordinate (exclusive)
+	/// * **new_val** - value to store in the 3D array in the bounding box defined by
+	/// (x1, y1, z1) -> (x2, y2, z2)
+	/// # Returns
+	/// Returns a Result type that is either empty or a *LookUpError* signalling that a
+	/// coordinate is out of bounds
+	pub fn fill(&mut self, x1: usize, y1: usize, z1: usize, x2: usize, y2: usize, z2: usize,
+				new_val: impl Borrow<T>) -> Result<(), LookUpError> {
+		return self.array.fill([x1, y1, z1], [x2, y2, z2], new_val);
+	}
+
+	/// Fills a box-shaped region of this 3D array with a given value, wrapping the axese when
+	/// coordinates go out of bounds.
+	/// # Parameters
+	/// * **x1** - the first x dimension coordinate (inclusive)
+	/// * **y1** - the first y dimension coordinate (inclusive)
+	/// * **z1** - the first z dimension coordinate (inclusive)
+	/// * **x2** - the second x dimension coordinate (exclusive)
+	/// * **y2** - the second y dimension coordinate (exclusive)
+	/// * **z2** - the second z dimension coordinate (exclusive)
+	/// * **new_val** - value to store in the 3D array in the bounding box defined by
+	/// (x1, y1, z1) -> (x2, y2, z2) with wrapped axese
+	pub fn wrapped_fill(&mut self, x1: isize, y1: isize, z1: isize, x2: isize, y2: isize, z2: isize,
+						new_val: impl Borrow<T>) {
+		self.array.wrapped_fill([x1, y1, z1], [x2, y2, z2], new_val);
+	}
+
+	/// Fills a box-shaped region of this 3D array with a given value, ignoring any coordinates
+	/// that go out of bounds.
+	/// # Parameters
+	/// * **x1** - the first x dimension coordinate (inclusive)
+	/// * **y1** - the first y dimension coordinate (inclusive)
+	/// * **z1** - the first z dimension coordinate (inclusive)
+	/// * **x2** - the second x dimension coordinate (exclusive)
+	/// * **y2** - the second y dimension coordinate (exclusive)
+	/// * **z2** - the second z dimension coordinate (exclusive)
+	/// * **new_val** - value to store in the 3D array in the bounding box defined by
+	/// (x1, y1, z1) -> (x2, y2, z2)
+	pub fn bounded_fill(&mut self, x1: isize, y1: isize, z1: isize, x2: isize, y2: isize, z2: isize,
+						new_val: impl Borrow<T>) {
+		self.array.bounded_fill([x1, y1, z1], [x2, y2, z2], new_val);
+	}
+
+	/// Creates a new sparse 3D array with the same size and values as a dense *ZArray3D*,
+	/// allocating only the data patches that contain more than one value
+	/// # Parameters
+	/// * **array** - the dense array to copy
+	/// * **background** - the background value of the new sparse array
+	/// # Returns
+	/// Returns a new *ZSparseArray3D* with the values of the dense array
+	pub fn from_dense(array: &ZArray3D<T, E>, background: T) -> ZSparseArray3D<T, E> {
+		return ZSparseArray3D { array: ZSparseArrayND::from_dense(array.as_nd(), background) };
+	}
+
+	/// Copies this sparse 3D array into a dense *ZArray3D* of the same size
+	/// # Returns
+	/// Returns a new *ZArray3D* with the values of this array
+	pub fn to_dense(&self) -> ZArray3D<T, E> {
+		return self.array.to_dense().into();
+	}
+}
+
+/// Iterator that iterates through a sparse 3D array, one data patch at a time
+pub struct ZSparseArray3DIterator<'a, T, const E: usize = DEFAULT_PATCH_EDGE> {
+	iter: ZSparseArrayNDIterator<'a, T, 3, E>
+}
+
+impl<'a, T, const E: usize> Iterator for ZSparseArray3DIterator<'a, T, E> {
+	type Item = ZArray3DIteratorItem<'a, T>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		let item = self.iter.next()?;
+		Some(ZArray3DIteratorItem{x: item.coord[0], y: item.coord[1], z: item.coord[2], value: item.value})
+	}
+}
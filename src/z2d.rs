@@ -14,8 +14,8 @@
 //! use zarray::z2d::ZArray2D;
 //! let w = 800;
 //! let h = 600;
-//! let mut input = ZArray2D::new(w, h, 0i32);
-//! let mut blurred = ZArray2D::new(w, h, 0i32);
+//! let mut input = ZArray2D::<_>::new(w, h, 0i32);
+//! let mut blurred = ZArray2D::<_>::new(w, h, 0i32);
 //! for y in 0..h {
 //!   for x in 0..w {
 //!     let random_number = (((x*1009+1031)*y*1013+1051) % 10) as i32;
@@ -35,148 +35,161 @@
 //!   }
 //! }
 //! ```
+//! The same blur can be done faster (and with the edges handled) with *convolve(...)*, which
+//! processes the array one data patch at a time:
+//! ```
+//! use zarray::EdgeMode;
+//! use zarray::z2d::ZArray2D;
+//! let (w, h) = (800, 600);
+//! let input = ZArray2D::<_>::new_with_constructor(w, h, |(x, y)| (((x*1009+1031)*y*1013+1051) % 10) as i32);
+//! let mut blurred = ZArray2D::<_>::new(w, h, 0i32);
+//! input.convolve(&mut blurred, &[1; 25], &EdgeMode::Clamp);
+//! blurred.transform(|_, sum| sum / 25);
+//! ```
 // Z-order indexing in 2 dimensions
 
-use core::hash::{Hash, Hasher};
+use alloc::vec::Vec;
 use core::borrow::Borrow;
-use core::marker::PhantomData;
-use array_init::array_init;
-use crate::LookUpError;
+use core::ops::{Add, Index, IndexMut, Mul, RangeBounds};
+use core::slice::Iter;
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+use crate::{clamp_range, signed_range, AxisTopology, BuildError, EdgeMode, ErrorCoord, LookUpError};
+use crate::znd::{StencilView, ZArrayND, ZArrayNDBoundedRegionIterator, ZArrayNDIntoIterator, ZArrayNDIterator,
+	ZArrayNDIteratorMut, ZArrayNDRasterIterator, ZArrayNDRegionIterator, ZArrayNDRegionIteratorMut,
+	ZArrayNDSnapshot, ZArrayNDView, ZArrayNDViewIterator, ZArrayNDViewIteratorMut, ZArrayNDViewMut, DEFAULT_PATCH_EDGE, zorder_index};
 
-/// Private struct for holding an 8x8 data patch
-#[derive(Debug)]
-#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
-struct Patch<T> {
-	contents: [T; 64]
+/// This is primary struct for z-indexed 2D arrays. Create new instances with
+/// ZArray2D::<_>::new(x_size, y_size, initial_value)
+///
+/// *ZArray2D* is a typed front-end for a 2-dimensional [ZArrayND], which
+/// holds the data and implements the look-ups, and it can be converted to and from a
+/// *ZArrayND<T, 2, E>* with `From`/`Into`.
+///
+/// The optional parameter *E* is the length of the data patches in every dimension, which may
+/// be 4, 8 (the default), or 16 (see [ZArrayND]). The constructors of *ZArray2D* are generic
+/// over *E*, so the patch length is picked by the type, eg
+/// `let a = ZArray2D::<u64, 4>::new(width, height, 0u64);` (write `ZArray2D::<_>::new(...)`
+/// to use the default patch length when the type is not otherwise known)
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ZArray2D<T, const E: usize = DEFAULT_PATCH_EDGE> {
+	// for heap allocated data
+	array: ZArrayND<T, 2, E>,
 }
 
-impl<T> Copy for Patch<T> where T: Copy{}
-impl<T> Clone for Patch<T> where T: Clone{
-	fn clone(&self) -> Self {
-			Self{contents: self.contents.clone()}
+impl<T, const E: usize> From<ZArrayND<T, 2, E>> for ZArray2D<T, E> {
+	/// Wraps a 2-dimensional *ZArrayND* as a *ZArray2D*, where the first dimension is X and the
+	/// second dimension is Y
+	fn from(array: ZArrayND<T, 2, E>) -> Self {
+		ZArray2D { array }
 	}
 }
-impl<T> PartialEq for Patch<T> where T: PartialEq{
-	fn eq(&self, other: &Self) -> bool {
-			self.contents.eq(&other.contents)
+
+impl<T, const E: usize> From<ZArray2D<T, E>> for ZArrayND<T, 2, E> {
+	/// Unwraps a *ZArray2D* into the 2-dimensional *ZArrayND* that holds its data
+	fn from(array: ZArray2D<T, E>) -> Self {
+		array.array
 	}
 }
-impl<T> Eq for Patch<T> where T: Eq{}
-impl<T> Hash for Patch<T> where T: Hash{
-	fn hash<H: Hasher>(&self, state: &mut H) {
-		for item in &self.contents {
-			item.hash(state);
+
+impl<T, const E: usize> TryFrom<Vec<Vec<T>>> for ZArray2D<T, E> where T: Clone {
+	type Error = BuildError;
+	/// Moves the values of a *Vec* of rows (where the value of (x, y) is at `[y][x]`) into a
+	/// *ZArray2D*, returning a *BuildError::Ragged* if the rows do not all have the same length
+	fn try_from(rows: Vec<Vec<T>>) -> Result<Self, Self::Error> {
+		let width = rows.first().map_or(0, |row| row.len());
+		if let Some((y, row)) = rows.iter().enumerate().find(|(_, row)| row.len() != width) {
+			return Err(BuildError::Ragged { index: ErrorCoord::new(&[y]), expected: width, actual: row.len() });
 		}
+		let height = rows.len();
+		let values: Vec<T> = rows.into_iter().flatten().collect();
+		return Ok(ZArrayND::from_row_major_vec([width, height], values).into());
 	}
 }
 
-
-impl<T> Patch<T> {
-	/// data patch getter
-	/// # Parameters
-	/// * **x** - x coord (only lowest 3 bits are used, rest of bits are ignored)
-	/// * **y** - y coord (only lowest 3 bits are used, rest of bits are ignored)
-	/// # Returns
-	/// Returns a reference to the value stored in the patch at location (x & 0x07), (y & 0x07)
-	fn get(&self, x: usize, y: usize) -> &T {
-		// 3-bit x 3-bit
-		return &self.contents[zorder_4bit_to_8bit(x as u8 & 0x07, y as u8 & 0x07) as usize];
+impl<T, const E: usize> AsRef<ZArrayND<T, 2, E>> for ZArray2D<T, E> {
+	fn as_ref(&self) -> &ZArrayND<T, 2, E> {
+		&self.array
 	}
-	/// data patch setter
-	/// # Parameters
-	/// * **x** - x coord (only lowest 3 bits are used, rest of bits are ignored)
-	/// * **y** - y coord (only lowest 3 bits are used, rest of bits are ignored)
-	/// * **new_val** - value to set
-	fn set(&mut self, x: usize, y: usize, new_val: T) {
-		// 3-bit x 3-bit
-		let i = zorder_4bit_to_8bit(x as u8 & 0x07, y as u8 & 0x07) as usize;
-		//let old_val = &self.contents[i];
-		self.contents[i] = new_val;
-		//return old_val;
-	}
-}
-
-/// function for converting coordinate to index of data patch in the array of patches
-fn patch_index(x: usize, y: usize, pwidth: usize) -> usize {
-	return (x >> 3) + ((y >> 3) * (pwidth));
 }
 
-/// function for getting the coords represented by a patch
-fn patch_coords(pwidth: usize, pindex: usize) -> [(usize, usize); 64] {
-	let mut outbuffer = [(0usize, 0usize); 64];
-	let bx = (pindex % pwidth) << 3;
-	let by = (pindex / pwidth) << 3;
-	for i in 0..64 {
-		let bitmask = REVERSE_ZLUT[i];
-		let dx = bitmask & 0b00000111u8;
-		let dy = (bitmask >> 3u8) & 0b00000111u8;
-		outbuffer[i] = (bx + dx as usize, by + dy as usize);
+impl<T, const E: usize> AsMut<ZArrayND<T, 2, E>> for ZArray2D<T, E> {
+	fn as_mut(&mut self) -> &mut ZArrayND<T, 2, E> {
+		&mut self.array
 	}
-	return outbuffer;
 }
 
-/// This is primary struct for z-indexed 2D arrays. Create new instances with
-/// ZArray2D::new(x_size, y_size, initial_value)
-#[derive(Debug)]
-#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
-pub struct ZArray2D<T> {
-	// for heap allocated data
-	width: usize,
-	height: usize,
-	pwidth: usize,
-	patches: Vec<Patch<T>>,
-	_phantomdata: PhantomData<T>,
+impl<T, const E: usize> Index<(usize, usize)> for ZArray2D<T, E> {
+	type Output = T;
+	/// Gets a reference to the value at coordinate (x, y), panicking with the *LookUpError*
+	/// message if the coordinate is out of bounds
+	fn index(&self, index: (usize, usize)) -> &Self::Output {
+		&self.array[[index.0, index.1]]
+	}
 }
-
-impl<T> Clone for ZArray2D<T> where T: Clone{
-	fn clone(&self) -> Self {
-			Self{
-				width: self.width,
-				height: self.height,
-				pwidth: self.pwidth,
-				patches: self.patches.clone(),
-				_phantomdata: self._phantomdata
-			}
+impl<T, const E: usize> IndexMut<(usize, usize)> for ZArray2D<T, E> {
+	/// Gets a mutable reference to the value at coordinate (x, y), panicking with the
+	/// *LookUpError* message if the coordinate is out of bounds
+	fn index_mut(&mut self, index: (usize, usize)) -> &mut Self::Output {
+		&mut self.array[[index.0, index.1]]
 	}
 }
-impl<T> PartialEq for ZArray2D<T> where T: PartialEq{
-	fn eq(&self, other: &Self) -> bool {
-		self.width == other.width
-		&& self.height == other.height
-		&& self.pwidth == other.pwidth
-		&& self.patches == other.patches
+impl<T, const E: usize> Index<[usize; 2]> for ZArray2D<T, E> {
+	type Output = T;
+	/// Same as indexing with an (x, y) tuple
+	fn index(&self, index: [usize; 2]) -> &Self::Output {
+		&self.array[index]
 	}
 }
-impl<T> Eq for ZArray2D<T> where T: Eq{}
-impl<T> Hash for ZArray2D<T> where T: Hash{
-	fn hash<H: Hasher>(&self, state: &mut H) {
-		for patch in &self.patches {
-			patch.hash(state);
-		}
+impl<T, const E: usize> IndexMut<[usize; 2]> for ZArray2D<T, E> {
+	/// Same as indexing with an (x, y) tuple
+	fn index_mut(&mut self, index: [usize; 2]) -> &mut Self::Output {
+		&mut self.array[index]
 	}
 }
 
-impl<T> ZArray2D<T> where T: Default {
+impl<T, const E: usize> ZArray2D<T, E> where T: Default {
 	/// Create a Z-index 2D array of values, initially filled with the default values
 	/// # Parameters
 	/// * **width** - size of this 2D array in the X dimension
 	/// * **height** - size of this 2D array in the Y dimension
 	/// # Returns
 	/// Returns an initialized *ZArray2D* struct filled with default values
-	pub fn new_with_default(width: usize, height: usize) -> ZArray2D<T> {
-		let pwidth = ((width-1) >> 3) + 1;
-		let pheight = ((height-1) >> 3) + 1;
-		let patch_count = pwidth * pheight;
-		let mut p = Vec::with_capacity(patch_count);
-		for _ in 0..patch_count {
-			let default_contents: [T; 64] = array_init(|_|T::default());
-			p.push(Patch { contents: default_contents });
-		}
-		return ZArray2D { width, height, pwidth, patches: p, _phantomdata: PhantomData };
+	pub fn new_with_default(width: usize, height: usize) -> ZArray2D<T, E> {
+		return ZArrayND::new_with_default([width, height]).into();
+	}
+
+	/// Create a Z-index 2D array from an iterator of values in row-major order (ie X changes
+	/// fastest, then Y). The iterator must provide exactly *width* x *height* values.
+	/// # Parameters
+	/// * **width** - size of this 2D array in the X dimension
+	/// * **height** - size of this 2D array in the Y dimension
+	/// * **values** - iterator of the values to store, in row-major order
+	/// # Returns
+	/// Returns a Result type that holds either the new *ZArray2D* or a
+	/// *BuildError::SizeMismatch* if the number of values does not match the dimensions
+	pub fn from_iter_row_major(width: usize, height: usize, values: impl IntoIterator<Item=T>)
+		-> Result<ZArray2D<T, E>, BuildError> {
+		return ZArrayND::from_iter_row_major([width, height], values).map(ZArray2D::from);
+	}
+
+	/// Create a Z-index 2D array from an iterator of coordinate and value pairs, in any order.
+	/// The iterator must provide exactly one value for every coordinate of the array.
+	/// # Parameters
+	/// * **width** - size of this 2D array in the X dimension
+	/// * **height** - size of this 2D array in the Y dimension
+	/// * **values** - iterator of ((x, y), value) pairs
+	/// # Returns
+	/// Returns a Result type that holds either the new *ZArray2D* or a *BuildError* if a
+	/// coordinate is out of bounds, provided twice, or missing
+	pub fn from_iter_coords(width: usize, height: usize, values: impl IntoIterator<Item=((usize, usize), T)>)
+		-> Result<ZArray2D<T, E>, BuildError> {
+		let values = values.into_iter().map(|((x, y), v)| ([x, y], v));
+		return ZArrayND::from_iter_coords([width, height], values).map(ZArray2D::from);
 	}
 }
 
-impl<T> ZArray2D<T> where T: Copy {
+impl<T, const E: usize> ZArray2D<T, E> where T: Copy {
 	 /// Create a Z-index 2D array of values, initially filled with the provided default value
 	/// # Parameters
 	/// * **width** - size of this 2D array in the X dimension
@@ -184,19 +197,28 @@ impl<T> ZArray2D<T> where T: Copy {
 	/// * **default_val** - initial fill value (it must implement the Copy trait)
 	/// # Returns
 	/// Returns an initialized *ZArray2D* struct filled with *default_val*
-	pub fn new(width: usize, height: usize, default_val: T) -> ZArray2D<T> {
-		let pwidth = ((width-1) >> 3) + 1;
-		let pheight = ((height-1) >> 3) + 1;
-		let patch_count = pwidth * pheight;
-		let mut p = Vec::with_capacity(patch_count);
-		for _ in 0..patch_count {
-			p.push(Patch { contents: [default_val; 64] });
-		}
-		return ZArray2D { width, height, pwidth, patches: p, _phantomdata: PhantomData };
+	pub fn new(width: usize, height: usize, default_val: T) -> ZArray2D<T, E> {
+		return ZArrayND::new([width, height], default_val).into();
+	}
+}
+
+impl<T, const E: usize> ZArray2D<T, E> where T: Clone {
+	/// Create a Z-index 2D array from a slice of values in row-major order (X changes
+	/// fastest, then Y), copying the values one data patch at a time
+	/// # Parameters
+	/// * **width** - size of this 2D array in the X dimension
+	/// * **height** - size of this 2D array in the Y dimension
+	/// * **values** - the values in row-major order (the value of (x, y) is at index
+	/// x + width * y)
+	/// # Returns
+	/// Returns a Result type that holds either the new *ZArray2D* or a
+	/// *BuildError::SizeMismatch* if the number of values is not width * height
+	pub fn from_row_major(width: usize, height: usize, values: &[T]) -> Result<ZArray2D<T, E>, BuildError> {
+		return ZArrayND::from_row_major([width, height], values).map(ZArray2D::from);
 	}
 }
 
-impl<T> ZArray2D<T> where T: Clone {
+impl<T, const E: usize> ZArray2D<T, E> where T: Clone {
 	/// Fills a region of this 2D array with a given value, or returns a *LookUpError* if the
 	/// provided coordinates go out of bounds. If you just want to ignore any
 	/// out-of-bounds coordinates, then you should use the *bounded_fill(x1, y1, x2, y2)*
@@ -214,12 +236,7 @@ impl<T> ZArray2D<T> where T: Clone {
 	/// coordinate is out of bounds
 	pub fn fill(&mut self, x1: usize, y1: usize, x2: usize, y2: usize, new_val: impl Borrow<T>)
 				-> Result<(), LookUpError> {
-		for y in y1..y2 {
-			for x in x1..x2 {
-				self.set(x, y, new_val.borrow().clone())?;
-			}
-		}
-		Ok(())
+		return self.array.fill([x1, y1], [x2, y2], new_val);
 	}
 
 	/// Fills a region of this 2D array with a given value, wrapping the axese when
@@ -232,15 +249,13 @@ impl<T> ZArray2D<T> where T: Clone {
 	/// * **new_val** - value to store in the 2D array in the bounding box defined by
 	/// (x1, y1) -> (x2, y2) with wrapped axese
 	pub fn wrapped_fill(&mut self, x1: isize, y1: isize, x2: isize, y2: isize, new_val: impl Borrow<T>) {
-		for y in y1..y2 {
-			for x in x1..x2 {
-				self.wrapped_set(x, y, new_val.borrow().clone());
-			}
-		}
+		self.array.wrapped_fill([x1, y1], [x2, y2], new_val);
 	}
 
 	/// Fills a region of this 2D array with a given value, ignoring any
-	/// coordinates that go out of bounds.
+	/// coordinates that go out of bounds. Coordinates outside of a wrapped or clamped axis are
+	/// handled by the topology of this 2D array (see *set_topology(x_axis, y_axis)*), so on a
+	/// cylindrical map the region can cross the edge of the wrapped axis.
 	/// # Parameters
 	/// * **x1** - the first x dimension coordinate (inclusive)
 	/// * **y1** - the first y dimension coordinate (inclusive)
@@ -249,15 +264,117 @@ impl<T> ZArray2D<T> where T: Clone {
 	/// * **new_val** - value to store in the 2D array in the bounding box defined by
 	/// (x1, y1) -> (x2, y2)
 	pub fn bounded_fill(&mut self, x1: isize, y1: isize, x2: isize, y2: isize, new_val: impl Borrow<T>) {
-		for y in y1..y2 {
-			for x in x1..x2 {
-				self.bounded_set(x, y, new_val.borrow().clone());
-			}
-		}
+		self.array.bounded_fill([x1, y1], [x2, y2], new_val);
+	}
+
+	/// Fills a region of this 2D array with a given value, using the provided edge mode to
+	/// handle coordinates that are negative or outside the size of this 2D array.
+	/// # Parameters
+	/// * **x1** - the first x dimension coordinate (inclusive)
+	/// * **y1** - the first y dimension coordinate (inclusive)
+	/// * **x2** - the second x dimension coordinate (exclusive)
+	/// * **y2** - the second y dimension coordinate (exclusive)
+	/// * **edge_mode** - how to handle out-of-bounds coordinates
+	/// * **new_val** - value to store in the 2D array in the bounding box defined by
+	/// (x1, y1) -> (x2, y2)
+	/// # Returns
+	/// Returns a Result type that is either empty or a *LookUpError* if a coordinate is out
+	/// of bounds and the edge mode is *EdgeMode::Error*
+	pub fn sample_fill(&mut self, x1: isize, y1: isize, x2: isize, y2: isize, edge_mode: &EdgeMode<T>,
+		new_val: impl Borrow<T>) -> Result<(), LookUpError> {
+		return self.array.sample_fill([x1, y1], [x2, y2], edge_mode, new_val);
+	}
+
+	/// Changes the size of this 2D array, keeping the existing values at their coordinates.
+	/// Values outside the new size are dropped and new cells are set to the fill value. Whole
+	/// data patches are copied, so this is much faster than copying one value at a time.
+	/// # Parameters
+	/// * **new_width** - new x dimension size
+	/// * **new_height** - new y dimension size
+	/// * **fill** - value for the new cells
+	/// # Panics
+	/// Panics if the new size overflows *usize* (including the padding to whole data patches)
+	pub fn resize(&mut self, new_width: usize, new_height: usize, fill: impl Borrow<T>) {
+		self.array.resize([new_width, new_height], fill);
+	}
+
+	/// Cuts this 2D array down to a rectangle, such that (x, y) becomes (0, 0). Whole data
+	/// patches are copied if x and y are multiples of the patch length (eg 8), otherwise the
+	/// values are re-packed.
+	/// # Parameters
+	/// * **x** - x dimension coordinate of the first corner of the rectangle to keep
+	/// * **y** - y dimension coordinate of the first corner of the rectangle to keep
+	/// * **width** - x dimension size of the rectangle
+	/// * **height** - y dimension size of the rectangle
+	/// # Returns
+	/// Returns a Result type that is either empty or a *LookUpError* signalling that the
+	/// rectangle goes out of bounds (in which case this 2D array is unchanged)
+	pub fn crop(&mut self, x: usize, y: usize, width: usize, height: usize) -> Result<(), LookUpError> {
+		return self.array.crop([x, y], [width, height]);
+	}
+
+	/// Adds cells around the edges of this 2D array, moving the existing values by (left, top).
+	/// Whole data patches are copied if left and top are multiples of the patch length (eg 8),
+	/// otherwise the values are re-packed.
+	/// # Parameters
+	/// * **left** - number of columns to add before x = 0
+	/// * **right** - number of columns to add after the last x coordinate
+	/// * **top** - number of rows to add before y = 0
+	/// * **bottom** - number of rows to add after the last y coordinate
+	/// * **fill** - value for the new cells
+	/// # Panics
+	/// Panics if the new size overflows *usize* (including the padding to whole data patches)
+	pub fn pad(&mut self, left: usize, right: usize, top: usize, bottom: usize, fill: impl Borrow<T>) {
+		self.array.pad([left, top], [right, bottom], fill);
+	}
+
+	/// Takes a snapshot of this 2D array, which copies the values into reference-counted data
+	/// patches (see [ZArrayND::snapshot()])
+	/// # Returns
+	/// Returns a snapshot of the values of this array
+	pub fn snapshot(&self) -> ZArrayNDSnapshot<T, 2, E> {
+		return self.array.snapshot();
+	}
+
+	/// Takes a snapshot of this 2D array that shares the data patches that have not changed
+	/// since a previous snapshot of this array, so it only copies the patches that changed
+	/// (see [ZArrayND::snapshot_since()])
+	/// # Parameters
+	/// * **previous** - an earlier snapshot of this array
+	/// # Returns
+	/// Returns a snapshot of the values of this array
+	pub fn snapshot_since(&self, previous: &ZArrayNDSnapshot<T, 2, E>) -> ZArrayNDSnapshot<T, 2, E> {
+		return self.array.snapshot_since(previous);
+	}
+
+	/// Sets the values of this 2D array back to those of a snapshot, copying only the data
+	/// patches that changed since the snapshot was taken (see [ZArrayND::restore()])
+	/// # Parameters
+	/// * **snapshot** - the snapshot to restore
+	pub fn restore(&mut self, snapshot: &ZArrayNDSnapshot<T, 2, E>) {
+		self.array.restore(snapshot);
+	}
+
+	/// Copies the values of this 2D array into a *Vec* in row-major order (X changes fastest,
+	/// then Y), one row of data patches at a time
+	/// # Returns
+	/// Returns a *Vec* where the value of (x, y) is at index x + width * y
+	pub fn to_row_major(&self) -> Vec<T> {
+		return self.array.to_row_major();
+	}
+
+	/// Copies the values of this 2D array into a *Vec* of rows
+	/// # Returns
+	/// Returns a *Vec* of *height* rows of *width* values, where the value of (x, y) is at
+	/// `[y][x]`
+	pub fn to_vec_of_vecs(&self) -> Vec<Vec<T>> {
+		let (width, height) = self.dimensions();
+		let mut values = self.to_row_major().into_iter();
+		return (0..height).map(|_| values.by_ref().take(width).collect()).collect();
 	}
 }
 
-impl<T> ZArray2D<T> {
+impl<T, const E: usize> ZArray2D<T, E> {
 	/// Create a Z-index 2D array of values, initially filled with the provided constructor function.
 	/// Note that the constructor function may be called for coordinates that are outside the
 	/// requested dimensions in order to initialize memory in 8x8 blocks. To avoid this, use only
@@ -268,31 +385,32 @@ impl<T> ZArray2D<T> {
 	/// * **constructor** - function which takes in the (X,Y) coords as a tuple and returns a value of type T
 	/// # Returns
 	/// Returns an initialized *ZArray2D* struct filled with *default_val*
-	pub fn new_with_constructor(width: usize, height: usize, constructor: impl Fn((usize, usize)) -> T) -> ZArray2D<T> {
-		let pwidth = ((width-1) >> 3) + 1;
-		let pheight = ((height-1) >> 3) + 1;
-		let patch_count = pwidth * pheight;
-		let mut p = Vec::with_capacity(patch_count);
-		for pindex in 0..patch_count {
-			let lookup_table = patch_coords(pwidth, pindex);
-			let initial_contents: [T; 64] = array_init(|i| constructor(lookup_table[i]));
-			p.push(Patch { contents: initial_contents });
-		}
-		return ZArray2D { width, height, pwidth, patches: p, _phantomdata: PhantomData };
+	pub fn new_with_constructor(width: usize, height: usize, constructor: impl Fn((usize, usize)) -> T) -> ZArray2D<T, E> {
+		return ZArrayND::new_with_constructor([width, height], |[x, y]| constructor((x, y))).into();
+	}
+}
+
+impl<T, const E: usize> ZArray2D<T, E> {
+	/// Gets the 2-dimensional *ZArrayND* that holds the data of this 2D array
+	/// # Returns
+	/// Returns a reference to the *ZArrayND*, where the first dimension is X
+	pub fn as_nd(&self) -> &ZArrayND<T, 2, E> {
+		return &self.array;
 	}
 
 	/// Gets the (x, y) size of this 2D array
 	/// # Returns
 	/// Returns a tuple of (width, height) for this 2D array
 	pub fn dimensions(&self) -> (usize, usize) {
-		return (self.width, self.height);
+		let [width, height] = self.array.dimensions();
+		return (width, height);
 	}
 
 	/// Gets the X-dimension size (aka width) of this 2D array
 	/// # Returns
 	/// Returns the size in the X dimension
 	pub fn xsize(&self) -> usize {
-		return self.width;
+		return self.array.dimensions()[0];
 	}
 
 
@@ -307,7 +425,7 @@ impl<T> ZArray2D<T> {
 	/// # Returns
 	/// Returns the size in the Y dimension
 	pub fn ysize(&self) -> usize {
-		return self.height;
+		return self.array.dimensions()[1];
 	}
 
 	/// Alias for `ysize()`
@@ -317,9 +435,45 @@ impl<T> ZArray2D<T> {
 		return self.ysize();
 	}
 
+	/// Sets the topology of this 2D array, which determines how each axis handles
+	/// out-of-bounds coordinates (eg wrapping X but bounding Y makes a cylindrical map).
+	/// *get(x, y)*, *set(x, y, val)*, the *bounded_get(x, y)* family of methods,
+	/// *bounded_iter_region(...)*, and the methods that take an *EdgeMode* (such as
+	/// *sample(...)*, *stencil(...)* and *neighbors(...)*) all wrap or clamp coordinates on
+	/// the wrapped or clamped axes. The edge mode only applies to the bounded axes. Both axes
+	/// are *AxisTopology::Bounded* by default.
+	/// # Parameters
+	/// * **x_axis** - topology of the X axis
+	/// * **y_axis** - topology of the Y axis
+	pub fn set_topology(&mut self, x_axis: AxisTopology, y_axis: AxisTopology) {
+		self.array.set_topology([x_axis, y_axis]);
+	}
+
+	/// Same as *set_topology(x_axis, y_axis)*, but consumes and returns this 2D array so that
+	/// it can be chained with a constructor
+	/// # Parameters
+	/// * **x_axis** - topology of the X axis
+	/// * **y_axis** - topology of the Y axis
+	/// # Returns
+	/// Returns this 2D array with the new topology
+	pub fn with_topology(mut self, x_axis: AxisTopology, y_axis: AxisTopology) -> ZArray2D<T, E> {
+		self.set_topology(x_axis, y_axis);
+		return self;
+	}
+
+	/// Gets the topology of this 2D array
+	/// # Returns
+	/// Returns a tuple of the (X axis, Y axis) topologies
+	pub fn topology(&self) -> (AxisTopology, AxisTopology) {
+		let [x_axis, y_axis] = self.array.topology();
+		return (x_axis, y_axis);
+	}
+
 	/// Gets a value from the 2D array, or returns a *LookUpError* if the provided coordinate
-	/// is out of bounds. If you are using a default value for out-of-bounds coordinates,
-	/// then you should use the *bounded_get(x, y)* method instead. If you want access to
+	/// is out of bounds. Coordinates past the end of a wrapped or clamped axis are handled by
+	/// the topology of this 2D array (see *set_topology(x_axis, y_axis)*). If you are using a
+	/// default value for out-of-bounds coordinates, then you should use the *bounded_get(x, y)*
+	/// method instead. If you want access to
 	/// wrap-around (eg (-2, 0) equivalent to (width-2,0)), then use the *wrapped_get(x, y)*
 	/// method.
 	/// # Parameters
@@ -329,15 +483,28 @@ impl<T> ZArray2D<T> {
 	/// Returns a Result type that holds either the returned data value (as a reference) from
 	/// the 2D array, or a *LookUpError* signalling that the coordinate is out of bounds
 	pub fn get(&self, x: usize, y: usize) -> Result<&T, LookUpError> {
-		if x < self.width && y < self.height {
-			Ok(self.patches[patch_index(x, y, self.pwidth)].get(x, y))
-		} else {
-			Err(LookUpError { coord: vec![x, y], bounds: vec![self.width, self.height] })
-		}
+		return self.array.get([x, y]);
+	}
+
+	/// Gets a mutable reference to a value in the 2D array, or returns a *LookUpError* if the
+	/// provided coordinate is out of bounds (coordinates past the end of a wrapped or clamped
+	/// axis are handled by the topology of this 2D array). If you are using a default value for
+	/// out-of-bounds coordinates, then you should use the *bounded_get_mut(x, y)* method
+	/// instead. If you want access to wrap-around (eg (-2, 0) equivalent to (width-2,0)), then
+	/// use the *wrapped_get_mut(x, y)* method.
+	/// # Parameters
+	/// * **x** - x dimension coordinate
+	/// * **y** - y dimension coordinate
+	/// # Returns
+	/// Returns a Result type that holds either a mutable reference to the data value in the
+	/// 2D array, or a *LookUpError* signalling that the coordinate is out of bounds
+	pub fn get_mut(&mut self, x: usize, y: usize) -> Result<&mut T, LookUpError> {
+		return self.array.get_mut([x, y]);
 	}
 
 	/// Sets a value in the 2D array, or returns a *LookUpError* if the provided coordinate
-	/// is out of bounds. If you want out-of-bound coordinates to result in a no-op, then use
+	/// is out of bounds (coordinates past the end of a wrapped or clamped axis are handled by
+	/// the topology of this 2D array). If you want out-of-bound coordinates to result in a no-op, then use
 	/// the *bounded_set(x, y, val)* method instead. If you want access to wrap-around (eg
 	/// (-2, 0) equivalent to (width-2,0)), then use the *wrapped_set(x, y, val)* method.
 	/// # Parameters
@@ -348,11 +515,7 @@ impl<T> ZArray2D<T> {
 	/// Returns a Result type that is either empty or a *LookUpError* signalling that the
 	/// coordinate is out of bounds
 	pub fn set(&mut self, x: usize, y: usize, new_val: T) -> Result<(), LookUpError> {
-		if x < self.width && y < self.height {
-			Ok(self.patches[patch_index(x, y, self.pwidth)].set(x, y, new_val))
-		} else {
-			Err(LookUpError { coord: vec![x, y], bounds: vec![self.width, self.height] })
-		}
+		return self.array.set([x, y], new_val);
 	}
 
 	/// Gets a value from the 2D array without bounds checking
@@ -362,7 +525,17 @@ impl<T> ZArray2D<T> {
 	/// # Returns
 	/// Returns a data value (as a reference) from the 2D array
 	pub fn get_unchecked(&self, x: usize, y: usize) -> &T {
-		return self.patches[patch_index(x, y, self.pwidth)].get(x, y);
+		return self.array.get_unchecked([x, y]);
+	}
+
+	/// Gets a mutable reference to a value in the 2D array without bounds checking
+	/// # Parameters
+	/// * **x** - x dimension coordinate
+	/// * **y** - y dimension coordinate
+	/// # Returns
+	/// Returns a mutable reference to the data value in the 2D array
+	pub fn get_unchecked_mut(&mut self, x: usize, y: usize) -> &mut T {
+		return self.array.get_unchecked_mut([x, y]);
 	}
 
 	/// Sets a value in the 2D array without bounds checking
@@ -371,7 +544,7 @@ impl<T> ZArray2D<T> {
 	/// * **y** - y dimension coordinate
 	/// * **new_val** - value to store in the 2D array at (x, y)
 	pub fn set_unchecked(&mut self, x: usize, y: usize, new_val: T) {
-		self.patches[patch_index(x, y, self.pwidth)].set(x, y, new_val);
+		self.array.set_unchecked([x, y], new_val);
 	}
 
 	/// Gets a value from the 2D array, wrapping around the X and Y axese when the coordinates
@@ -384,9 +557,19 @@ impl<T> ZArray2D<T> {
 	/// Returns a reference to the data stored at the provided coordinate (wrapping both x
 	/// and y dimensions)
 	pub fn wrapped_get(&self, x: isize, y: isize) -> &T {
-		let x = (self.width as isize + (x % self.width as isize)) as usize % self.width;
-		let y = (self.height as isize + (y % self.height as isize)) as usize % self.height;
-		return &self.patches[patch_index(x, y, self.pwidth)].get(x, y);
+		return self.array.wrapped_get([x, y]);
+	}
+
+	/// Gets a mutable reference to a value in the 2D array, wrapping around the X and Y axese
+	/// when the coordinates are negative or outside the size of this 2D array.
+	/// # Parameters
+	/// * **x** - x dimension coordinate
+	/// * **y** - y dimension coordinate
+	/// # Returns
+	/// Returns a mutable reference to the data stored at the provided coordinate (wrapping
+	/// both x and y dimensions)
+	pub fn wrapped_get_mut(&mut self, x: isize, y: isize) -> &mut T {
+		return self.array.wrapped_get_mut([x, y]);
 	}
 
 	/// Sets a value in the 2D array at the provided coordinate, wrapping the X and Y axese
@@ -397,13 +580,13 @@ impl<T> ZArray2D<T> {
 	/// * **new_val** - value to store in the 2D array at (x, y), wrapping around both the x
 	/// and y dimensions
 	pub fn wrapped_set(&mut self, x: isize, y: isize, new_val: T) {
-		let x = (self.width as isize + (x % self.width as isize)) as usize % self.width;
-		let y = (self.height as isize + (y % self.height as isize)) as usize % self.height;
-		self.patches[patch_index(x, y, self.pwidth)].set(x, y, new_val);
+		self.array.wrapped_set([x, y], new_val);
 	}
 
 	/// Gets a value from the 2D array as an Option that is None if the coordinate
-	/// is out of bounds.
+	/// is out of bounds. Coordinates outside of a wrapped or clamped axis are handled by the
+	/// topology of this 2D array (see *set_topology(x_axis, y_axis)*), so only the bounded
+	/// axes can be out of bounds.
 	/// # Parameters
 	/// * **x** - x dimension coordinate
 	/// * **y** - y dimension coordinate
@@ -412,34 +595,282 @@ impl<T> ZArray2D<T> {
 	/// the 2D array, or *None* signalling that the coordinate is out of bounds (which can be
 	/// combined with .unwrap_or(default_value) to implement an out-of-bounds default)
 	pub fn bounded_get(&self, x: isize, y: isize) -> Option<&T> {
-		if x >= 0 && y >= 0 && x < self.width as isize && y < self.height as isize {
-			return Some(&self.patches[patch_index(x as usize, y as usize, self.pwidth)]
-				.get(x as usize, y as usize));
-		} else {
-			return None;
-		}
+		return self.array.bounded_get([x, y]);
+	}
+
+	/// Gets a mutable reference to a value in the 2D array as an Option that is None if the
+	/// coordinate is out of bounds (coordinates outside of a wrapped or clamped axis are
+	/// handled by the topology of this 2D array).
+	/// # Parameters
+	/// * **x** - x dimension coordinate
+	/// * **y** - y dimension coordinate
+	/// # Returns
+	/// Returns an Option type that holds either a mutable reference to the data value in the
+	/// 2D array, or *None* signalling that the coordinate is out of bounds
+	pub fn bounded_get_mut(&mut self, x: isize, y: isize) -> Option<&mut T> {
+		return self.array.bounded_get_mut([x, y]);
 	}
 
-	/// Sets a value in the 2D array if and only if the provided coordinate is in bounds.
-	/// Otherwise this method does nothing if the coordiante is out of bounds.
+	/// Sets a value in the 2D array if and only if the provided coordinate is in bounds
+	/// (coordinates outside of a wrapped or clamped axis are handled by the topology of this
+	/// 2D array). Otherwise this method does nothing if the coordiante is out of bounds.
 	/// # Parameters
 	/// * **x** - x dimension coordinate
 	/// * **y** - y dimension coordinate
 	/// * **new_val** - value to store int eh 2D array at (x, y)
 	pub fn bounded_set(&mut self, x: isize, y: isize, new_val: T) {
-		if x >= 0 && y >= 0 && x < self.width as isize && y < self.height as isize {
-			self.patches[patch_index(x as usize, y as usize, self.pwidth)]
-				.set(x as usize, y as usize, new_val);
-		} else {
-			// no-op
-		}
+		self.array.bounded_set([x, y], new_val);
+	}
+
+	/// Gets a value from the 2D array, using the provided edge mode to handle coordinates that
+	/// are negative or outside the size of this 2D array (eg *EdgeMode::Clamp* to
+	/// clamp-to-edge or *EdgeMode::Mirror* to mirror the array at its edges). The edge mode
+	/// only applies to the bounded axes, as the wrapped and clamped axes are handled by the
+	/// topology of this 2D array (see *set_topology(x_axis, y_axis)*).
+	/// # Parameters
+	/// * **x** - x dimension coordinate
+	/// * **y** - y dimension coordinate
+	/// * **edge_mode** - how to handle out-of-bounds coordinates
+	/// # Returns
+	/// Returns a Result type that holds either the returned data value (as a reference) or a
+	/// *LookUpError* if the coordinate is out of bounds and the edge mode is *EdgeMode::Error*
+	pub fn sample<'a>(&'a self, x: isize, y: isize, edge_mode: &'a EdgeMode<T>) -> Result<&'a T, LookUpError> {
+		return self.array.sample([x, y], edge_mode);
+	}
+
+	/// Gets a mutable reference to a value in the 2D array, using the provided edge mode to
+	/// handle coordinates that are negative or outside the size of this 2D array.
+	/// # Parameters
+	/// * **x** - x dimension coordinate
+	/// * **y** - y dimension coordinate
+	/// * **edge_mode** - how to handle out-of-bounds coordinates
+	/// # Returns
+	/// Returns a Result type that holds either a mutable reference to the data value or a
+	/// *LookUpError* if the coordinate is out of bounds and the edge mode is *EdgeMode::Error*
+	/// or *EdgeMode::Default* (as there is no value in the array to mutate)
+	pub fn sample_mut(&mut self, x: isize, y: isize, edge_mode: &EdgeMode<T>) -> Result<&mut T, LookUpError> {
+		return self.array.sample_mut([x, y], edge_mode);
+	}
+
+	/// Sets a value in the 2D array, using the provided edge mode to handle coordinates that
+	/// are negative or outside the size of this 2D array. Writes to out-of-bounds coordinates
+	/// are ignored with *EdgeMode::Default*.
+	/// # Parameters
+	/// * **x** - x dimension coordinate
+	/// * **y** - y dimension coordinate
+	/// * **edge_mode** - how to handle out-of-bounds coordinates
+	/// * **new_val** - value to store in the 2D array
+	/// # Returns
+	/// Returns a Result type that is either empty or a *LookUpError* if the coordinate is out
+	/// of bounds and the edge mode is *EdgeMode::Error*
+	pub fn sample_set(&mut self, x: isize, y: isize, edge_mode: &EdgeMode<T>, new_val: T) -> Result<(), LookUpError> {
+		return self.array.sample_set([x, y], edge_mode, new_val);
+	}
+
+	/// Creates an iterator that iterates through a rectangular region of signed coordinates
+	/// in raster order, like *bounded_get(x, y)*: coordinates outside of a wrapped or clamped
+	/// axis are handled by the topology of this 2D array (see *set_topology(x_axis, y_axis)*)
+	/// and coordinates that are out of bounds on a bounded axis are skipped. The coordinates
+	/// of the items are the in-bounds coordinates that were looked up (so a clamped axis may
+	/// visit the same cell more than once).
+	/// # Parameters
+	/// * **x_range** - range of x dimension coordinates (eg `-4..4`)
+	/// * **y_range** - range of y dimension coordinates (eg `..`)
+	/// # Returns
+	/// A new ZArray2DBoundedRegionIterator instance
+	pub fn bounded_iter_region(&self, x_range: impl RangeBounds<isize>, y_range: impl RangeBounds<isize>)
+		-> ZArray2DBoundedRegionIterator<'_, T, E> {
+		let (x1, x2) = signed_range(&x_range, self.width());
+		let (y1, y2) = signed_range(&y_range, self.height());
+		ZArray2DBoundedRegionIterator{iter: self.array.bounded_iter_region([x1, y1], [x2, y2])}
 	}
 
 	/// Creates an iterator that iterates through the 2D array in Z-order
 	/// # Returns
 	/// A new ZArray2DIterator instance
-	pub fn iter(&self) -> ZArray2DIterator<T> {
-		ZArray2DIterator::new(self)
+	pub fn iter(&self) -> ZArray2DIterator<'_, T, E> {
+		ZArray2DIterator{iter: self.array.iter()}
+	}
+
+	/// Creates an iterator that iterates through the 2D array in Z-order, providing mutable
+	/// access to each value
+	/// # Returns
+	/// A new ZArray2DIteratorMut instance
+	pub fn iter_mut(&mut self) -> ZArray2DIteratorMut<'_, T, E> {
+		ZArray2DIteratorMut{iter: self.array.iter_mut()}
+	}
+
+	/// Creates an iterator that iterates through the 2D array in raster order (aka row-major
+	/// order, X changes fastest, then Y). This is slower than Z-order iteration with
+	/// *iter()*, but is useful when the order of the values matters (eg for exporting data).
+	/// # Returns
+	/// A new ZArray2DRasterIterator instance
+	pub fn iter_raster(&self) -> ZArray2DRasterIterator<'_, T, E> {
+		ZArray2DRasterIterator{iter: self.array.iter_raster()}
+	}
+
+	/// Alias for `iter_raster()`
+	/// # Returns
+	/// A new ZArray2DRasterIterator instance
+	pub fn iter_rows(&self) -> ZArray2DRasterIterator<'_, T, E> {
+		return self.iter_raster();
+	}
+
+	/// Creates an iterator that iterates through one row of the 2D array (all X coordinates
+	/// for a given Y coordinate) in order of increasing X
+	/// # Parameters
+	/// * **y** - y dimension coordinate of the row
+	/// # Returns
+	/// Returns a Result type that holds either a new ZArray2DRasterIterator instance or a
+	/// *LookUpError* signalling that the row is out of bounds
+	pub fn row(&self, y: usize) -> Result<ZArray2DRasterIterator<'_, T, E>, LookUpError> {
+		let (width, height) = self.dimensions();
+		if y < height {
+			Ok(ZArray2DRasterIterator{iter: self.array.iter_raster_region([0, y], [width, y + 1])})
+		} else {
+			Err(LookUpError::new(&[0, y as isize], &[width, height]))
+		}
+	}
+
+	/// Creates an iterator that iterates through one column of the 2D array (all Y
+	/// coordinates for a given X coordinate) in order of increasing Y
+	/// # Parameters
+	/// * **x** - x dimension coordinate of the column
+	/// # Returns
+	/// Returns a Result type that holds either a new ZArray2DRasterIterator instance or a
+	/// *LookUpError* signalling that the column is out of bounds
+	pub fn column(&self, x: usize) -> Result<ZArray2DRasterIterator<'_, T, E>, LookUpError> {
+		let (width, height) = self.dimensions();
+		if x < width {
+			Ok(ZArray2DRasterIterator{iter: self.array.iter_raster_region([x, 0], [x + 1, height])})
+		} else {
+			Err(LookUpError::new(&[x as isize, 0], &[width, height]))
+		}
+	}
+
+	/// Creates an iterator that iterates through a rectangular region of the 2D array in
+	/// Z-order, visiting only the data patches that overlap the region. The region is clipped
+	/// to the bounds of this 2D array (use *bounded_iter_region(x_range, y_range)* for a
+	/// region that crosses the edges of wrapped or clamped axes).
+	/// # Parameters
+	/// * **x_range** - range of x dimension coordinates (eg `3..10`)
+	/// * **y_range** - range of y dimension coordinates (eg `..=7`)
+	/// # Returns
+	/// A new ZArray2DRegionIterator instance
+	pub fn iter_region(&self, x_range: impl RangeBounds<usize>, y_range: impl RangeBounds<usize>)
+		-> ZArray2DRegionIterator<'_, T, E> {
+		let (x1, x2) = clamp_range(&x_range, self.width());
+		let (y1, y2) = clamp_range(&y_range, self.height());
+		ZArray2DRegionIterator{iter: self.array.iter_region([x1, y1], [x2, y2])}
+	}
+
+	/// Creates an iterator that iterates through a rectangular region of the 2D array in
+	/// Z-order, providing mutable access to each value and visiting only the data patches that
+	/// overlap the region. The region is clipped to the bounds of this 2D array.
+	/// # Parameters
+	/// * **x_range** - range of x dimension coordinates (eg `3..10`)
+	/// * **y_range** - range of y dimension coordinates (eg `..=7`)
+	/// # Returns
+	/// A new ZArray2DRegionIteratorMut instance
+	pub fn iter_region_mut(&mut self, x_range: impl RangeBounds<usize>, y_range: impl RangeBounds<usize>)
+		-> ZArray2DRegionIteratorMut<'_, T, E> {
+		let (x1, x2) = clamp_range(&x_range, self.width());
+		let (y1, y2) = clamp_range(&y_range, self.height());
+		ZArray2DRegionIteratorMut{iter: self.array.iter_region_mut([x1, y1], [x2, y2])}
+	}
+
+	/// Creates an iterator that iterates through the neighbors of a given cell, for use in
+	/// operations such as blurring and cellular automata. The center cell itself is not
+	/// included. If the whole neighborhood lies inside a single data patch, then the
+	/// neighbors are read directly from that patch without any bounds checking.
+	/// # Parameters
+	/// * **x** - x dimension coordinate of the center cell
+	/// * **y** - y dimension coordinate of the center cell
+	/// * **kind** - shape of the neighborhood (eg *Neighborhood::Moore*)
+	/// * **radius** - size of the neighborhood (ignored for *Neighborhood::Custom*)
+	/// * **edge_mode** - how to handle neighbors that are outside of this 2D array (neighbors
+	/// are left out with *EdgeMode::Error*)
+	/// # Returns
+	/// A new ZArray2DNeighborIterator instance
+	/// # Example
+	/// ```
+	/// use zarray::EdgeMode;
+	/// use zarray::z2d::{Neighborhood, ZArray2D};
+	/// let mut map = ZArray2D::<_>::new(10, 10, 0u8);
+	/// map.bounded_fill(0, 0, 5, 10, 1u8);
+	/// let sum: u8 = map.neighbors(5, 5, Neighborhood::Moore, 1, &EdgeMode::Error)
+	///   .map(|n| *n.value).sum();
+	/// assert_eq!(sum, 3);
+	/// ```
+	pub fn neighbors<'a>(&'a self, x: usize, y: usize, kind: Neighborhood<'a>, radius: usize,
+		edge_mode: &'a EdgeMode<T>) -> ZArray2DNeighborIterator<'a, T, E> {
+		ZArray2DNeighborIterator::new(self, x, y, kind, radius, edge_mode)
+	}
+
+	/// Applies a stencil function to every cell of this 2D array and writes the results to
+	/// the output array (which must have the same dimensions), eg for blurring or cellular
+	/// automata. This is the "double-buffered" form of a loop over *neighbors(...)*: the
+	/// stencil function is given a [StencilView](crate::znd::StencilView) of each cell's
+	/// neighborhood, where neighbors are addressed by their offset `[dx, dy]` from the cell.
+	/// The array is processed one data patch at a time, looking up each patch and the halo of
+	/// cells around it only once, which is much faster than looking up the neighbors of every
+	/// cell with *bounded_get(...)*.
+	/// # Parameters
+	/// * **output** - 2D array to write the results to
+	/// * **radius** - size of the neighborhood in each direction
+	/// * **edge_mode** - how to handle neighbors that are outside of this 2D array (they read
+	/// as None with *EdgeMode::Error*)
+	/// * **stencil_fn** - function that takes the view of a cell's neighborhood and returns
+	/// the output value for that cell
+	/// # Panics
+	/// Panics if the output array does not have the same dimensions as this 2D array
+	/// # Example
+	/// ```
+	/// use zarray::EdgeMode;
+	/// use zarray::z2d::ZArray2D;
+	/// let mut map = ZArray2D::<_>::new(10, 10, 0u8);
+	/// map.set(5, 5, 9).unwrap();
+	/// // 3x3 box blur
+	/// let mut blurred = ZArray2D::<_>::new(10, 10, 0u8);
+	/// map.stencil(&mut blurred, 1, &EdgeMode::Clamp, |view| {
+	///   (view.neighbors().map(|(_, v)| *v as u32).sum::<u32>() + *view.center() as u32) as u8 / 9
+	/// });
+	/// assert_eq!(*blurred.get(4, 6).unwrap(), 1);
+	/// assert_eq!(*blurred.get(3, 6).unwrap(), 0);
+	/// ```
+	pub fn stencil<U>(&self, output: &mut ZArray2D<U, E>, radius: usize, edge_mode: &EdgeMode<T>,
+		stencil_fn: impl FnMut(&StencilView<'_, T, 2>) -> U) {
+		self.array.stencil(&mut output.array, radius, edge_mode, stencil_fn);
+	}
+
+	/// Convolves this 2D array with a kernel of weights and writes the results to the output
+	/// array (which must have the same dimensions), eg for a box or Gaussian blur. Each output
+	/// value is the sum of the neighboring values multiplied by their weights. Neighbors that
+	/// are out of bounds with *EdgeMode::Error* are left out of the sum.
+	/// # Parameters
+	/// * **output** - 2D array to write the results to
+	/// * **kernel** - weights of the neighborhood in raster order (X changes fastest), which
+	/// must be 2r+1 values long in each dimension for a radius of r (eg 9 values for a 3x3
+	/// kernel)
+	/// * **edge_mode** - how to handle neighbors that are outside of this 2D array
+	/// # Panics
+	/// Panics if the output array does not have the same dimensions as this 2D array, or if
+	/// the kernel is not the same odd length in every dimension
+	/// # Example
+	/// ```
+	/// use zarray::EdgeMode;
+	/// use zarray::z2d::ZArray2D;
+	/// let mut map = ZArray2D::<_>::new(10, 10, 0u8);
+	/// map.set(5, 5, 9).unwrap();
+	/// // 3x3 box blur
+	/// let mut blurred = ZArray2D::<_>::new(10, 10, 0f32);
+	/// map.convolve(&mut blurred, &[1f32 / 9f32; 9], &EdgeMode::Clamp);
+	/// assert!((*blurred.get(4, 6).unwrap() - 1f32).abs() < 1e-6);
+	/// assert_eq!(*blurred.get(3, 6).unwrap(), 0f32);
+	/// ```
+	pub fn convolve<U>(&self, output: &mut ZArray2D<U, E>, kernel: &[U], edge_mode: &EdgeMode<T>)
+		where T: Copy + Into<U>, U: Copy + Default + Add<Output=U> + Mul<Output=U> {
+		self.array.convolve(&mut output.array, kernel, edge_mode);
 	}
 
 	/// Applies a function to the Z-array to mutate it in-place
@@ -447,45 +878,473 @@ impl<T> ZArray2D<T> {
 	/// * **transform_fn** - Function that takes the coordsinate as a tuple and a
 	/// reference to the old value and returns the new value
 	pub fn transform(&mut self, transform_fn: impl Fn((usize, usize), &T) -> T) {
-		for pindex in 0..self.patches.len() {
-			let patch_coords = patch_coords(self.pwidth, pindex);
-			for coord in patch_coords {
-				if coord.0 < self.width && coord.1 < self.height {
-					let old_val = self.get_unchecked(coord.0, coord.1);
-					self.set_unchecked(coord.0, coord.1, transform_fn(coord, old_val));
-				}
-			}
-		}
+		self.array.transform(|[x, y], old_val| transform_fn((x, y), old_val));
 	}
 
 	/// Returns a vector of all valid (x, y) coordinates in this 2D array in Z-order
 	pub fn coords(&self) -> Vec<(usize, usize)> {
-		let mut out: Vec<(usize, usize)> = Vec::with_capacity(self.width * self.height);
-		for pindex in 0..self.patches.len() {
-			let patch_coords = patch_coords(self.pwidth, pindex);
-			for coord in patch_coords {
-				if coord.0 < self.width && coord.1 < self.height {
-					out.push(coord);
-				}
-			}
-		}
-		return out;
+		return self.iter().map(|item| (item.x, item.y)).collect();
+	}
+}
+
+#[cfg(feature = "rayon")]
+impl<T, const E: usize> ZArray2D<T, E> {
+	/// Creates a parallel iterator (using *rayon*) over the values of the 2D array, which
+	/// splits the work between threads one data patch at a time
+	/// # Returns
+	/// A rayon *ParallelIterator* of *ZArray2DIteratorItem* instances
+	pub fn par_iter(&self) -> impl ParallelIterator<Item=ZArray2DIteratorItem<'_, T>> where T: Sync {
+		return self.array.par_iter().map(|item| ZArray2DIteratorItem{x: item.coord[0], y: item.coord[1], value: item.value});
+	}
+
+	/// Creates a parallel iterator (using *rayon*) over the values of the 2D array, providing
+	/// mutable access to each value, which splits the work between threads one data patch at
+	/// a time
+	/// # Returns
+	/// A rayon *ParallelIterator* of *ZArray2DIteratorItemMut* instances
+	pub fn par_iter_mut(&mut self) -> impl ParallelIterator<Item=ZArray2DIteratorItemMut<'_, T>> where T: Send {
+		return self.array.par_iter_mut().map(|item| ZArray2DIteratorItemMut{x: item.coord[0], y: item.coord[1], value: item.value});
+	}
+
+	/// Applies a function to the Z-array to mutate it in-place, like *transform(transform_fn)*,
+	/// but using all available threads (with *rayon*)
+	/// # Parameters
+	/// * **transform_fn** - Function that takes the coordinate as a tuple and a reference to
+	/// the old value and returns the new value
+	pub fn par_transform(&mut self, transform_fn: impl Fn((usize, usize), &T) -> T + Sync) where T: Send {
+		self.array.par_transform(|[x, y], old_val| transform_fn((x, y), old_val));
+	}
+
+	/// Returns a vector of all valid (x, y) coordinates in this 2D array in Z-order, like
+	/// *coords()*, but using all available threads (with *rayon*)
+	pub fn par_coords(&self) -> Vec<(usize, usize)> {
+		return self.array.par_coords().into_par_iter().map(|[x, y]| (x, y)).collect();
 	}
 }
 
 
 #[test]
 fn check_patch_count_2d() {
-	let arr = ZArray2D::new(1, 1, 0u8);
-	assert_eq!(arr.patches.len(), 1, "Allocated wrong number of patches for array of size {}x{}", arr.width, arr.height);
-	let arr = ZArray2D::new(8, 8, 0u8);
-	assert_eq!(arr.patches.len(), 1, "Allocated wrong number of patches for array of size {}x{}", arr.width, arr.height);
-	let arr = ZArray2D::new(9, 8, 0u8);
-	assert_eq!(arr.patches.len(), 2, "Allocated wrong number of patches for array of size {}x{}", arr.width, arr.height);
-	let arr = ZArray2D::new(8, 9, 0u8);
-	assert_eq!(arr.patches.len(), 2, "Allocated wrong number of patches for array of size {}x{}", arr.width, arr.height);
-	let arr = ZArray2D::new(9, 9, 0u8);
-	assert_eq!(arr.patches.len(), 4, "Allocated wrong number of patches for array of size {}x{}", arr.width, arr.height);
+	let arr = ZArray2D::<_>::new(1, 1, 0u8);
+	assert_eq!(arr.array.patch_count(), 1, "Allocated wrong number of patches for array of size {}x{}", arr.width(), arr.height());
+	let arr = ZArray2D::<_>::new(8, 8, 0u8);
+	assert_eq!(arr.array.patch_count(), 1, "Allocated wrong number of patches for array of size {}x{}", arr.width(), arr.height());
+	let arr = ZArray2D::<_>::new(9, 8, 0u8);
+	assert_eq!(arr.array.patch_count(), 2, "Allocated wrong number of patches for array of size {}x{}", arr.width(), arr.height());
+	let arr = ZArray2D::<_>::new(8, 9, 0u8);
+	assert_eq!(arr.array.patch_count(), 2, "Allocated wrong number of patches for array of size {}x{}", arr.width(), arr.height());
+	let arr = ZArray2D::<_>::new(9, 9, 0u8);
+	assert_eq!(arr.array.patch_count(), 4, "Allocated wrong number of patches for array of size {}x{}", arr.width(), arr.height());
+}
+
+impl<T, const E: usize> ZArray2D<T, E> {
+	/// Borrows a rectangular region of the 2D array as a view, which uses local coordinates
+	/// (the first corner of the region is (0, 0) in the view). The region is clipped to the
+	/// bounds of this 2D array.
+	/// # Parameters
+	/// * **x_range** - range of x dimension coordinates (eg `128..192`)
+	/// * **y_range** - range of y dimension coordinates (eg `256..320`)
+	/// # Returns
+	/// A new ZArray2DView instance
+	pub fn view(&self, x_range: impl RangeBounds<usize>, y_range: impl RangeBounds<usize>) -> ZArray2DView<'_, T, E> {
+		let (x1, x2) = clamp_range(&x_range, self.width());
+		let (y1, y2) = clamp_range(&y_range, self.height());
+		ZArray2DView{view: self.array.view([x1, y1], [x2, y2])}
+	}
+
+	/// Mutably borrows a rectangular region of the 2D array as a view, which uses local
+	/// coordinates (the first corner of the region is (0, 0) in the view). The region is
+	/// clipped to the bounds of this 2D array.
+	/// # Parameters
+	/// * **x_range** - range of x dimension coordinates (eg `128..192`)
+	/// * **y_range** - range of y dimension coordinates (eg `256..320`)
+	/// # Returns
+	/// A new ZArray2DViewMut instance
+	pub fn view_mut(&mut self, x_range: impl RangeBounds<usize>, y_range: impl RangeBounds<usize>)
+		-> ZArray2DViewMut<'_, T, E> {
+		let (x1, x2) = clamp_range(&x_range, self.width());
+		let (y1, y2) = clamp_range(&y_range, self.height());
+		ZArray2DViewMut{view: self.array.view_mut([x1, y1], [x2, y2])}
+	}
+
+	/// Divides the 2D array into two disjoint mutable views at an x coordinate, which can be
+	/// modified at the same time (eg on different threads)
+	/// # Parameters
+	/// * **x** - x dimension coordinate where the second view starts
+	/// # Returns
+	/// Returns a tuple of the left view (x coordinates before *x*) and the right view
+	/// # Panics
+	/// Panics if *x* is greater than the width of the 2D array
+	pub fn split_at_x_mut(&mut self, x: usize) -> (ZArray2DViewMut<'_, T, E>, ZArray2DViewMut<'_, T, E>) {
+		let (left, right) = self.array.split_at_mut(0, x);
+		return (ZArray2DViewMut{view: left}, ZArray2DViewMut{view: right});
+	}
+
+	/// Divides the 2D array into two disjoint mutable views at a y coordinate, which can be
+	/// modified at the same time (eg on different threads)
+	/// # Parameters
+	/// * **y** - y dimension coordinate where the second view starts
+	/// # Returns
+	/// Returns a tuple of the top view (y coordinates before *y*) and the bottom view
+	/// # Panics
+	/// Panics if *y* is greater than the height of the 2D array
+	pub fn split_at_y_mut(&mut self, y: usize) -> (ZArray2DViewMut<'_, T, E>, ZArray2DViewMut<'_, T, E>) {
+		let (top, bottom) = self.array.split_at_mut(1, y);
+		return (ZArray2DViewMut{view: top}, ZArray2DViewMut{view: bottom});
+	}
+
+	/// Divides the 2D array into disjoint mutable views (tiles) of the given size, which can
+	/// be modified at the same time (eg on different threads). The tiles at the right and
+	/// bottom edges are smaller if the size of the 2D array is not a multiple of the tile size.
+	/// # Parameters
+	/// * **chunk_width** - x dimension size of each tile
+	/// * **chunk_height** - y dimension size of each tile
+	/// # Returns
+	/// Returns a list of the tiles in raster order (X changes fastest, then Y)
+	/// # Panics
+	/// Panics if the width or height of the tiles is 0
+	pub fn chunks_mut(&mut self, chunk_width: usize, chunk_height: usize) -> Vec<ZArray2DViewMut<'_, T, E>> {
+		return self.array.chunks_mut([chunk_width, chunk_height]).into_iter()
+			.map(|view| ZArray2DViewMut{view}).collect();
+	}
+}
+
+/// This struct is an immutable view of a rectangular region of a *ZArray2D* (see
+/// *ZArray2D.view(x_range, y_range)*), which uses local coordinates where the first corner of
+/// the region is (0, 0).
+#[derive(Debug)]
+pub struct ZArray2DView<'a, T, const E: usize = DEFAULT_PATCH_EDGE> {
+	view: ZArrayNDView<'a, T, 2, E>
+}
+
+impl<'a, T, const E: usize> Clone for ZArray2DView<'a, T, E> {
+	fn clone(&self) -> Self {
+		ZArray2DView{view: self.view.clone()}
+	}
+}
+
+impl<'a, T, const E: usize> ZArray2DView<'a, T, E> {
+	/// Gets the (x, y) size of this view
+	pub fn dimensions(&self) -> (usize, usize) {
+		let [width, height] = self.view.dimensions();
+		return (width, height);
+	}
+
+	/// Gets the x dimension size of this view
+	pub fn width(&self) -> usize {
+		return self.view.dimensions()[0];
+	}
+
+	/// Gets the y dimension size of this view
+	pub fn height(&self) -> usize {
+		return self.view.dimensions()[1];
+	}
+
+	/// Gets the (x, y) coordinate in the 2D array of the origin of this view
+	pub fn origin(&self) -> (usize, usize) {
+		let [x, y] = self.view.origin();
+		return (x, y);
+	}
+
+	/// Gets a value from the view, or returns a *LookUpError* if the provided local coordinate
+	/// is out of bounds
+	/// # Parameters
+	/// * **x** - x dimension coordinate (relative to the origin of the view)
+	/// * **y** - y dimension coordinate (relative to the origin of the view)
+	/// # Returns
+	/// Returns a Result type that holds either the returned data value (as a reference) from
+	/// the 2D array, or a *LookUpError* signalling that the coordinate is out of bounds
+	pub fn get(&self, x: usize, y: usize) -> Result<&'a T, LookUpError> {
+		return self.view.get([x, y]);
+	}
+
+	/// Gets a value from the view as an Option that is None if the local coordinate is out of
+	/// bounds (of the view, not the 2D array).
+	/// # Parameters
+	/// * **x** - x dimension coordinate (relative to the origin of the view)
+	/// * **y** - y dimension coordinate (relative to the origin of the view)
+	/// # Returns
+	/// Returns an Option type that holds either the returned data value (as a reference) from
+	/// the 2D array, or *None* signalling that the coordinate is out of bounds
+	pub fn bounded_get(&self, x: isize, y: isize) -> Option<&'a T> {
+		return self.view.bounded_get([x, y]);
+	}
+
+	/// Gets a value from the view, wrapping around the edges of the view when the local
+	/// coordinates are negative or outside the size of this view.
+	/// # Parameters
+	/// * **x** - x dimension coordinate (relative to the origin of the view)
+	/// * **y** - y dimension coordinate (relative to the origin of the view)
+	/// # Returns
+	/// Returns a reference to the data stored at the provided coordinate (wrapping both
+	/// x and y dimensions)
+	pub fn wrapped_get(&self, x: isize, y: isize) -> &'a T {
+		return self.view.wrapped_get([x, y]);
+	}
+
+	/// Creates an iterator that iterates through the view in Z-order, with local coordinates
+	/// # Returns
+	/// A new ZArray2DViewIterator instance
+	pub fn iter(&self) -> ZArray2DViewIterator<'a, T, E> {
+		ZArray2DViewIterator{iter: self.view.iter()}
+	}
+
+	/// Borrows a rectangular region of this view as a smaller view. The region is clipped to
+	/// the bounds of this view.
+	/// # Parameters
+	/// * **x_range** - range of local x dimension coordinates
+	/// * **y_range** - range of local y dimension coordinates
+	/// # Returns
+	/// A new ZArray2DView instance
+	pub fn view(&self, x_range: impl RangeBounds<usize>, y_range: impl RangeBounds<usize>) -> ZArray2DView<'a, T, E> {
+		let (x1, x2) = clamp_range(&x_range, self.width());
+		let (y1, y2) = clamp_range(&y_range, self.height());
+		ZArray2DView{view: self.view.view([x1, y1], [x2, y2])}
+	}
+}
+
+/// This struct is a mutable view of a rectangular region of a *ZArray2D* (see
+/// *ZArray2D.view_mut(x_range, y_range)*), which uses local coordinates where the first
+/// corner of the region is (0, 0). Views that do not overlap (eg from
+/// *ZArray2D.chunks_mut(chunk_width, chunk_height)*) can be used at the same time, including
+/// on different threads.
+#[derive(Debug)]
+pub struct ZArray2DViewMut<'a, T, const E: usize = DEFAULT_PATCH_EDGE> {
+	view: ZArrayNDViewMut<'a, T, 2, E>
+}
+
+impl<'a, T, const E: usize> ZArray2DViewMut<'a, T, E> {
+	/// Gets the (x, y) size of this view
+	pub fn dimensions(&self) -> (usize, usize) {
+		let [width, height] = self.view.dimensions();
+		return (width, height);
+	}
+
+	/// Gets the x dimension size of this view
+	pub fn width(&self) -> usize {
+		return self.view.dimensions()[0];
+	}
+
+	/// Gets the y dimension size of this view
+	pub fn height(&self) -> usize {
+		return self.view.dimensions()[1];
+	}
+
+	/// Gets the (x, y) coordinate in the 2D array of the origin of this view
+	pub fn origin(&self) -> (usize, usize) {
+		let [x, y] = self.view.origin();
+		return (x, y);
+	}
+
+	/// Borrows this view as an immutable view
+	pub fn as_view(&self) -> ZArray2DView<'_, T, E> {
+		ZArray2DView{view: self.view.as_view()}
+	}
+
+	/// Gets a value from the view, or returns a *LookUpError* if the provided local coordinate
+	/// is out of bounds
+	/// # Parameters
+	/// * **x** - x dimension coordinate (relative to the origin of the view)
+	/// * **y** - y dimension coordinate (relative to the origin of the view)
+	/// # Returns
+	/// Returns a Result type that holds either the returned data value (as a reference) from
+	/// the 2D array, or a *LookUpError* signalling that the coordinate is out of bounds
+	pub fn get(&self, x: usize, y: usize) -> Result<&T, LookUpError> {
+		return self.view.get([x, y]);
+	}
+
+	/// Gets a mutable reference to a value in the view, or returns a *LookUpError* if the
+	/// provided local coordinate is out of bounds
+	/// # Parameters
+	/// * **x** - x dimension coordinate (relative to the origin of the view)
+	/// * **y** - y dimension coordinate (relative to the origin of the view)
+	/// # Returns
+	/// Returns a Result type that holds either a mutable reference to the data value in the
+	/// 2D array, or a *LookUpError* signalling that the coordinate is out of bounds
+	pub fn get_mut(&mut self, x: usize, y: usize) -> Result<&mut T, LookUpError> {
+		return self.view.get_mut([x, y]);
+	}
+
+	/// Sets a value in the view, or returns a *LookUpError* if the provided local coordinate
+	/// is out of bounds
+	/// # Parameters
+	/// * **x** - x dimension coordinate (relative to the origin of the view)
+	/// * **y** - y dimension coordinate (relative to the origin of the view)
+	/// * **new_val** - value to store in the 2D array at (x, y) in the view
+	/// # Returns
+	/// Returns a Result type that is either empty or a *LookUpError* signalling that the
+	/// coordinate is out of bounds
+	pub fn set(&mut self, x: usize, y: usize, new_val: T) -> Result<(), LookUpError> {
+		return self.view.set([x, y], new_val);
+	}
+
+	/// Gets a value from the view as an Option that is None if the local coordinate is out of
+	/// bounds (of the view, not the 2D array).
+	/// # Parameters
+	/// * **x** - x dimension coordinate (relative to the origin of the view)
+	/// * **y** - y dimension coordinate (relative to the origin of the view)
+	/// # Returns
+	/// Returns an Option type that holds either the returned data value (as a reference) from
+	/// the 2D array, or *None* signalling that the coordinate is out of bounds
+	pub fn bounded_get(&self, x: isize, y: isize) -> Option<&T> {
+		return self.view.bounded_get([x, y]);
+	}
+
+	/// Gets a mutable reference to a value in the view as an Option that is None if the local
+	/// coordinate is out of bounds (of the view, not the 2D array).
+	/// # Parameters
+	/// * **x** - x dimension coordinate (relative to the origin of the view)
+	/// * **y** - y dimension coordinate (relative to the origin of the view)
+	/// # Returns
+	/// Returns an Option type that holds either a mutable reference to the data value in the
+	/// 2D array, or *None* signalling that the coordinate is out of bounds
+	pub fn bounded_get_mut(&mut self, x: isize, y: isize) -> Option<&mut T> {
+		return self.view.bounded_get_mut([x, y]);
+	}
+
+	/// Sets a value in the view if and only if the provided local coordinate is in bounds (of
+	/// the view, not the 2D array). Otherwise this method does nothing.
+	/// # Parameters
+	/// * **x** - x dimension coordinate (relative to the origin of the view)
+	/// * **y** - y dimension coordinate (relative to the origin of the view)
+	/// * **new_val** - value to store in the 2D array at (x, y) in the view
+	pub fn bounded_set(&mut self, x: isize, y: isize, new_val: T) {
+		self.view.bounded_set([x, y], new_val);
+	}
+
+	/// Gets a value from the view, wrapping around the edges of the view when the local
+	/// coordinates are negative or outside the size of this view.
+	/// # Parameters
+	/// * **x** - x dimension coordinate (relative to the origin of the view)
+	/// * **y** - y dimension coordinate (relative to the origin of the view)
+	/// # Returns
+	/// Returns a reference to the data stored at the provided coordinate (wrapping both
+	/// x and y dimensions)
+	pub fn wrapped_get(&self, x: isize, y: isize) -> &T {
+		return self.view.wrapped_get([x, y]);
+	}
+
+	/// Gets a mutable reference to a value in the view, wrapping around the edges of the view
+	/// when the local coordinates are negative or outside the size of this view.
+	/// # Parameters
+	/// * **x** - x dimension coordinate (relative to the origin of the view)
+	/// * **y** - y dimension coordinate (relative to the origin of the view)
+	/// # Returns
+	/// Returns a mutable reference to the data stored at the provided coordinate (wrapping
+	/// both x and y dimensions)
+	pub fn wrapped_get_mut(&mut self, x: isize, y: isize) -> &mut T {
+		return self.view.wrapped_get_mut([x, y]);
+	}
+
+	/// Sets a value in the view at the provided local coordinate, wrapping around the edges
+	/// of the view if the coordinate is negative or out of bounds.
+	/// # Parameters
+	/// * **x** - x dimension coordinate (relative to the origin of the view)
+	/// * **y** - y dimension coordinate (relative to the origin of the view)
+	/// * **new_val** - value to store in the 2D array at (x, y) in the view, wrapping around
+	/// both x and y dimensions
+	pub fn wrapped_set(&mut self, x: isize, y: isize, new_val: T) {
+		self.view.wrapped_set([x, y], new_val);
+	}
+
+	/// Creates an iterator that iterates through the view in Z-order, with local coordinates
+	/// # Returns
+	/// A new ZArray2DViewIterator instance
+	pub fn iter(&self) -> ZArray2DViewIterator<'_, T, E> {
+		ZArray2DViewIterator{iter: self.view.iter()}
+	}
+
+	/// Creates an iterator that iterates through the view in Z-order, with local coordinates,
+	/// providing mutable access to each value
+	/// # Returns
+	/// A new ZArray2DViewIteratorMut instance
+	pub fn iter_mut(&mut self) -> ZArray2DViewIteratorMut<'_, T, E> {
+		ZArray2DViewIteratorMut{iter: self.view.iter_mut()}
+	}
+
+	/// Fills a region of this view with a given value, or returns a *LookUpError* if the
+	/// provided local coordinates go out of bounds (of the view, not the 2D array).
+	/// # Parameters
+	/// * **x1** - the first x dimension coordinate (inclusive)
+	/// * **y1** - the first y dimension coordinate (inclusive)
+	/// * **x2** - the second x dimension coordinate (exclusive)
+	/// * **y2** - the second y dimension coordinate (exclusive)
+	/// * **new_val** - value to store in the view in the bounding box defined by
+	/// (x1, y1) -> (x2, y2)
+	/// # Returns
+	/// Returns a Result type that is either empty or a *LookUpError* signalling that a
+	/// coordinate is out of bounds
+	pub fn fill(&mut self, x1: usize, y1: usize, x2: usize, y2: usize, new_val: impl Borrow<T>)
+		-> Result<(), LookUpError> where T: Clone {
+		return self.view.fill([x1, y1], [x2, y2], new_val);
+	}
+
+	/// Mutably borrows a rectangular region of this view as a smaller view. The region is
+	/// clipped to the bounds of this view.
+	/// # Parameters
+	/// * **x_range** - range of local x dimension coordinates
+	/// * **y_range** - range of local y dimension coordinates
+	/// # Returns
+	/// A new ZArray2DViewMut instance
+	pub fn view_mut(&mut self, x_range: impl RangeBounds<usize>, y_range: impl RangeBounds<usize>)
+		-> ZArray2DViewMut<'_, T, E> {
+		let (x1, x2) = clamp_range(&x_range, self.width());
+		let (y1, y2) = clamp_range(&y_range, self.height());
+		ZArray2DViewMut{view: self.view.view_mut([x1, y1], [x2, y2])}
+	}
+
+	/// Divides this view into two disjoint mutable views at a local x coordinate
+	/// # Parameters
+	/// * **x** - local x dimension coordinate where the second view starts
+	/// # Returns
+	/// Returns a tuple of the left view (x coordinates before *x*) and the right view
+	/// # Panics
+	/// Panics if *x* is greater than the width of this view
+	pub fn split_at_x_mut(self, x: usize) -> (ZArray2DViewMut<'a, T, E>, ZArray2DViewMut<'a, T, E>) {
+		let (left, right) = self.view.split_at_mut(0, x);
+		return (ZArray2DViewMut{view: left}, ZArray2DViewMut{view: right});
+	}
+
+	/// Divides this view into two disjoint mutable views at a local y coordinate
+	/// # Parameters
+	/// * **y** - local y dimension coordinate where the second view starts
+	/// # Returns
+	/// Returns a tuple of the top view (y coordinates before *y*) and the bottom view
+	/// # Panics
+	/// Panics if *y* is greater than the height of this view
+	pub fn split_at_y_mut(self, y: usize) -> (ZArray2DViewMut<'a, T, E>, ZArray2DViewMut<'a, T, E>) {
+		let (top, bottom) = self.view.split_at_mut(1, y);
+		return (ZArray2DViewMut{view: top}, ZArray2DViewMut{view: bottom});
+	}
+}
+
+/// Iterator that iterates through a view of a 2D array in Z-order
+pub struct ZArray2DViewIterator<'a, T, const E: usize = DEFAULT_PATCH_EDGE> {
+	iter: ZArrayNDViewIterator<'a, T, 2, E>
+}
+
+impl<'a, T, const E: usize> Iterator for ZArray2DViewIterator<'a, T, E> {
+	type Item = ZArray2DIteratorItem<'a, T>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		let item = self.iter.next()?;
+		Some(ZArray2DIteratorItem{x: item.coord[0], y: item.coord[1], value: item.value})
+	}
+}
+
+/// Iterator that iterates through a view of a 2D array in Z-order, providing mutable access
+/// to each value
+pub struct ZArray2DViewIteratorMut<'a, T, const E: usize = DEFAULT_PATCH_EDGE> {
+	iter: ZArrayNDViewIteratorMut<'a, T, 2, E>
+}
+
+impl<'a, T, const E: usize> Iterator for ZArray2DViewIteratorMut<'a, T, E> {
+	type Item = ZArray2DIteratorItemMut<'a, T>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		let item = self.iter.next()?;
+		Some(ZArray2DIteratorItemMut{x: item.coord[0], y: item.coord[1], value: item.value})
+	}
 }
 
 /// This struct is used by `ZArray2DIterator` to present values to the consumer of the
@@ -500,64 +1359,273 @@ pub struct ZArray2DIteratorItem<'a, T> {
 	pub value: &'a T
 }
 
-/// private state management enum
-enum IterState {
-	Start, Processing, Done
-}
 /// Iterator that iterates through the array
-pub struct ZArray2DIterator<'a, T> {
-	/// array to iterate over
-	array: &'a ZArray2D<T>,
-	patch: usize,
-	index: usize,
-	state: IterState
-}
-
-impl<'a, T> ZArray2DIterator<'a, T> {
-	fn new(array: &'a ZArray2D<T>) -> ZArray2DIterator<'a, T> {
-		if array.width == 0 || array.height == 0 {
-			ZArray2DIterator{array, patch: 0, index: 0, state: IterState::Done} // make a "done" iterator for empty arrays
-		} else {
-			ZArray2DIterator{array, patch: 0, index: 0, state: IterState::Start}
-		}
+pub struct ZArray2DIterator<'a, T, const E: usize = DEFAULT_PATCH_EDGE> {
+	iter: ZArrayNDIterator<'a, T, 2, E>
+}
+
+impl<'a, T, const E: usize> Iterator for ZArray2DIterator<'a, T, E> {
+	type Item = ZArray2DIteratorItem<'a, T>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		let item = self.iter.next()?;
+		Some(ZArray2DIteratorItem{x: item.coord[0], y: item.coord[1], value: item.value})
 	}
 }
 
-impl<'a, T> Iterator for ZArray2DIterator<'a, T> {
+/// Iterator that iterates through a rectangular area of the array in raster order (X changes
+/// fastest, then Y)
+pub struct ZArray2DRasterIterator<'a, T, const E: usize = DEFAULT_PATCH_EDGE> {
+	iter: ZArrayNDRasterIterator<'a, T, 2, E>
+}
+
+impl<'a, T, const E: usize> Iterator for ZArray2DRasterIterator<'a, T, E> {
 	type Item = ZArray2DIteratorItem<'a, T>;
 
 	fn next(&mut self) -> Option<Self::Item> {
-		match &self.state {
-			IterState::Done=> None,
-			IterState::Start=> {
-				self.state = IterState::Processing;
-				Some(ZArray2DIteratorItem{x: 0, y: 0, value: &self.array.patches[0].contents[0]})
-			},
-			IterState::Processing => {
-				let mut x ; let mut y ;
-				loop {
-					self.index += 1;
-					if self.index >= 64 {
-						self.index  = 0;
-						self.patch += 1;
-					}
-					let yx_lower_pits = REVERSE_ZLUT[self.index];
-					x = ((self.patch % self.array.pwidth) << 3) | (yx_lower_pits & 0x07) as usize;
-					y = ((self.patch / self.array.pwidth) << 3) | ((yx_lower_pits >> 3) & 0x07) as usize;
-					if x < self.array.width && y < self.array.height{
-						break;
-					}
-					if self.patch >= self.array.patches.len() {
-						self.state = IterState::Done;
-						return None;
-					}
+		let item = self.iter.next()?;
+		Some(ZArray2DIteratorItem{x: item.coord[0], y: item.coord[1], value: item.value})
+	}
+
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		self.iter.size_hint()
+	}
+}
+
+impl<'a, T, const E: usize> ExactSizeIterator for ZArray2DRasterIterator<'a, T, E> {}
+
+/// Iterator that iterates through a rectangular region of the array in Z-order
+pub struct ZArray2DRegionIterator<'a, T, const E: usize = DEFAULT_PATCH_EDGE> {
+	iter: ZArrayNDRegionIterator<'a, T, 2, E>
+}
+
+impl<'a, T, const E: usize> Iterator for ZArray2DRegionIterator<'a, T, E> {
+	type Item = ZArray2DIteratorItem<'a, T>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		let item = self.iter.next()?;
+		Some(ZArray2DIteratorItem{x: item.coord[0], y: item.coord[1], value: item.value})
+	}
+}
+
+/// Iterator that iterates through a rectangular region of the array in Z-order, providing
+/// mutable access to each value
+pub struct ZArray2DRegionIteratorMut<'a, T, const E: usize = DEFAULT_PATCH_EDGE> {
+	iter: ZArrayNDRegionIteratorMut<'a, T, 2, E>
+}
+
+impl<'a, T, const E: usize> Iterator for ZArray2DRegionIteratorMut<'a, T, E> {
+	type Item = ZArray2DIteratorItemMut<'a, T>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		let item = self.iter.next()?;
+		Some(ZArray2DIteratorItemMut{x: item.coord[0], y: item.coord[1], value: item.value})
+	}
+}
+
+/// This enum specifies the shape of the neighborhood around a cell for
+/// *ZArray2D.neighbors(...)*
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Neighborhood<'a> {
+	/// All cells within the radius in both the X and Y directions (the 8 surrounding cells
+	/// for radius 1)
+	Moore,
+	/// All cells within the radius in Manhattan distance (the 4 adjacent cells for radius 1)
+	VonNeumann,
+	/// A custom list of (dx, dy) offsets relative to the center cell
+	Custom(&'a [(isize, isize)])
+}
+
+/// This struct is used by `ZArray2DNeighborIterator` to present neighbors to the consumer of
+/// the iterator
+#[derive(Debug)]
+pub struct ZArray2DNeighborItem<'a, T> {
+	/// x-dimension coordinate of the neighbor (before any wrapping is applied)
+	pub x: isize,
+	/// y-dimension coordinate of the neighbor (before any wrapping is applied)
+	pub y: isize,
+	/// reference to value of the neighbor
+	pub value: &'a T
+}
+
+/// private enum for generating the offsets of a neighborhood
+enum Offsets<'a> {
+	/// square stencil, filtered by Manhattan distance if von_neumann is true
+	Stencil { radius: isize, von_neumann: bool, next: (isize, isize) },
+	Custom(Iter<'a, (isize, isize)>)
+}
+
+impl<'a> Iterator for Offsets<'a> {
+	type Item = (isize, isize);
+
+	fn next(&mut self) -> Option<Self::Item> {
+		match self {
+			Offsets::Custom(iter) => iter.next().copied(),
+			Offsets::Stencil { radius, von_neumann, next } => loop {
+				let (dx, dy) = *next;
+				if dy > *radius {
+					return None;
+				}
+				next.0 += 1;
+				if next.0 > *radius {
+					next.0 = -*radius;
+					next.1 += 1;
+				}
+				if (dx, dy) != (0, 0) && (!*von_neumann || dx.abs() + dy.abs() <= *radius) {
+					return Some((dx, dy));
 				}
-				Some(ZArray2DIteratorItem{x, y, value: &self.array.patches[self.patch].contents[self.index]})
 			}
 		}
 	}
 }
 
+/// Iterator that iterates through the neighbors of a cell
+pub struct ZArray2DNeighborIterator<'a, T, const E: usize = DEFAULT_PATCH_EDGE> {
+	array: &'a ZArray2D<T, E>,
+	x: isize,
+	y: isize,
+	edge_mode: &'a EdgeMode<T>,
+	offsets: Offsets<'a>,
+	/// data patch that contains the whole neighborhood, if there is one
+	patch: Option<&'a [T]>,
+}
+
+impl<'a, T, const E: usize> ZArray2DNeighborIterator<'a, T, E> {
+	fn new(array: &'a ZArray2D<T, E>, x: usize, y: usize, kind: Neighborhood<'a>, radius: usize,
+		edge_mode: &'a EdgeMode<T>) -> ZArray2DNeighborIterator<'a, T, E> {
+		let (x, y) = (x as isize, y as isize);
+		// bounding box of the neighborhood, relative to the center
+		let (offsets, min, max) = match kind {
+			Neighborhood::Custom(list) => {
+				let min = list.iter().fold((0, 0), |a, b| (a.0.min(b.0), a.1.min(b.1)));
+				let max = list.iter().fold((0, 0), |a, b| (a.0.max(b.0), a.1.max(b.1)));
+				(Offsets::Custom(list.iter()), min, max)
+			},
+			_ => {
+				let r = radius as isize;
+				let von_neumann = kind == Neighborhood::VonNeumann;
+				(Offsets::Stencil { radius: r, von_neumann, next: (-r, -r) }, (-r, -r), (r, r))
+			}
+		};
+		let patch = array.array.patch_spanning([x + min.0, y + min.1], [x + max.0, y + max.1]);
+		ZArray2DNeighborIterator{array, x, y, edge_mode, offsets, patch}
+	}
+}
+
+impl<'a, T, const E: usize> Iterator for ZArray2DNeighborIterator<'a, T, E> {
+	type Item = ZArray2DNeighborItem<'a, T>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		loop {
+			let (dx, dy) = self.offsets.next()?;
+			let (x, y) = (self.x + dx, self.y + dy);
+			let value = match self.patch {
+				Some(patch) => Ok(&patch[zorder_index::<2, E>(&[x as usize, y as usize])]),
+				None => self.array.sample(x, y, self.edge_mode),
+			};
+			if let Ok(value) = value {
+				return Some(ZArray2DNeighborItem{x, y, value});
+			}
+		}
+	}
+}
+
+/// Iterator that iterates through a rectangular region of signed coordinates in raster order,
+/// mapping out-of-bounds coordinates according to the topology of the array
+pub struct ZArray2DBoundedRegionIterator<'a, T, const E: usize = DEFAULT_PATCH_EDGE> {
+	iter: ZArrayNDBoundedRegionIterator<'a, T, 2, E>
+}
+
+impl<'a, T, const E: usize> Iterator for ZArray2DBoundedRegionIterator<'a, T, E> {
+	type Item = ZArray2DIteratorItem<'a, T>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		let item = self.iter.next()?;
+		Some(ZArray2DIteratorItem{x: item.coord[0], y: item.coord[1], value: item.value})
+	}
+}
+
+/// This struct is used by `ZArray2DIteratorMut` to present mutable values to the consumer of
+/// the iterator
+#[derive(Debug)]
+pub struct ZArray2DIteratorItemMut<'a, T> {
+	/// x-dimension coordinate
+	pub x: usize,
+	/// y-dimension coordinate
+	pub y: usize,
+	/// mutable reference to value at this coordinate
+	pub value: &'a mut T
+}
+
+/// Iterator that iterates through the array in Z-order, providing mutable access to each value
+pub struct ZArray2DIteratorMut<'a, T, const E: usize = DEFAULT_PATCH_EDGE> {
+	iter: ZArrayNDIteratorMut<'a, T, 2, E>
+}
+
+impl<'a, T, const E: usize> Iterator for ZArray2DIteratorMut<'a, T, E> {
+	type Item = ZArray2DIteratorItemMut<'a, T>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		let item = self.iter.next()?;
+		Some(ZArray2DIteratorItemMut{x: item.coord[0], y: item.coord[1], value: item.value})
+	}
+}
+
+/// This struct is used by `ZArray2DIntoIterator` to present owned values to the consumer of
+/// the iterator
+#[derive(Debug)]
+pub struct ZArray2DIntoIteratorItem<T> {
+	/// x-dimension coordinate
+	pub x: usize,
+	/// y-dimension coordinate
+	pub y: usize,
+	/// value that was stored at this coordinate
+	pub value: T
+}
+
+/// Iterator that consumes the array, moving each value out in Z-order. Values stored in the
+/// padding outside of the array's dimensions are dropped.
+pub struct ZArray2DIntoIterator<T, const E: usize = DEFAULT_PATCH_EDGE> {
+	iter: ZArrayNDIntoIterator<T, 2, E>
+}
+
+impl<T, const E: usize> Iterator for ZArray2DIntoIterator<T, E> {
+	type Item = ZArray2DIntoIteratorItem<T>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		let item = self.iter.next()?;
+		Some(ZArray2DIntoIteratorItem{x: item.coord[0], y: item.coord[1], value: item.value})
+	}
+}
+
+impl<T, const E: usize> IntoIterator for ZArray2D<T, E> {
+	type Item = ZArray2DIntoIteratorItem<T>;
+	type IntoIter = ZArray2DIntoIterator<T, E>;
+
+	/// Consumes the 2D array and iterates through its values in Z-order
+	fn into_iter(self) -> Self::IntoIter {
+		ZArray2DIntoIterator{iter: self.array.into_iter()}
+	}
+}
+
+impl<'a, T, const E: usize> IntoIterator for &'a ZArray2D<T, E> {
+	type Item = ZArray2DIteratorItem<'a, T>;
+	type IntoIter = ZArray2DIterator<'a, T, E>;
+
+	fn into_iter(self) -> Self::IntoIter {
+		self.iter()
+	}
+}
+
+impl<'a, T, const E: usize> IntoIterator for &'a mut ZArray2D<T, E> {
+	type Item = ZArray2DIteratorItemMut<'a, T>;
+	type IntoIter = ZArray2DIteratorMut<'a, T, E>;
+
+	fn into_iter(self) -> Self::IntoIter {
+		self.iter_mut()
+	}
+}
+
 /// Used for Z-index look-up
 const ZLUT: [u8; 16] = [
 	0b00000000,
@@ -578,13 +1646,6 @@ const ZLUT: [u8; 16] = [
 	0b01010101
 ];
 
-/// used by iterators for fast conversion from internal index to X, Y. Each number is 0byyyxxx
-const REVERSE_ZLUT: [u8; 64] = [
-	0 ,  1,  8,  9,  2,  3, 10, 11, 16, 17, 24, 25, 18, 19, 26, 27,
-	4 ,  5, 12, 13,  6,  7, 14, 15, 20, 21, 28, 29, 22, 23, 30, 31,
-	32, 33, 40, 41, 34, 35, 42, 43, 48, 49, 56, 57, 50, 51, 58, 59,
-	36, 37, 44, 45, 38, 39, 46, 47, 52, 53, 60, 61, 54, 55, 62, 63
-];
 
 /// General purpose Z-index function to convert a two-dimensional coordinate into a localized
 /// one-dimensional coordinate
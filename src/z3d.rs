@@ -19,7 +19,7 @@
 //! let rock_hardness = 8f32;
 //! let drip_power = 1.5f32;
 //! let iterations = 12;
-//! let mut map = ZArray3D::new(width, length, depth, air);
+//! let mut map = ZArray3D::<_>::new(width, length, depth, air);
 //! map.fill(0,0,5, width,length,depth, soil_hardness).unwrap();
 //! map.fill(0,0,15, width,length,depth, rock_hardness).unwrap();
 //! for boulder in [(34,88,6), (66,122,9), (11,154,5), (35,93,8), (72,75,12)]{
@@ -45,137 +45,116 @@
 //! ```
 // Z-order indexing in 3 dimensions
 
-use core::hash::{Hash, Hasher};
+use alloc::vec::Vec;
 use core::borrow::Borrow;
-use core::marker::PhantomData;
-use array_init::array_init;
-use crate::LookUpError;
+use core::ops::{Add, Index, IndexMut, Mul, RangeBounds};
+use core::slice::Iter;
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+use crate::{clamp_range, signed_range, AxisTopology, BuildError, EdgeMode, ErrorCoord, LookUpError};
+use crate::z2d::ZArray2D;
+use crate::znd::{StencilView, ZArrayND, ZArrayNDBoundedRegionIterator, ZArrayNDIntoIterator, ZArrayNDIterator,
+	ZArrayNDIteratorMut, ZArrayNDRasterIterator, ZArrayNDRegionIterator, ZArrayNDRegionIteratorMut,
+	ZArrayNDSnapshot, ZArrayNDView, ZArrayNDViewIterator, ZArrayNDViewIteratorMut, ZArrayNDViewMut, DEFAULT_PATCH_EDGE, zorder_index};
 
-
-/// Private struct for holding an 8x8x8 data patch
-#[derive(Debug)]
-#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
-struct Patch<T>{
-	contents: [T;512]
+/// This is primary struct for z-indexed 3D arrays. Create new instances with
+/// ZArray3D::<_>::new(x_size, y_size, z_size, initial_value)
+///
+/// *ZArray3D* is a typed front-end for a 3-dimensional [ZArrayND], which
+/// holds the data and implements the look-ups, and it can be converted to and from a
+/// *ZArrayND<T, 3, E>* with `From`/`Into`.
+///
+/// The optional parameter *E* is the length of the data patches in every dimension, which may
+/// be 4, 8 (the default), or 16 (see [ZArrayND]). The constructors of *ZArray3D* are generic
+/// over *E*, so the patch length is picked by the type, eg
+/// `let a = ZArray3D::<u64, 4>::new(xsize, ysize, zsize, 0u64);` (write
+/// `ZArray3D::<_>::new(...)` to use the default patch length when the type is not otherwise
+/// known)
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ZArray3D<T, const E: usize = DEFAULT_PATCH_EDGE> {
+	// for heap allocated data
+	array: ZArrayND<T, 3, E>,
 }
 
-impl<T> Copy for Patch<T> where T: Copy{}
-impl<T> Clone for Patch<T> where T: Clone{
-	fn clone(&self) -> Self {
-			Self{contents: self.contents.clone()}
+impl<T, const E: usize> From<ZArrayND<T, 3, E>> for ZArray3D<T, E> {
+	/// Wraps a 3-dimensional *ZArrayND* as a *ZArray3D*, where the dimensions are X, Y, and Z
+	/// in that order
+	fn from(array: ZArrayND<T, 3, E>) -> Self {
+		ZArray3D { array }
 	}
 }
-impl<T> PartialEq for Patch<T> where T: PartialEq{
-	fn eq(&self, other: &Self) -> bool {
-			self.contents.eq(&other.contents)
+
+impl<T, const E: usize> From<ZArray3D<T, E>> for ZArrayND<T, 3, E> {
+	/// Unwraps a *ZArray3D* into the 3-dimensional *ZArrayND* that holds its data
+	fn from(array: ZArray3D<T, E>) -> Self {
+		array.array
 	}
 }
-impl<T> Eq for Patch<T> where T: Eq{}
-impl<T> Hash for Patch<T> where T: Hash{
-	fn hash<H: Hasher>(&self, state: &mut H) {
-		for item in &self.contents {
-			item.hash(state);
+
+impl<T, const E: usize> TryFrom<Vec<Vec<Vec<T>>>> for ZArray3D<T, E> where T: Clone {
+	type Error = BuildError;
+	/// Moves the values of a *Vec* of XY planes of rows (where the value of (x, y, z) is at
+	/// `[z][y][x]`) into a *ZArray3D*, returning a *BuildError::Ragged* if the planes do not
+	/// all have the same number of rows or the rows do not all have the same length
+	fn try_from(planes: Vec<Vec<Vec<T>>>) -> Result<Self, Self::Error> {
+		let ysize = planes.first().map_or(0, |plane| plane.len());
+		let xsize = planes.first().and_then(|plane| plane.first()).map_or(0, |row| row.len());
+		for (z, plane) in planes.iter().enumerate() {
+			if plane.len() != ysize {
+				return Err(BuildError::Ragged { index: ErrorCoord::new(&[z]), expected: ysize, actual: plane.len() });
+			}
+			if let Some((y, row)) = plane.iter().enumerate().find(|(_, row)| row.len() != xsize) {
+				return Err(BuildError::Ragged { index: ErrorCoord::new(&[y, z]), expected: xsize, actual: row.len() });
+			}
 		}
+		let zsize = planes.len();
+		let values: Vec<T> = planes.into_iter().flatten().flatten().collect();
+		return Ok(ZArrayND::from_row_major_vec([xsize, ysize, zsize], values).into());
 	}
 }
 
-impl<T> Patch<T> {
-	/// data patch getter
-	/// # Parameters
-	/// * **x** - x coord (only lowest 3 bits are used, rest of bits are ignored)
-	/// * **y** - y coord (only lowest 3 bits are used, rest of bits are ignored)
-	/// * **z** - z coord (only lowest 3 bits are used, rest of bits are ignored)
-	/// # Returns
-	/// Returns a reference to the value stored in the patch at location (x, y, z) (lowest 3
-	/// bits only)
-	fn get(&self, x: usize, y:usize, z:usize) -> &T {
-		// 3-bit x 3-bit x 3-bit
-		return &self.contents[zorder_4bit_to_12bit(
-			x as u8 & 0x07, y as u8 & 0x07, z as u8 & 0x07) as usize];
-	}
-	/// data patch setter
-	/// # Parameters
-	/// * **x** - x coord (only lowest 3 bits are used, rest of bits are ignored)
-	/// * **y** - y coord (only lowest 3 bits are used, rest of bits are ignored)
-	/// * **z** - z coord (only lowest 3 bits are used, rest of bits are ignored)
-	/// * **new_val** - value to set at (x,y,z)
-	fn set(&mut self, x: usize, y:usize, z:usize, new_val: T) {
-		// 3-bit x 3-bit
-		let i = zorder_4bit_to_12bit(
-			x as u8 & 0x07, y as u8 & 0x07, z as u8 & 0x07) as usize;
-		self.contents[i] = new_val;
+impl<T, const E: usize> AsRef<ZArrayND<T, 3, E>> for ZArray3D<T, E> {
+	fn as_ref(&self) -> &ZArrayND<T, 3, E> {
+		&self.array
 	}
 }
 
-/// function for converting coordinate to index of data patch in the array of patches
-fn patch_index(x: usize, y:usize, z:usize, pxsize: usize, pysize: usize) -> usize{
-	return (x >> 3) + pxsize * ((y >> 3) + (pysize * (z >> 3)));
-}
-
-/// function for getting the coords represented by a patch
-fn patch_coords(pxsize: usize, pysize: usize, pindex: usize) -> [(usize, usize, usize); 512] {
-	let mut outbuffer = [(0usize, 0usize, 0usize); 512];
-	let bx = (pindex % pxsize) << 3;
-	let by = ((pindex / pxsize) % pysize) << 3;
-	let bz = (pindex / (pxsize * pysize)) << 3;
-	for i in 0..512 {
-		let bitmask = REVERSE_ZLUT[i];
-		let dx = bitmask & 0b00000111u16;
-		let dy = (bitmask >> 3u16) & 0b00000111u16;
-		let dz = (bitmask >> 6u16) & 0b00000111u16;
-		outbuffer[i] = (bx + dx as usize, by + dy as usize, bz + dz as usize);
+impl<T, const E: usize> AsMut<ZArrayND<T, 3, E>> for ZArray3D<T, E> {
+	fn as_mut(&mut self) -> &mut ZArrayND<T, 3, E> {
+		&mut self.array
 	}
-	return outbuffer;
 }
 
-/// This is primary struct for z-indexed 3D arrays. Create new instances with
-/// ZArray3D::new(x_size, y_size, z_size, initial_value)
-#[derive(Debug)]
-#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
-pub struct ZArray3D<T> {
-	// for heap allocated data
-	xsize: usize,
-	ysize: usize,
-	zsize: usize,
-	pxsize: usize,
-	pysize: usize,
-	patches: Vec<Patch<T>>,
-	_phantomdata: PhantomData<T>,
+impl<T, const E: usize> Index<(usize, usize, usize)> for ZArray3D<T, E> {
+	type Output = T;
+	/// Gets a reference to the value at coordinate (x, y, z), panicking with the *LookUpError*
+	/// message if the coordinate is out of bounds
+	fn index(&self, index: (usize, usize, usize)) -> &Self::Output {
+		&self.array[[index.0, index.1, index.2]]
+	}
 }
-
-impl<T> Clone for ZArray3D<T> where T: Clone{
-	fn clone(&self) -> Self {
-			Self{
-				xsize: self.xsize,
-				ysize: self.ysize,
-				zsize: self.zsize,
-				pxsize: self.pxsize,
-				pysize: self.pysize,
-				patches: self.patches.clone(),
-				_phantomdata: self._phantomdata
-			}
+impl<T, const E: usize> IndexMut<(usize, usize, usize)> for ZArray3D<T, E> {
+	/// Gets a mutable reference to the value at coordinate (x, y, z), panicking with the
+	/// *LookUpError* message if the coordinate is out of bounds
+	fn index_mut(&mut self, index: (usize, usize, usize)) -> &mut Self::Output {
+		&mut self.array[[index.0, index.1, index.2]]
 	}
 }
-impl<T> PartialEq for ZArray3D<T> where T: PartialEq{
-	fn eq(&self, other: &Self) -> bool {
-		self.xsize == other.xsize
-		&& self.ysize == other.ysize
-		&& self.zsize == other.zsize
-		&& self.pxsize == other.pxsize
-		&& self.pysize == other.pysize
-		&& self.patches == other.patches
+impl<T, const E: usize> Index<[usize; 3]> for ZArray3D<T, E> {
+	type Output = T;
+	/// Same as indexing with an (x, y, z) tuple
+	fn index(&self, index: [usize; 3]) -> &Self::Output {
+		&self.array[index]
 	}
 }
-impl<T> Eq for ZArray3D<T> where T: Eq{}
-impl<T> Hash for ZArray3D<T> where T: Hash{
-	fn hash<H: Hasher>(&self, state: &mut H) {
-		for patch in &self.patches {
-			patch.hash(state);
-		}
+impl<T, const E: usize> IndexMut<[usize; 3]> for ZArray3D<T, E> {
+	/// Same as indexing with an (x, y, z) tuple
+	fn index_mut(&mut self, index: [usize; 3]) -> &mut Self::Output {
+		&mut self.array[index]
 	}
 }
 
-impl<T> ZArray3D<T> where T: Default {
+impl<T, const E: usize> ZArray3D<T, E> where T: Default {
 	/// Create a Z-index 3D array of values, initially filled with the default values
 	/// # Parameters
 	/// * **xsize** - size of this 3D array in the X dimension
@@ -183,22 +162,44 @@ impl<T> ZArray3D<T> where T: Default {
 	/// * **zsize** - size of this 3D array in the Z dimension
 	/// # Returns
 	/// Returns an initialized *ZArray2D* struct filled with default values
-	pub fn new_with_default(xsize: usize, ysize: usize, zsize: usize) -> ZArray3D<T> {
-		let px = ((xsize-1) >> 3) + 1;
-		let py = ((ysize-1) >> 3) + 1;
-		let pz = ((zsize-1) >> 3) + 1;
-		let patch_count = px * py * pz;
-		let mut p = Vec::with_capacity(patch_count);
-		for _ in 0..patch_count {
-			let default_contents: [T; 512] = array_init(|_|T::default());
-			p.push(Patch { contents: default_contents });
-		}
-		return ZArray3D { xsize, ysize, zsize, pxsize: px, pysize: py,
-			patches: p, _phantomdata: PhantomData};
+	pub fn new_with_default(xsize: usize, ysize: usize, zsize: usize) -> ZArray3D<T, E> {
+		return ZArrayND::new_with_default([xsize, ysize, zsize]).into();
+	}
+
+	/// Create a Z-index 3D array from an iterator of values in row-major order (ie X changes
+	/// fastest, then Y, then Z). The iterator must provide exactly *xsize* x *ysize* x *zsize*
+	/// values.
+	/// # Parameters
+	/// * **xsize** - size of this 3D array in the X dimension
+	/// * **ysize** - size of this 3D array in the Y dimension
+	/// * **zsize** - size of this 3D array in the Z dimension
+	/// * **values** - iterator of the values to store, in row-major order
+	/// # Returns
+	/// Returns a Result type that holds either the new *ZArray3D* or a
+	/// *BuildError::SizeMismatch* if the number of values does not match the dimensions
+	pub fn from_iter_row_major(xsize: usize, ysize: usize, zsize: usize, values: impl IntoIterator<Item=T>)
+		-> Result<ZArray3D<T, E>, BuildError> {
+		return ZArrayND::from_iter_row_major([xsize, ysize, zsize], values).map(ZArray3D::from);
+	}
+
+	/// Create a Z-index 3D array from an iterator of coordinate and value pairs, in any order.
+	/// The iterator must provide exactly one value for every coordinate of the array.
+	/// # Parameters
+	/// * **xsize** - size of this 3D array in the X dimension
+	/// * **ysize** - size of this 3D array in the Y dimension
+	/// * **zsize** - size of this 3D array in the Z dimension
+	/// * **values** - iterator of ((x, y, z), value) pairs
+	/// # Returns
+	/// Returns a Result type that holds either the new *ZArray3D* or a *BuildError* if a
+	/// coordinate is out of bounds, provided twice, or missing
+	pub fn from_iter_coords(xsize: usize, ysize: usize, zsize: usize, values: impl IntoIterator<Item=((usize, usize, usize), T)>)
+		-> Result<ZArray3D<T, E>, BuildError> {
+		let values = values.into_iter().map(|((x, y, z), v)| ([x, y, z], v));
+		return ZArrayND::from_iter_coords([xsize, ysize, zsize], values).map(ZArray3D::from);
 	}
 }
 
-impl<T> ZArray3D<T> where T: Copy {
+impl<T, const E: usize> ZArray3D<T, E> where T: Copy {
 	/// Create a Z-index 3D array of values, initially filled with the provided default value
 	/// # Parameters
 	/// * **xsize** - size of this 3D array in the X dimension
@@ -208,21 +209,29 @@ impl<T> ZArray3D<T> where T: Copy {
 	/// Copy trait)
 	/// # Returns
 	/// Returns an initialized *ZArray3D* struct filled with *default_val*
-	pub fn new(xsize: usize, ysize: usize, zsize: usize, default_val: T) -> ZArray3D<T>{
-		let px = ((xsize-1) >> 3) + 1;
-		let py = ((ysize-1) >> 3) + 1;
-		let pz = ((zsize-1) >> 3) + 1;
-		let patch_count = px * py * pz;
-		let mut p = Vec::with_capacity(patch_count);
-		for _ in 0..patch_count{
-			p.push(Patch{contents: [default_val; 512]});
-		}
-		return ZArray3D { xsize, ysize, zsize, pxsize: px, pysize: py,
-			patches: p, _phantomdata: PhantomData};
+	pub fn new(xsize: usize, ysize: usize, zsize: usize, default_val: T) -> ZArray3D<T, E>{
+		return ZArrayND::new([xsize, ysize, zsize], default_val).into();
+	}
+}
+
+impl<T, const E: usize> ZArray3D<T, E> where T: Clone {
+	/// Create a Z-index 3D array from a slice of values in raster order (X changes fastest,
+	/// then Y, then Z), copying the values one data patch at a time
+	/// # Parameters
+	/// * **xsize** - size of this 3D array in the X dimension
+	/// * **ysize** - size of this 3D array in the Y dimension
+	/// * **zsize** - size of this 3D array in the Z dimension
+	/// * **values** - the values in raster order (the value of (x, y, z) is at index
+	/// x + xsize * (y + ysize * z))
+	/// # Returns
+	/// Returns a Result type that holds either the new *ZArray3D* or a
+	/// *BuildError::SizeMismatch* if the number of values is not xsize * ysize * zsize
+	pub fn from_row_major(xsize: usize, ysize: usize, zsize: usize, values: &[T]) -> Result<ZArray3D<T, E>, BuildError> {
+		return ZArrayND::from_row_major([xsize, ysize, zsize], values).map(ZArray3D::from);
 	}
 }
 
-impl<T> ZArray3D<T> where T: Clone {
+impl<T, const E: usize> ZArray3D<T, E> where T: Clone {
 	
 	/// Fills a region of this 3D array with a given value, or returns a *LookUpError* if the
 	/// provided coordinates go out of bounds. If you just want to ignore any
@@ -243,10 +252,7 @@ impl<T> ZArray3D<T> where T: Clone {
 	/// Returns a Result type that is either empty or a *LookUpError* signalling that a
 	/// coordinate is out of bounds
 	pub fn fill(&mut self, x1: usize, y1: usize, z1: usize, x2: usize, y2: usize, z2: usize, new_val: impl Borrow<T>) -> Result<(), LookUpError> {
-		for y in y1..y2{ for x in x1..x2{ for z in z1..z2{
-			self.set(x, y, z, new_val.borrow().clone())?;
-		} } }
-		Ok(())
+		return self.array.fill([x1, y1, z1], [x2, y2, z2], new_val);
 	}
 
 		/// Fills a region of this 3D array with a given value, wrapping the axese when
@@ -262,13 +268,14 @@ impl<T> ZArray3D<T> where T: Clone {
 		/// (x1, y1, z1) -> (x2, y2, z2)
 		pub fn wrapped_fill(&mut self, x1: isize, y1: isize, z1: isize,
 						x2: isize, y2: isize, z2: isize, new_val: impl Borrow<T>) {
-		for y in y1..y2{ for x in x1..x2{ for z in z1..z2{
-			self.wrapped_set(x, y, z, new_val.borrow().clone());
-		} } }
+		self.array.wrapped_fill([x1, y1, z1], [x2, y2, z2], new_val);
 	}
 
 		/// Fills a region of this 3D array with a given value, ignoring any
-		/// coordinates that go out of bounds.
+		/// coordinates that go out of bounds. Coordinates outside of a wrapped or clamped axis
+		/// are handled by the topology of this 3D array (see
+		/// *set_topology(x_axis, y_axis, z_axis)*), so on a toroidal world the region can cross
+		/// the edges of the wrapped axes.
 		/// # Parameters
 		/// * **x1** - the first x dimension coordinate (inclusive)
 		/// * **y1** - the first y dimension coordinate (inclusive)
@@ -280,13 +287,237 @@ impl<T> ZArray3D<T> where T: Clone {
 		/// (x1, y1, z1) -> (x2, y2, z2)
 		pub fn bounded_fill(&mut self, x1: isize, y1: isize, z1: isize,
 						x2: isize, y2: isize, z2: isize, new_val: impl Borrow<T>) {
-		for y in y1..y2{ for x in x1..x2{ for z in z1..z2{
-			self.bounded_set(x, y, z, new_val.borrow().clone());
-		} } }
+		self.array.bounded_fill([x1, y1, z1], [x2, y2, z2], new_val);
+	}
+
+	/// Fills a region of this 3D array with a given value, using the provided edge mode to
+	/// handle coordinates that are negative or outside the size of this 3D array.
+	/// # Parameters
+	/// * **x1** - the first x dimension coordinate (inclusive)
+	/// * **y1** - the first y dimension coordinate (inclusive)
+	/// * **z1** - the first z dimension coordinate (inclusive)
+	/// * **x2** - the second x dimension coordinate (exclusive)
+	/// * **y2** - the second y dimension coordinate (exclusive)
+	/// * **z2** - the second z dimension coordinate (exclusive)
+	/// * **edge_mode** - how to handle out-of-bounds coordinates
+	/// * **new_val** - value to store in the 3D array in the bounding box defined by
+	/// (x1, y1, z1) -> (x2, y2, z2)
+	/// # Returns
+	/// Returns a Result type that is either empty or a *LookUpError* if a coordinate is out
+	/// of bounds and the edge mode is *EdgeMode::Error*
+	pub fn sample_fill(&mut self, x1: isize, y1: isize, z1: isize, x2: isize, y2: isize, z2: isize,
+		edge_mode: &EdgeMode<T>, new_val: impl Borrow<T>) -> Result<(), LookUpError> {
+		return self.array.sample_fill([x1, y1, z1], [x2, y2, z2], edge_mode, new_val);
+	}
+
+	/// Changes the size of this 3D array, keeping the existing values at their coordinates.
+	/// Values outside the new size are dropped and new cells are set to the fill value. Whole
+	/// data patches are copied, so this is much faster than copying one value at a time.
+	/// # Parameters
+	/// * **new_xsize** - new x dimension size
+	/// * **new_ysize** - new y dimension size
+	/// * **new_zsize** - new z dimension size
+	/// * **fill** - value for the new cells
+	/// # Panics
+	/// Panics if the new size overflows *usize* (including the padding to whole data patches)
+	pub fn resize(&mut self, new_xsize: usize, new_ysize: usize, new_zsize: usize, fill: impl Borrow<T>) {
+		self.array.resize([new_xsize, new_ysize, new_zsize], fill);
+	}
+
+	/// Cuts this 3D array down to a box, such that (x, y, z) becomes (0, 0, 0). Whole data
+	/// patches are copied if x, y and z are multiples of the patch length (eg 8), otherwise
+	/// the values are re-packed.
+	/// # Parameters
+	/// * **x** - x dimension coordinate of the first corner of the box to keep
+	/// * **y** - y dimension coordinate of the first corner of the box to keep
+	/// * **z** - z dimension coordinate of the first corner of the box to keep
+	/// * **xsize** - x dimension size of the box
+	/// * **ysize** - y dimension size of the box
+	/// * **zsize** - z dimension size of the box
+	/// # Returns
+	/// Returns a Result type that is either empty or a *LookUpError* signalling that the box
+	/// goes out of bounds (in which case this 3D array is unchanged)
+	pub fn crop(&mut self, x: usize, y: usize, z: usize, xsize: usize, ysize: usize, zsize: usize) -> Result<(), LookUpError> {
+		return self.array.crop([x, y, z], [xsize, ysize, zsize]);
+	}
+
+	/// Adds cells around the edges of this 3D array, moving the existing values by
+	/// (left, top, front). Whole data patches are copied if left, top and front are multiples
+	/// of the patch length (eg 8), otherwise the values are re-packed.
+	/// # Parameters
+	/// * **left** - number of cells to add before x = 0
+	/// * **right** - number of cells to add after the last x coordinate
+	/// * **top** - number of cells to add before y = 0
+	/// * **bottom** - number of cells to add after the last y coordinate
+	/// * **front** - number of cells to add before z = 0
+	/// * **back** - number of cells to add after the last z coordinate
+	/// * **fill** - value for the new cells
+	/// # Panics
+	/// Panics if the new size overflows *usize* (including the padding to whole data patches)
+	pub fn pad(&mut self, left: usize, right: usize, top: usize, bottom: usize, front: usize, back: usize,
+		fill: impl Borrow<T>) {
+		self.array.pad([left, top, front], [right, bottom, back], fill);
+	}
+
+	/// Takes a snapshot of this 3D array, which copies the values into reference-counted data
+	/// patches (see [ZArrayND::snapshot()])
+	/// # Returns
+	/// Returns a snapshot of the values of this array
+	pub fn snapshot(&self) -> ZArrayNDSnapshot<T, 3, E> {
+		return self.array.snapshot();
+	}
+
+	/// Takes a snapshot of this 3D array that shares the data patches that have not changed
+	/// since a previous snapshot of this array, so it only copies the patches that changed
+	/// (see [ZArrayND::snapshot_since()])
+	/// # Parameters
+	/// * **previous** - an earlier snapshot of this array
+	/// # Returns
+	/// Returns a snapshot of the values of this array
+	pub fn snapshot_since(&self, previous: &ZArrayNDSnapshot<T, 3, E>) -> ZArrayNDSnapshot<T, 3, E> {
+		return self.array.snapshot_since(previous);
+	}
+
+	/// Sets the values of this 3D array back to those of a snapshot, copying only the data
+	/// patches that changed since the snapshot was taken (see [ZArrayND::restore()])
+	/// # Parameters
+	/// * **snapshot** - the snapshot to restore
+	pub fn restore(&mut self, snapshot: &ZArrayNDSnapshot<T, 3, E>) {
+		self.array.restore(snapshot);
+	}
+
+	/// Copies the values of this 3D array into a *Vec* in raster order (X changes fastest,
+	/// then Y, then Z), one row of data patches at a time
+	/// # Returns
+	/// Returns a *Vec* where the value of (x, y, z) is at index x + xsize * (y + ysize * z)
+	pub fn to_row_major(&self) -> Vec<T> {
+		return self.array.to_row_major();
+	}
+
+	/// Copies the values of this 3D array into a *Vec* of XY planes, each of which is a *Vec*
+	/// of rows
+	/// # Returns
+	/// Returns a *Vec* of *zsize* planes of *ysize* rows of *xsize* values, where the value of
+	/// (x, y, z) is at `[z][y][x]`
+	pub fn to_vec_of_vecs(&self) -> Vec<Vec<Vec<T>>> {
+		let (xsize, ysize, zsize) = self.dimensions();
+		let mut values = self.to_row_major().into_iter();
+		return (0..zsize).map(|_| {
+			(0..ysize).map(|_| values.by_ref().take(xsize).collect()).collect()
+		}).collect();
+	}
+
+	/// private function for checking the position of a plane on its fixed axis
+	/// # Returns
+	/// Returns the coordinate that fixes the plane, or a *LookUpError* if it is out of bounds
+	fn plane_position(&self, axis: usize, at: usize) -> Result<[usize; 3], LookUpError> {
+		let dims = self.array.dimensions();
+		let mut fixed = [0; 3];
+		fixed[axis] = at;
+		if at >= dims[axis] {
+			return Err(LookUpError::new(&fixed.map(|c| c as isize), &dims));
+		}
+		return Ok(fixed);
+	}
+
+	/// private function for writing a 2D array into a plane, after checking its position and
+	/// size
+	fn set_plane(&mut self, axes: [usize; 2], axis: usize, at: usize, slice: &ZArray2D<T, E>) -> Result<(), BuildError> {
+		let fixed = self.plane_position(axis, at)?;
+		let dims = self.array.dimensions();
+		let expected = [dims[axes[0]], dims[axes[1]]];
+		let actual = slice.as_nd().dimensions();
+		if actual != expected {
+			return Err(BuildError::DimensionMismatch { expected: ErrorCoord::new(&expected), actual: ErrorCoord::new(&actual) });
+		}
+		self.array.paste_plane(axes, fixed, slice.as_nd());
+		return Ok(());
+	}
+
+	/// Copies an XY cross-section (all X and Y coordinates at a given Z coordinate) of this 3D
+	/// array into a new 2D array, one data patch at a time. To read the cross-section without
+	/// copying it, use a view instead, eg `view(.., .., z..=z)`.
+	/// # Parameters
+	/// * **z** - z dimension coordinate of the cross-section
+	/// # Returns
+	/// Returns a Result type that holds either a new *ZArray2D* of size (xsize, ysize), where
+	/// (x, y) in the 2D array is (x, y, z) in this 3D array, or a *LookUpError* signalling that
+	/// the z coordinate is out of bounds
+	pub fn slice_xy(&self, z: usize) -> Result<ZArray2D<T, E>, LookUpError> {
+		let fixed = self.plane_position(2, z)?;
+		return Ok(self.array.copy_plane([0, 1], fixed).into());
+	}
+
+	/// Copies an XZ cross-section (all X and Z coordinates at a given Y coordinate) of this 3D
+	/// array into a new 2D array, one data patch at a time
+	/// # Parameters
+	/// * **y** - y dimension coordinate of the cross-section
+	/// # Returns
+	/// Returns a Result type that holds either a new *ZArray2D* of size (xsize, zsize), where
+	/// (x, z) in the 2D array is (x, y, z) in this 3D array, or a *LookUpError* signalling that
+	/// the y coordinate is out of bounds
+	pub fn slice_xz(&self, y: usize) -> Result<ZArray2D<T, E>, LookUpError> {
+		let fixed = self.plane_position(1, y)?;
+		return Ok(self.array.copy_plane([0, 2], fixed).into());
+	}
+
+	/// Copies a YZ cross-section (all Y and Z coordinates at a given X coordinate) of this 3D
+	/// array into a new 2D array, one data patch at a time
+	/// # Parameters
+	/// * **x** - x dimension coordinate of the cross-section
+	/// # Returns
+	/// Returns a Result type that holds either a new *ZArray2D* of size (ysize, zsize), where
+	/// (y, z) in the 2D array is (x, y, z) in this 3D array, or a *LookUpError* signalling that
+	/// the x coordinate is out of bounds
+	pub fn slice_yz(&self, x: usize) -> Result<ZArray2D<T, E>, LookUpError> {
+		let fixed = self.plane_position(0, x)?;
+		return Ok(self.array.copy_plane([1, 2], fixed).into());
+	}
+
+	/// Writes a 2D array into an XY cross-section of this 3D array (the reverse of
+	/// *slice_xy(z)*), one data patch at a time
+	/// # Parameters
+	/// * **z** - z dimension coordinate of the cross-section
+	/// * **slice** - the values to write, where (x, y) in the 2D array is written to (x, y, z)
+	/// in this 3D array
+	/// # Returns
+	/// Returns a Result type that is either empty or a *BuildError* signalling that the z
+	/// coordinate is out of bounds (*BuildError::OutOfBounds*) or that the size of the 2D array
+	/// is not (xsize, ysize) (*BuildError::DimensionMismatch*)
+	pub fn set_slice_xy(&mut self, z: usize, slice: &ZArray2D<T, E>) -> Result<(), BuildError> {
+		return self.set_plane([0, 1], 2, z, slice);
+	}
+
+	/// Writes a 2D array into an XZ cross-section of this 3D array (the reverse of
+	/// *slice_xz(y)*), one data patch at a time
+	/// # Parameters
+	/// * **y** - y dimension coordinate of the cross-section
+	/// * **slice** - the values to write, where (x, z) in the 2D array is written to (x, y, z)
+	/// in this 3D array
+	/// # Returns
+	/// Returns a Result type that is either empty or a *BuildError* signalling that the y
+	/// coordinate is out of bounds (*BuildError::OutOfBounds*) or that the size of the 2D array
+	/// is not (xsize, zsize) (*BuildError::DimensionMismatch*)
+	pub fn set_slice_xz(&mut self, y: usize, slice: &ZArray2D<T, E>) -> Result<(), BuildError> {
+		return self.set_plane([0, 2], 1, y, slice);
+	}
+
+	/// Writes a 2D array into a YZ cross-section of this 3D array (the reverse of
+	/// *slice_yz(x)*), one data patch at a time
+	/// # Parameters
+	/// * **x** - x dimension coordinate of the cross-section
+	/// * **slice** - the values to write, where (y, z) in the 2D array is written to (x, y, z)
+	/// in this 3D array
+	/// # Returns
+	/// Returns a Result type that is either empty or a *BuildError* signalling that the x
+	/// coordinate is out of bounds (*BuildError::OutOfBounds*) or that the size of the 2D array
+	/// is not (ysize, zsize) (*BuildError::DimensionMismatch*)
+	pub fn set_slice_yz(&mut self, x: usize, slice: &ZArray2D<T, E>) -> Result<(), BuildError> {
+		return self.set_plane([1, 2], 0, x, slice);
 	}
 }
 
-impl<T> ZArray3D<T> {
+impl<T, const E: usize> ZArray3D<T, E> {
 	/// Create a Z-index 3D array of values, initially filled with the provided constructor function.
 	/// Note that the constructor function may be called for coordinates that are outside the
 	/// requested dimensions in order to initialize memory in 8x8x8 blocks. To avoid this, use only
@@ -298,33 +529,32 @@ impl<T> ZArray3D<T> {
 	/// * **constructor** - function which takes in the (X,Y,Z) coords as a tuple and returns a value of type T
 	/// # Returns
 	/// Returns an initialized *ZArray2D* struct filled with *default_val*
-	pub fn new_with_constructor(xsize: usize, ysize: usize, zsize: usize, constructor: impl Fn((usize, usize, usize)) -> T) -> ZArray3D<T> {
-		let px = ((xsize-1) >> 3) + 1;
-		let py = ((ysize-1) >> 3) + 1;
-		let pz = ((zsize-1) >> 3) + 1;
-		let patch_count = px * py * pz;
-		let mut p = Vec::with_capacity(patch_count);
-		for pindex in 0..patch_count {
-			let lookup_table = patch_coords(px, py, pindex);
-			let initial_contents: [T; 512] = array_init(|i| constructor(lookup_table[i]));
-			p.push(Patch { contents: initial_contents });
-		}
-		return ZArray3D { xsize, ysize, zsize, pxsize: px, pysize: py,
-			patches: p, _phantomdata: PhantomData};
+	pub fn new_with_constructor(xsize: usize, ysize: usize, zsize: usize, constructor: impl Fn((usize, usize, usize)) -> T) -> ZArray3D<T, E> {
+		return ZArrayND::new_with_constructor([xsize, ysize, zsize], |[x, y, z]| constructor((x, y, z))).into();
+	}
+}
+
+impl<T, const E: usize> ZArray3D<T, E> {
+	/// Gets the 3-dimensional *ZArrayND* that holds the data of this 3D array
+	/// # Returns
+	/// Returns a reference to the *ZArrayND*, where the first dimension is X
+	pub fn as_nd(&self) -> &ZArrayND<T, 3, E> {
+		return &self.array;
 	}
 
 	/// Gets the (x, y, z) size of this 3D array
 	/// # Returns
 	/// Returns a tuple of (width, height, depth) for this 2D array
 	pub fn dimensions(&self) -> (usize, usize, usize){
-		return (self.xsize, self.ysize, self.zsize);
+		let [xsize, ysize, zsize] = self.array.dimensions();
+		return (xsize, ysize, zsize);
 	}
 
 	/// Gets the X-dimension size (aka width) of this 3D array
 	/// # Returns
 	/// Returns the size in the X dimension
 	pub fn xsize(&self) -> usize {
-		return self.xsize;
+		return self.array.dimensions()[0];
 	}
 
 
@@ -339,7 +569,7 @@ impl<T> ZArray3D<T> {
 	/// # Returns
 	/// Returns the size in the Y dimension
 	pub fn ysize(&self) -> usize {
-		return self.ysize;
+		return self.array.dimensions()[1];
 	}
 
 	/// Alias for `ysize()`
@@ -353,7 +583,7 @@ impl<T> ZArray3D<T> {
 	/// # Returns
 	/// Returns the size in the Z dimension
 	pub fn zsize(&self) -> usize {
-		return self.zsize;
+		return self.array.dimensions()[2];
 	}
 
 	/// Alias for `zsize()`
@@ -364,9 +594,47 @@ impl<T> ZArray3D<T> {
 	}
 
 
+	/// Sets the topology of this 3D array, which determines how each axis handles
+	/// out-of-bounds coordinates (eg wrapping X and Y but bounding Z makes a toroidal world
+	/// with a floor and ceiling). *get(x, y, z)*, *set(x, y, z, val)*, the
+	/// *bounded_get(x, y, z)* family of methods, *bounded_iter_region(...)*, and the methods
+	/// that take an *EdgeMode* (such as *sample(...)*, *stencil(...)* and *neighbors(...)*)
+	/// all wrap or clamp coordinates on the wrapped or clamped axes. The edge mode only
+	/// applies to the bounded axes. All axes are *AxisTopology::Bounded* by default.
+	/// # Parameters
+	/// * **x_axis** - topology of the X axis
+	/// * **y_axis** - topology of the Y axis
+	/// * **z_axis** - topology of the Z axis
+	pub fn set_topology(&mut self, x_axis: AxisTopology, y_axis: AxisTopology, z_axis: AxisTopology) {
+		self.array.set_topology([x_axis, y_axis, z_axis]);
+	}
+
+	/// Same as *set_topology(x_axis, y_axis, z_axis)*, but consumes and returns this 3D array
+	/// so that it can be chained with a constructor
+	/// # Parameters
+	/// * **x_axis** - topology of the X axis
+	/// * **y_axis** - topology of the Y axis
+	/// * **z_axis** - topology of the Z axis
+	/// # Returns
+	/// Returns this 3D array with the new topology
+	pub fn with_topology(mut self, x_axis: AxisTopology, y_axis: AxisTopology, z_axis: AxisTopology) -> ZArray3D<T, E> {
+		self.set_topology(x_axis, y_axis, z_axis);
+		return self;
+	}
+
+	/// Gets the topology of this 3D array
+	/// # Returns
+	/// Returns a tuple of the (X axis, Y axis, Z axis) topologies
+	pub fn topology(&self) -> (AxisTopology, AxisTopology, AxisTopology) {
+		let [x_axis, y_axis, z_axis] = self.array.topology();
+		return (x_axis, y_axis, z_axis);
+	}
+
 	/// Gets a value from the 3D array, or returns a *LookUpError* if the provided coordinate
-	/// is out of bounds. If you are using a default value for out-of-bounds coordinates,
-	/// then you should use the *bounded_get(x, y, z)* method instead. If you want access to
+	/// is out of bounds. Coordinates past the end of a wrapped or clamped axis are handled by
+	/// the topology of this 3D array (see *set_topology(x_axis, y_axis, z_axis)*). If you are
+	/// using a default value for out-of-bounds coordinates, then you should use the
+	/// *bounded_get(x, y, z)* method instead. If you want access to
 	/// wrap-around (eg (-2, 0, 1) equivalent to (width-2, 0, 1)), then use the
 	/// *wrapped_get(x, y, z)* method.
 	/// # Parameters
@@ -377,16 +645,29 @@ impl<T> ZArray3D<T> {
 	/// Returns a Result type that holds either the returned data value (as a reference) from
 	/// the 3D array, or a *LookUpError* signalling that the coordinate is out of bounds
 	pub fn get(&self, x: usize, y: usize, z: usize) -> Result<&T,LookUpError>{
-		if x < self.xsize && y < self.ysize && z < self.zsize {
-			Ok(self.patches[patch_index(x, y, z, self.pxsize, self.pysize)].get(x, y, z))
-		} else {
-			Err(LookUpError{coord: vec![x, y, z],
-				bounds: vec![self.xsize, self.ysize, self.zsize]})
-		}
+		return self.array.get([x, y, z]);
+	}
+
+	/// Gets a mutable reference to a value in the 3D array, or returns a *LookUpError* if the
+	/// provided coordinate is out of bounds (coordinates past the end of a wrapped or clamped
+	/// axis are handled by the topology of this 3D array). If you are using a default value for
+	/// out-of-bounds coordinates, then you should use the *bounded_get_mut(x, y, z)* method
+	/// instead. If you want access to wrap-around (eg (-2, 0, 1) equivalent to
+	/// (width-2, 0, 1)), then use the *wrapped_get_mut(x, y, z)* method.
+	/// # Parameters
+	/// * **x** - x dimension coordinate
+	/// * **y** - y dimension coordinate
+	/// * **z** - z dimension coordinate
+	/// # Returns
+	/// Returns a Result type that holds either a mutable reference to the data value in the
+	/// 3D array, or a *LookUpError* signalling that the coordinate is out of bounds
+	pub fn get_mut(&mut self, x: usize, y: usize, z: usize) -> Result<&mut T,LookUpError>{
+		return self.array.get_mut([x, y, z]);
 	}
 
 	/// Sets a value in the 3D array, or returns a *LookUpError* if the provided coordinate
-	/// is out of bounds. If you want out-of-bound coordinates to result in a no-op, then use
+	/// is out of bounds (coordinates past the end of a wrapped or clamped axis are handled by
+	/// the topology of this 3D array). If you want out-of-bound coordinates to result in a no-op, then use
 	/// the *bounded_set(x, y, z, val)* method instead. If you want access to wrap-around (eg
 	/// (-2, 0, 1) equivalent to (width-2, 0, 1)), then use the
 	/// *wrapped_set(x, y, z, val)* method.
@@ -399,13 +680,7 @@ impl<T> ZArray3D<T> {
 	/// Returns a Result type that is either empty or a *LookUpError* signalling that the
 	/// coordinate is out of bounds
 	pub fn set(&mut self, x: usize, y: usize, z: usize, new_val: T) -> Result<(),LookUpError>{
-		if x < self.xsize && y < self.ysize && z < self.zsize {
-			Ok(self.patches[patch_index(x, y, z, self.pxsize, self.pysize)]
-				.set(x, y, z, new_val))
-		} else {
-			Err(LookUpError{coord: vec![x, y, z],
-				bounds: vec![self.xsize, self.ysize, self.zsize]})
-		}
+		return self.array.set([x, y, z], new_val);
 	}
 
 	/// Gets a value from the 3D array without bounds checking
@@ -416,7 +691,18 @@ impl<T> ZArray3D<T> {
 	/// # Returns
 	/// Returns the data value (as a reference) from the 3D array
 	pub fn get_unchecked(&self, x: usize, y: usize, z: usize) -> &T {
-		return self.patches[patch_index(x, y, z, self.pxsize, self.pysize)].get(x, y, z);
+		return self.array.get_unchecked([x, y, z]);
+	}
+
+	/// Gets a mutable reference to a value in the 3D array without bounds checking
+	/// # Parameters
+	/// * **x** - x dimension coordinate
+	/// * **y** - y dimension coordinate
+	/// * **z** - z dimension coordinate
+	/// # Returns
+	/// Returns a mutable reference to the data value in the 3D array
+	pub fn get_unchecked_mut(&mut self, x: usize, y: usize, z: usize) -> &mut T {
+		return self.array.get_unchecked_mut([x, y, z]);
 	}
 
 	/// Sets a value in the 3D array without bounds checking
@@ -426,8 +712,7 @@ impl<T> ZArray3D<T> {
 	/// * **z** - z dimension coordinate
 	/// * **new_val** - value to store in the 3D array at (x, y, z)
 	pub fn set_unchecked(&mut self, x: usize, y: usize, z: usize, new_val: T) {
-		self.patches[patch_index(x, y, z, self.pxsize, self.pysize)]
-				.set(x, y, z, new_val);
+		self.array.set_unchecked([x, y, z], new_val);
 	}
 
 	/// Gets a value from the 3D array, wrapping around the X and Y axese when the coordinates
@@ -441,10 +726,20 @@ impl<T> ZArray3D<T> {
 	/// Returns a reference to the data stored at the provided coordinate (wrapping both x
 	/// and y dimensions)
 	pub fn wrapped_get(&self, x: isize, y: isize, z: isize) -> &T{
-		let x = (self.xsize as isize + (x % self.xsize as isize)) as usize % self.xsize;
-		let y = (self.ysize as isize + (y % self.ysize as isize)) as usize % self.ysize;
-		let z = (self.zsize as isize + (z % self.zsize as isize)) as usize % self.zsize;
-		return &self.patches[patch_index(x, y, z, self.pxsize, self.pysize)].get(x, y, z);
+		return self.array.wrapped_get([x, y, z]);
+	}
+
+	/// Gets a mutable reference to a value in the 3D array, wrapping around the X, Y, and Z
+	/// axese when the coordinates are negative or outside the size of this 3D array.
+	/// # Parameters
+	/// * **x** - x dimension coordinate
+	/// * **y** - y dimension coordinate
+	/// * **z** - z dimension coordinate
+	/// # Returns
+	/// Returns a mutable reference to the data stored at the provided coordinate (wrapping
+	/// the x, y, and z dimensions)
+	pub fn wrapped_get_mut(&mut self, x: isize, y: isize, z: isize) -> &mut T{
+		return self.array.wrapped_get_mut([x, y, z]);
 	}
 
 	/// Sets a value in the 3D array at the provided coordinate, wrapping the X, Y, and Z axese
@@ -456,14 +751,13 @@ impl<T> ZArray3D<T> {
 	/// * **new_val** - value to store in the 3D array at (x, y, z), wrapping around
 	/// the x, y, and z dimensions
 	pub fn wrapped_set(&mut self, x: isize, y: isize, z: isize, new_val: T) {
-		let x = (self.xsize as isize + (x % self.xsize as isize)) as usize % self.xsize;
-		let y = (self.ysize as isize + (y % self.ysize as isize)) as usize % self.ysize;
-		let z = (self.zsize as isize + (z % self.zsize as isize)) as usize % self.zsize;
-		self.patches[patch_index(x, y, z, self.pxsize, self.pysize)].set(x, y, z, new_val);
+		self.array.wrapped_set([x, y, z], new_val);
 	}
 
 	/// Gets a value from the 3D array as an Option that is None if the coordinate
-	/// is out of bounds.
+	/// is out of bounds. Coordinates outside of a wrapped or clamped axis are handled by the
+	/// topology of this 3D array (see *set_topology(x_axis, y_axis, z_axis)*), so only the
+	/// bounded axes can be out of bounds.
 	/// # Parameters
 	/// * **x** - x dimension coordinate
 	/// * **y** - y dimension coordinate
@@ -473,91 +767,388 @@ impl<T> ZArray3D<T> {
 	/// the 3D array, or *None* signalling that the coordinate is out of bounds (which can be
 	/// combined with .unwrap_or(default_value) to implement an out-of-bounds default)
 	pub fn bounded_get(&self, x: isize, y: isize, z: isize) -> Option<&T>{
-		if x >= 0 && y >= 0 && z >= 0
-			&& x < self.xsize as isize && y < self.ysize as isize && z < self.zsize as isize {
-			return Some(&self.patches[
-				patch_index(x as usize, y as usize, z as usize, self.pxsize, self.pysize)]
-				.get(x as usize, y as usize, z as usize));
-		} else {
-			return None;
-		}
+		return self.array.bounded_get([x, y, z]);
+	}
+
+	/// Gets a mutable reference to a value in the 3D array as an Option that is None if the
+	/// coordinate is out of bounds (coordinates outside of a wrapped or clamped axis are
+	/// handled by the topology of this 3D array).
+	/// # Parameters
+	/// * **x** - x dimension coordinate
+	/// * **y** - y dimension coordinate
+	/// * **z** - z dimension coordinate
+	/// # Returns
+	/// Returns an Option type that holds either a mutable reference to the data value in the
+	/// 3D array, or *None* signalling that the coordinate is out of bounds
+	pub fn bounded_get_mut(&mut self, x: isize, y: isize, z: isize) -> Option<&mut T>{
+		return self.array.bounded_get_mut([x, y, z]);
 	}
 
-	/// Sets a value in the 3D array if and only if the provided coordinate is in bounds.
-	/// Otherwise this method does nothing if the coordinate is out of bounds.
+	/// Sets a value in the 3D array if and only if the provided coordinate is in bounds
+	/// (coordinates outside of a wrapped or clamped axis are handled by the topology of this
+	/// 3D array). Otherwise this method does nothing if the coordinate is out of bounds.
 	/// # Parameters
 	/// * **x** - x dimension coordinate
 	/// * **y** - y dimension coordinate
 	/// * **z** - z dimension coordinate
 	/// * **new_val** - value to store int eh zD array at (x, y, z)
 	pub fn bounded_set(&mut self, x: isize, y: isize, z: isize, new_val: T) {
-		if x >= 0 && y >= 0 && z >= 0
-			&& x < self.xsize as isize && y < self.ysize as isize && z < self.zsize as isize {
-			self.patches[
-				patch_index(x as usize, y as usize, z as usize, self.pxsize, self.pysize)]
-				.set(x as usize, y as usize, z as usize, new_val);
-		} else {
-			// no-op
-		}
+		self.array.bounded_set([x, y, z], new_val);
+	}
+
+	/// Gets a value from the 3D array, using the provided edge mode to handle coordinates that
+	/// are negative or outside the size of this 3D array (eg *EdgeMode::Clamp* to
+	/// clamp-to-edge or *EdgeMode::Mirror* to mirror the array at its edges). The edge mode
+	/// only applies to the bounded axes, as the wrapped and clamped axes are handled by the
+	/// topology of this 3D array (see *set_topology(x_axis, y_axis, z_axis)*).
+	/// # Parameters
+	/// * **x** - x dimension coordinate
+	/// * **y** - y dimension coordinate
+	/// * **z** - z dimension coordinate
+	/// * **edge_mode** - how to handle out-of-bounds coordinates
+	/// # Returns
+	/// Returns a Result type that holds either the returned data value (as a reference) or a
+	/// *LookUpError* if the coordinate is out of bounds and the edge mode is *EdgeMode::Error*
+	pub fn sample<'a>(&'a self, x: isize, y: isize, z: isize, edge_mode: &'a EdgeMode<T>) -> Result<&'a T, LookUpError> {
+		return self.array.sample([x, y, z], edge_mode);
+	}
+
+	/// Gets a mutable reference to a value in the 3D array, using the provided edge mode to
+	/// handle coordinates that are negative or outside the size of this 3D array.
+	/// # Parameters
+	/// * **x** - x dimension coordinate
+	/// * **y** - y dimension coordinate
+	/// * **z** - z dimension coordinate
+	/// * **edge_mode** - how to handle out-of-bounds coordinates
+	/// # Returns
+	/// Returns a Result type that holds either a mutable reference to the data value or a
+	/// *LookUpError* if the coordinate is out of bounds and the edge mode is *EdgeMode::Error*
+	/// or *EdgeMode::Default* (as there is no value in the array to mutate)
+	pub fn sample_mut(&mut self, x: isize, y: isize, z: isize, edge_mode: &EdgeMode<T>) -> Result<&mut T, LookUpError> {
+		return self.array.sample_mut([x, y, z], edge_mode);
+	}
+
+	/// Sets a value in the 3D array, using the provided edge mode to handle coordinates that
+	/// are negative or outside the size of this 3D array. Writes to out-of-bounds coordinates
+	/// are ignored with *EdgeMode::Default*.
+	/// # Parameters
+	/// * **x** - x dimension coordinate
+	/// * **y** - y dimension coordinate
+	/// * **z** - z dimension coordinate
+	/// * **edge_mode** - how to handle out-of-bounds coordinates
+	/// * **new_val** - value to store in the 3D array
+	/// # Returns
+	/// Returns a Result type that is either empty or a *LookUpError* if the coordinate is out
+	/// of bounds and the edge mode is *EdgeMode::Error*
+	pub fn sample_set(&mut self, x: isize, y: isize, z: isize, edge_mode: &EdgeMode<T>, new_val: T) -> Result<(), LookUpError> {
+		return self.array.sample_set([x, y, z], edge_mode, new_val);
+	}
+
+	/// Creates an iterator that iterates through a box-shaped region of signed coordinates in
+	/// raster order, like *bounded_get(x, y, z)*: coordinates outside of a wrapped or clamped
+	/// axis are handled by the topology of this 3D array (see
+	/// *set_topology(x_axis, y_axis, z_axis)*) and coordinates that are out of bounds on a
+	/// bounded axis are skipped. The coordinates of the items are the in-bounds coordinates
+	/// that were looked up (so a clamped axis may visit the same cell more than once).
+	/// # Parameters
+	/// * **x_range** - range of x dimension coordinates (eg `-4..4`)
+	/// * **y_range** - range of y dimension coordinates (eg `..`)
+	/// * **z_range** - range of z dimension coordinates (eg `0..=2`)
+	/// # Returns
+	/// A new ZArray3DBoundedRegionIterator instance
+	pub fn bounded_iter_region(&self, x_range: impl RangeBounds<isize>, y_range: impl RangeBounds<isize>,
+							z_range: impl RangeBounds<isize>) -> ZArray3DBoundedRegionIterator<'_, T, E> {
+		let (x1, x2) = signed_range(&x_range, self.xsize());
+		let (y1, y2) = signed_range(&y_range, self.ysize());
+		let (z1, z2) = signed_range(&z_range, self.zsize());
+		ZArray3DBoundedRegionIterator{iter: self.array.bounded_iter_region([x1, y1, z1], [x2, y2, z2])}
 	}
 
 	/// Creates an iterator that iterates through the 3D array in Z-order
 	/// # Returns
 	/// A new ZArray3DIterator instance
-	pub fn iter(&self) -> ZArray3DIterator<T> {
-		ZArray3DIterator::new(self)
+	pub fn iter(&self) -> ZArray3DIterator<'_, T, E> {
+		ZArray3DIterator{iter: self.array.iter()}
 	}
 	
+	/// Creates an iterator that iterates through the 3D array in Z-order, providing mutable
+	/// access to each value
+	/// # Returns
+	/// A new ZArray3DIteratorMut instance
+	pub fn iter_mut(&mut self) -> ZArray3DIteratorMut<'_, T, E> {
+		ZArray3DIteratorMut{iter: self.array.iter_mut()}
+	}
+
+	/// Creates an iterator that iterates through the 3D array in raster order (aka row-major
+	/// order, X changes fastest, then Y, then Z). This is slower than Z-order iteration with
+	/// *iter()*, but is useful when the order of the values matters (eg for exporting data).
+	/// # Returns
+	/// A new ZArray3DRasterIterator instance
+	pub fn iter_raster(&self) -> ZArray3DRasterIterator<'_, T, E> {
+		ZArray3DRasterIterator{iter: self.array.iter_raster()}
+	}
+
+	/// Alias for `iter_raster()`
+	/// # Returns
+	/// A new ZArray3DRasterIterator instance
+	pub fn iter_rows(&self) -> ZArray3DRasterIterator<'_, T, E> {
+		return self.iter_raster();
+	}
+
+	/// Creates an iterator that iterates through one row of the 3D array (all X coordinates
+	/// for a given Y and Z coordinate) in order of increasing X
+	/// # Parameters
+	/// * **y** - y dimension coordinate of the row
+	/// * **z** - z dimension coordinate of the row
+	/// # Returns
+	/// Returns a Result type that holds either a new ZArray3DRasterIterator instance or a
+	/// *LookUpError* signalling that the row is out of bounds
+	pub fn row(&self, y: usize, z: usize) -> Result<ZArray3DRasterIterator<'_, T, E>, LookUpError> {
+		let (xsize, ysize, zsize) = self.dimensions();
+		if y < ysize && z < zsize {
+			Ok(ZArray3DRasterIterator{iter: self.array.iter_raster_region([0, y, z], [xsize, y + 1, z + 1])})
+		} else {
+			Err(LookUpError::new(&[0, y as isize, z as isize], &[xsize, ysize, zsize]))
+		}
+	}
+
+	/// Creates an iterator that iterates through one column of the 3D array (all Y
+	/// coordinates for a given X and Z coordinate) in order of increasing Y
+	/// # Parameters
+	/// * **x** - x dimension coordinate of the column
+	/// * **z** - z dimension coordinate of the column
+	/// # Returns
+	/// Returns a Result type that holds either a new ZArray3DRasterIterator instance or a
+	/// *LookUpError* signalling that the column is out of bounds
+	pub fn column(&self, x: usize, z: usize) -> Result<ZArray3DRasterIterator<'_, T, E>, LookUpError> {
+		let (xsize, ysize, zsize) = self.dimensions();
+		if x < xsize && z < zsize {
+			Ok(ZArray3DRasterIterator{iter: self.array.iter_raster_region([x, 0, z], [x + 1, ysize, z + 1])})
+		} else {
+			Err(LookUpError::new(&[x as isize, 0, z as isize], &[xsize, ysize, zsize]))
+		}
+	}
+
+	/// Creates an iterator that iterates through one pillar of the 3D array (all Z
+	/// coordinates for a given X and Y coordinate) in order of increasing Z
+	/// # Parameters
+	/// * **x** - x dimension coordinate of the pillar
+	/// * **y** - y dimension coordinate of the pillar
+	/// # Returns
+	/// Returns a Result type that holds either a new ZArray3DRasterIterator instance or a
+	/// *LookUpError* signalling that the pillar is out of bounds
+	pub fn pillar(&self, x: usize, y: usize) -> Result<ZArray3DRasterIterator<'_, T, E>, LookUpError> {
+		let (xsize, ysize, zsize) = self.dimensions();
+		if x < xsize && y < ysize {
+			Ok(ZArray3DRasterIterator{iter: self.array.iter_raster_region([x, y, 0], [x + 1, y + 1, zsize])})
+		} else {
+			Err(LookUpError::new(&[x as isize, y as isize, 0], &[xsize, ysize, zsize]))
+		}
+	}
+
+	/// Creates an iterator that iterates through one XY plane of the 3D array (all X and Y
+	/// coordinates for a given Z coordinate) in raster order
+	/// # Parameters
+	/// * **z** - z dimension coordinate of the plane
+	/// # Returns
+	/// Returns a Result type that holds either a new ZArray3DRasterIterator instance or a
+	/// *LookUpError* signalling that the plane is out of bounds
+	pub fn plane(&self, z: usize) -> Result<ZArray3DRasterIterator<'_, T, E>, LookUpError> {
+		let (xsize, ysize, zsize) = self.dimensions();
+		if z < zsize {
+			Ok(ZArray3DRasterIterator{iter: self.array.iter_raster_region([0, 0, z], [xsize, ysize, z + 1])})
+		} else {
+			Err(LookUpError::new(&[0, 0, z as isize], &[xsize, ysize, zsize]))
+		}
+	}
+
+	/// Creates an iterator that iterates through a box-shaped region of the 3D array in
+	/// Z-order, visiting only the data patches that overlap the region. The region is clipped
+	/// to the bounds of this 3D array (use *bounded_iter_region(x_range, y_range, z_range)*
+	/// for a region that crosses the edges of wrapped or clamped axes).
+	/// # Parameters
+	/// * **x_range** - range of x dimension coordinates (eg `3..10`)
+	/// * **y_range** - range of y dimension coordinates (eg `..=7`)
+	/// * **z_range** - range of z dimension coordinates (eg `..`)
+	/// # Returns
+	/// A new ZArray3DRegionIterator instance
+	pub fn iter_region(&self, x_range: impl RangeBounds<usize>, y_range: impl RangeBounds<usize>,
+					   z_range: impl RangeBounds<usize>) -> ZArray3DRegionIterator<'_, T, E> {
+		let (x1, x2) = clamp_range(&x_range, self.xsize());
+		let (y1, y2) = clamp_range(&y_range, self.ysize());
+		let (z1, z2) = clamp_range(&z_range, self.zsize());
+		ZArray3DRegionIterator{iter: self.array.iter_region([x1, y1, z1], [x2, y2, z2])}
+	}
+
+	/// Creates an iterator that iterates through a box-shaped region of the 3D array in
+	/// Z-order, providing mutable access to each value and visiting only the data patches that
+	/// overlap the region. The region is clipped to the bounds of this 3D array.
+	/// # Parameters
+	/// * **x_range** - range of x dimension coordinates (eg `3..10`)
+	/// * **y_range** - range of y dimension coordinates (eg `..=7`)
+	/// * **z_range** - range of z dimension coordinates (eg `..`)
+	/// # Returns
+	/// A new ZArray3DRegionIteratorMut instance
+	pub fn iter_region_mut(&mut self, x_range: impl RangeBounds<usize>, y_range: impl RangeBounds<usize>,
+					   z_range: impl RangeBounds<usize>) -> ZArray3DRegionIteratorMut<'_, T, E> {
+		let (x1, x2) = clamp_range(&x_range, self.xsize());
+		let (y1, y2) = clamp_range(&y_range, self.ysize());
+		let (z1, z2) = clamp_range(&z_range, self.zsize());
+		ZArray3DRegionIteratorMut{iter: self.array.iter_region_mut([x1, y1, z1], [x2, y2, z2])}
+	}
+
+	/// Creates an iterator that iterates through the neighbors of a given cell, for use in
+	/// operations such as blurring and cellular automata. The center cell itself is not
+	/// included. If the whole neighborhood lies inside a single data patch, then the
+	/// neighbors are read directly from that patch without any bounds checking.
+	/// # Parameters
+	/// * **x** - x dimension coordinate of the center cell
+	/// * **y** - y dimension coordinate of the center cell
+	/// * **z** - z dimension coordinate of the center cell
+	/// * **kind** - shape of the neighborhood (eg *Neighborhood::Moore* for 26-connectivity)
+	/// * **radius** - size of the neighborhood (ignored for *Neighborhood::Custom*)
+	/// * **edge_mode** - how to handle neighbors that are outside of this 3D array (neighbors
+	/// are left out with *EdgeMode::Error*)
+	/// # Returns
+	/// A new ZArray3DNeighborIterator instance
+	pub fn neighbors<'a>(&'a self, x: usize, y: usize, z: usize, kind: Neighborhood<'a>,
+		radius: usize, edge_mode: &'a EdgeMode<T>) -> ZArray3DNeighborIterator<'a, T, E> {
+		ZArray3DNeighborIterator::new(self, x, y, z, kind, radius, edge_mode)
+	}
+
+	/// Applies a stencil function to every cell of this 3D array and writes the results to
+	/// the output array (which must have the same dimensions), eg for blurring or cellular
+	/// automata. This is the "double-buffered" form of a loop over *neighbors(...)*: the
+	/// stencil function is given a [StencilView](crate::znd::StencilView) of each cell's
+	/// neighborhood, where neighbors are addressed by their offset `[dx, dy, dz]` from the cell.
+	/// The array is processed one data patch at a time, looking up each patch and the halo of
+	/// cells around it only once, which is much faster than looking up the neighbors of every
+	/// cell with *bounded_get(...)*.
+	/// # Parameters
+	/// * **output** - 3D array to write the results to
+	/// * **radius** - size of the neighborhood in each direction
+	/// * **edge_mode** - how to handle neighbors that are outside of this 3D array (they read
+	/// as None with *EdgeMode::Error*)
+	/// * **stencil_fn** - function that takes the view of a cell's neighborhood and returns
+	/// the output value for that cell
+	/// # Panics
+	/// Panics if the output array does not have the same dimensions as this 3D array
+	/// # Example
+	/// ```
+	/// use zarray::EdgeMode;
+	/// use zarray::z3d::ZArray3D;
+	/// let mut voxels = ZArray3D::<_>::new(10, 10, 10, false);
+	/// voxels.set(5, 5, 5, true).unwrap();
+	/// // grow the solid voxels by one voxel in every direction
+	/// let mut grown = ZArray3D::<_>::new(10, 10, 10, false);
+	/// voxels.stencil(&mut grown, 1, &EdgeMode::Default(false), |view| {
+	///   *view.center() || view.neighbors().any(|(_, v)| *v)
+	/// });
+	/// assert_eq!(grown.iter().filter(|item| *item.value).count(), 27);
+	/// ```
+	pub fn stencil<U>(&self, output: &mut ZArray3D<U, E>, radius: usize, edge_mode: &EdgeMode<T>,
+		stencil_fn: impl FnMut(&StencilView<'_, T, 3>) -> U) {
+		self.array.stencil(&mut output.array, radius, edge_mode, stencil_fn);
+	}
+
+	/// Convolves this 3D array with a kernel of weights and writes the results to the output
+	/// array (which must have the same dimensions), eg for a box or Gaussian blur. Each output
+	/// value is the sum of the neighboring values multiplied by their weights. Neighbors that
+	/// are out of bounds with *EdgeMode::Error* are left out of the sum.
+	/// # Parameters
+	/// * **output** - 3D array to write the results to
+	/// * **kernel** - weights of the neighborhood in raster order (X changes fastest), which
+	/// must be 2r+1 values long in each dimension for a radius of r (eg 27 values for a 3x3x3
+	/// kernel)
+	/// * **edge_mode** - how to handle neighbors that are outside of this 3D array
+	/// # Panics
+	/// Panics if the output array does not have the same dimensions as this 3D array, or if
+	/// the kernel is not the same odd length in every dimension
+	/// # Example
+	/// ```
+	/// use zarray::EdgeMode;
+	/// use zarray::z3d::ZArray3D;
+	/// let mut density = ZArray3D::<_>::new(10, 10, 10, 0u8);
+	/// density.set(5, 5, 5, 27).unwrap();
+	/// // 3x3x3 box blur
+	/// let mut blurred = ZArray3D::<_>::new(10, 10, 10, 0f32);
+	/// density.convolve(&mut blurred, &[1f32 / 27f32; 27], &EdgeMode::Default(0));
+	/// assert!((*blurred.get(4, 6, 5).unwrap() - 1f32).abs() < 1e-6);
+	/// ```
+	pub fn convolve<U>(&self, output: &mut ZArray3D<U, E>, kernel: &[U], edge_mode: &EdgeMode<T>)
+		where T: Copy + Into<U>, U: Copy + Default + Add<Output=U> + Mul<Output=U> {
+		self.array.convolve(&mut output.array, kernel, edge_mode);
+	}
+
 	/// Applies a function to the Z-array to mutate it in-place
 	/// # Parameters
 	/// * **transform_fn** - Function that takes the coordsinate as a tuple and a
 	/// reference to the old value and returns the new value
 	pub fn transform(&mut self, transform_fn: impl Fn((usize, usize, usize), &T) -> T) {
-		for pindex in 0..self.patches.len() {
-			let patch_coords = patch_coords(self.pxsize, self.pysize, pindex);
-			for coord in patch_coords {
-				if coord.0 < self.xsize && coord.1 < self.ysize && coord.2 < self.zsize {
-					let old_val = self.get_unchecked(coord.0, coord.1, coord.2);
-					self.set_unchecked(coord.0, coord.1, coord.2, transform_fn(coord, old_val));
-				}
-			}
-		}
+		self.array.transform(|[x, y, z], old_val| transform_fn((x, y, z), old_val));
 	}
 
 	/// Returns a vector of all valid (x, y, z) coordinates in this 3D array in Z-order
 	pub fn coords(&self) -> Vec<(usize, usize, usize)> {
-		let mut out: Vec<(usize, usize, usize)> = Vec::with_capacity(self.xsize * self.ysize * self.zsize);
-		for pindex in 0..self.patches.len() {
-			let patch_coords = patch_coords(self.pxsize, self.pysize, pindex);
-			for coord in patch_coords {
-				if coord.0 < self.xsize && coord.1 < self.ysize && coord.2 < self.zsize {
-					out.push(coord);
-				}
-			}
-		}
-		return out;
+		return self.iter().map(|item| (item.x, item.y, item.z)).collect();
 	}
 }
 
-#[test]
-fn check_patch_count_3d() {
-	let arr = ZArray3D::new(1, 1, 1, 0u8);
-	assert_eq!(arr.patches.len(), 1, "Allocated wrong number of patches for array of size {}x{}x{}", arr.xsize, arr.ysize, arr.zsize);
-	let arr = ZArray3D::new(8, 8, 8, 0u8);
-	assert_eq!(arr.patches.len(), 1, "Allocated wrong number of patches for array of size {}x{}x{}", arr.xsize, arr.ysize, arr.zsize);
-	let arr = ZArray3D::new(9, 8, 8, 0u8);
-	assert_eq!(arr.patches.len(), 2, "Allocated wrong number of patches for array of size {}x{}x{}", arr.xsize, arr.ysize, arr.zsize);
-	let arr = ZArray3D::new(8, 9, 8, 0u8);
-	assert_eq!(arr.patches.len(), 2, "Allocated wrong number of patches for array of size {}x{}x{}", arr.xsize, arr.ysize, arr.zsize);
-	let arr = ZArray3D::new(8, 8, 9, 0u8);
-	assert_eq!(arr.patches.len(), 2, "Allocated wrong number of patches for array of size {}x{}x{}", arr.xsize, arr.ysize, arr.zsize);
-	let arr = ZArray3D::new(9, 9, 9, 0u8);
-	assert_eq!(arr.patches.len(), 8, "Allocated wrong number of patches for array of size {}x{}x{}", arr.xsize, arr.ysize, arr.zsize);
-}
+#[cfg(feature = "rayon")]
+impl<T, const E: usize> ZArray3D<T, E> {
+	/// Creates a parallel iterator (using *rayon*) over the values of the 3D array, which
+	/// splits the work between threads one data patch at a time
+	/// # Returns
+	/// A rayon *ParallelIterator* of *ZArray3DIteratorItem* instances
+	pub fn par_iter(&self) -> impl ParallelIterator<Item=ZArray3DIteratorItem<'_, T>> where T: Sync {
+		return self.array.par_iter().map(|item| ZArray3DIteratorItem{x: item.coord[0], y: item.coord[1], z: item.coord[2], value: item.value});
+	}
 
-/// Used for converting 3D coords to linear Z-index
-const ZLUT: [u16; 16] = [
-	0b0000000000000000,
+	/// Creates a parallel iterator (using *rayon*) over the values of the 3D array, providing
+	/// mutable access to each value, which splits the work between threads one data patch at
+	/// a time
+	/// # Returns
+	/// A rayon *ParallelIterator* of *ZArray3DIteratorItemMut* instances
+	pub fn par_iter_mut(&mut self) -> impl ParallelIterator<Item=ZArray3DIteratorItemMut<'_, T>> where T: Send {
+		return self.array.par_iter_mut().map(|item| ZArray3DIteratorItemMut{x: item.coord[0], y: item.coord[1], z: item.coord[2], value: item.value});
+	}
+
+	/// Applies a function to the Z-array to mutate it in-place, like *transform(transform_fn)*,
+	/// but using all available threads (with *rayon*)
+	/// # Parameters
+	/// * **transform_fn** - Function that takes the coordinate as a tuple and a reference to
+	/// the old value and returns the new value
+	pub fn par_transform(&mut self, transform_fn: impl Fn((usize, usize, usize), &T) -> T + Sync) where T: Send {
+		self.array.par_transform(|[x, y, z], old_val| transform_fn((x, y, z), old_val));
+	}
+
+	/// Returns a vector of all valid (x, y, z) coordinates in this 3D array in Z-order, like
+	/// *coords()*, but using all available threads (with *rayon*)
+	pub fn par_coords(&self) -> Vec<(usize, usize, usize)> {
+		return self.array.par_coords().into_par_iter().map(|[x, y, z]| (x, y, z)).collect();
+	}
+}
+
+
+#[test]
+fn check_patch_count_3d() {
+	let arr = ZArray3D::<_>::new(1, 1, 1, 0u8);
+	assert_eq!(arr.array.patch_count(), 1, "Allocated wrong number of patches for array of size {}x{}x{}", arr.xsize(), arr.ysize(), arr.zsize());
+	let arr = ZArray3D::<_>::new(8, 8, 8, 0u8);
+	assert_eq!(arr.array.patch_count(), 1, "Allocated wrong number of patches for array of size {}x{}x{}", arr.xsize(), arr.ysize(), arr.zsize());
+	let arr = ZArray3D::<_>::new(9, 8, 8, 0u8);
+	assert_eq!(arr.array.patch_count(), 2, "Allocated wrong number of patches for array of size {}x{}x{}", arr.xsize(), arr.ysize(), arr.zsize());
+	let arr = ZArray3D::<_>::new(8, 9, 8, 0u8);
+	assert_eq!(arr.array.patch_count(), 2, "Allocated wrong number of patches for array of size {}x{}x{}", arr.xsize(), arr.ysize(), arr.zsize());
+	let arr = ZArray3D::<_>::new(8, 8, 9, 0u8);
+	assert_eq!(arr.array.patch_count(), 2, "Allocated wrong number of patches for array of size {}x{}x{}", arr.xsize(), arr.ysize(), arr.zsize());
+	let arr = ZArray3D::<_>::new(9, 9, 9, 0u8);
+	assert_eq!(arr.array.patch_count(), 8, "Allocated wrong number of patches for array of size {}x{}x{}", arr.xsize(), arr.ysize(), arr.zsize());
+}
+
+/// Used for converting 3D coords to linear Z-index
+const ZLUT: [u16; 16] = [
+	0b0000000000000000,
 	0b0000000000000001,
 	0b0000000000001000,
 	0b0000000000001001,
@@ -606,44 +1197,485 @@ pub fn zorder_8bit_to_24bit(x:u8, y:u8, z: u8) -> u32 {
 		| zorder_4bit_to_12bit(x, y, z) as u32
 }
 
-/// Used by iterators to back-calculate the XYZ of an index, bit order is 0bzzzyyyxxx
-const REVERSE_ZLUT: [u16; 512] = [
-	0, 1, 8, 9, 64, 65, 72, 73, 2, 3, 10, 11, 66, 67, 74, 75,
-	16, 17, 24, 25, 80, 81, 88, 89, 18, 19, 26, 27, 82, 83, 90, 91,
-	128, 129, 136, 137, 192, 193, 200, 201, 130, 131, 138, 139, 194, 195, 202, 203,
-	144, 145, 152, 153, 208, 209, 216, 217, 146, 147, 154, 155, 210, 211, 218, 219,
-	4, 5, 12, 13, 68, 69, 76, 77, 6, 7, 14, 15, 70, 71, 78, 79,
-	20, 21, 28, 29, 84, 85, 92, 93, 22, 23, 30, 31, 86, 87, 94, 95,
-	132, 133, 140, 141, 196, 197, 204, 205, 134, 135, 142, 143, 198, 199, 206, 207,
-	148, 149, 156, 157, 212, 213, 220, 221, 150, 151, 158, 159, 214, 215, 222, 223,
-	32, 33, 40, 41, 96, 97, 104, 105, 34, 35, 42, 43, 98, 99, 106, 107,
-	48, 49, 56, 57, 112, 113, 120, 121, 50, 51, 58, 59, 114, 115, 122, 123,
-	160, 161, 168, 169, 224, 225, 232, 233, 162, 163, 170, 171, 226, 227, 234, 235,
-	176, 177, 184, 185, 240, 241, 248, 249, 178, 179, 186, 187, 242, 243, 250, 251,
-	36, 37, 44, 45, 100, 101, 108, 109, 38, 39, 46, 47, 102, 103, 110, 111,
-	52, 53, 60, 61, 116, 117, 124, 125, 54, 55, 62, 63, 118, 119, 126, 127,
-	164, 165, 172, 173, 228, 229, 236, 237, 166, 167, 174, 175, 230, 231, 238, 239,
-	180, 181, 188, 189, 244, 245, 252, 253, 182, 183, 190, 191, 246, 247, 254, 255,
-	256, 257, 264, 265, 320, 321, 328, 329, 258, 259, 266, 267, 322, 323, 330, 331,
-	272, 273, 280, 281, 336, 337, 344, 345, 274, 275, 282, 283, 338, 339, 346, 347,
-	384, 385, 392, 393, 448, 449, 456, 457, 386, 387, 394, 395, 450, 451, 458, 459,
-	400, 401, 408, 409, 464, 465, 472, 473, 402, 403, 410, 411, 466, 467, 474, 475,
-	260, 261, 268, 269, 324, 325, 332, 333, 262, 263, 270, 271, 326, 327, 334, 335,
-	276, 277, 284, 285, 340, 341, 348, 349, 278, 279, 286, 287, 342, 343, 350, 351,
-	388, 389, 396, 397, 452, 453, 460, 461, 390, 391, 398, 399, 454, 455, 462, 463,
-	404, 405, 412, 413, 468, 469, 476, 477, 406, 407, 414, 415, 470, 471, 478, 479,
-	288, 289, 296, 297, 352, 353, 360, 361, 290, 291, 298, 299, 354, 355, 362, 363,
-	304, 305, 312, 313, 368, 369, 376, 377, 306, 307, 314, 315, 370, 371, 378, 379,
-	416, 417, 424, 425, 480, 481, 488, 489, 418, 419, 426, 427, 482, 483, 490, 491,
-	432, 433, 440, 441, 496, 497, 504, 505, 434, 435, 442, 443, 498, 499, 506, 507,
-	292, 293, 300, 301, 356, 357, 364, 365, 294, 295, 302, 303, 358, 359, 366, 367,
-	308, 309, 316, 317, 372, 373, 380, 381, 310, 311, 318, 319, 374, 375, 382, 383,
-	420, 421, 428, 429, 484, 485, 492, 493, 422, 423, 430, 431, 486, 487, 494, 495,
-	436, 437, 444, 445, 500, 501, 508, 509, 438, 439, 446, 447, 502, 503, 510, 511,
-];
+impl<T, const E: usize> ZArray3D<T, E> {
+	/// Borrows a box-shaped region of the 3D array as a view, which uses local coordinates
+	/// (the first corner of the region is (0, 0, 0) in the view). The region is clipped to the
+	/// bounds of this 3D array.
+	/// # Parameters
+	/// * **x_range** - range of x dimension coordinates (eg `128..192`)
+	/// * **y_range** - range of y dimension coordinates (eg `256..320`)
+	/// * **z_range** - range of z dimension coordinates (eg `0..64`)
+	/// # Returns
+	/// A new ZArray3DView instance
+	pub fn view(&self, x_range: impl RangeBounds<usize>, y_range: impl RangeBounds<usize>,
+		z_range: impl RangeBounds<usize>) -> ZArray3DView<'_, T, E> {
+		let (x1, x2) = clamp_range(&x_range, self.width());
+		let (y1, y2) = clamp_range(&y_range, self.height());
+		let (z1, z2) = clamp_range(&z_range, self.depth());
+		ZArray3DView{view: self.array.view([x1, y1, z1], [x2, y2, z2])}
+	}
+
+	/// Mutably borrows a box-shaped region of the 3D array as a view, which uses local
+	/// coordinates (the first corner of the region is (0, 0, 0) in the view). The region is
+	/// clipped to the bounds of this 3D array.
+	/// # Parameters
+	/// * **x_range** - range of x dimension coordinates (eg `128..192`)
+	/// * **y_range** - range of y dimension coordinates (eg `256..320`)
+	/// * **z_range** - range of z dimension coordinates (eg `0..64`)
+	/// # Returns
+	/// A new ZArray3DViewMut instance
+	pub fn view_mut(&mut self, x_range: impl RangeBounds<usize>, y_range: impl RangeBounds<usize>,
+		z_range: impl RangeBounds<usize>)
+		-> ZArray3DViewMut<'_, T, E> {
+		let (x1, x2) = clamp_range(&x_range, self.width());
+		let (y1, y2) = clamp_range(&y_range, self.height());
+		let (z1, z2) = clamp_range(&z_range, self.depth());
+		ZArray3DViewMut{view: self.array.view_mut([x1, y1, z1], [x2, y2, z2])}
+	}
+
+	/// Divides the 3D array into two disjoint mutable views at an x coordinate, which can be
+	/// modified at the same time (eg on different threads)
+	/// # Parameters
+	/// * **x** - x dimension coordinate where the second view starts
+	/// # Returns
+	/// Returns a tuple of the view of the x coordinates before *x* and the view of the rest
+	/// of the 3D array
+	/// # Panics
+	/// Panics if *x* is greater than the x dimension size of the 3D array
+	pub fn split_at_x_mut(&mut self, x: usize) -> (ZArray3DViewMut<'_, T, E>, ZArray3DViewMut<'_, T, E>) {
+		let (first, second) = self.array.split_at_mut(0, x);
+		return (ZArray3DViewMut{view: first}, ZArray3DViewMut{view: second});
+	}
+
+	/// Divides the 3D array into two disjoint mutable views at a y coordinate, which can be
+	/// modified at the same time (eg on different threads)
+	/// # Parameters
+	/// * **y** - y dimension coordinate where the second view starts
+	/// # Returns
+	/// Returns a tuple of the view of the y coordinates before *y* and the view of the rest
+	/// of the 3D array
+	/// # Panics
+	/// Panics if *y* is greater than the y dimension size of the 3D array
+	pub fn split_at_y_mut(&mut self, y: usize) -> (ZArray3DViewMut<'_, T, E>, ZArray3DViewMut<'_, T, E>) {
+		let (first, second) = self.array.split_at_mut(1, y);
+		return (ZArray3DViewMut{view: first}, ZArray3DViewMut{view: second});
+	}
+
+	/// Divides the 3D array into two disjoint mutable views at a z coordinate, which can be
+	/// modified at the same time (eg on different threads)
+	/// # Parameters
+	/// * **z** - z dimension coordinate where the second view starts
+	/// # Returns
+	/// Returns a tuple of the view of the z coordinates before *z* and the view of the rest
+	/// of the 3D array
+	/// # Panics
+	/// Panics if *z* is greater than the z dimension size of the 3D array
+	pub fn split_at_z_mut(&mut self, z: usize) -> (ZArray3DViewMut<'_, T, E>, ZArray3DViewMut<'_, T, E>) {
+		let (first, second) = self.array.split_at_mut(2, z);
+		return (ZArray3DViewMut{view: first}, ZArray3DViewMut{view: second});
+	}
+
+	/// Divides the 3D array into disjoint mutable views (chunks) of the given size, which can
+	/// be modified at the same time (eg on different threads). The chunks at the far edges
+	/// are smaller if the size of the 3D array is not a multiple of the chunk size.
+	/// # Parameters
+	/// * **chunk_xsize** - x dimension size of each chunk
+	/// * **chunk_ysize** - y dimension size of each chunk
+	/// * **chunk_zsize** - z dimension size of each chunk
+	/// # Returns
+	/// Returns a list of the chunks in raster order (X changes fastest, then Y, then Z)
+	/// # Panics
+	/// Panics if any dimension of the chunks is 0
+	pub fn chunks_mut(&mut self, chunk_xsize: usize, chunk_ysize: usize, chunk_zsize: usize) -> Vec<ZArray3DViewMut<'_, T, E>> {
+		return self.array.chunks_mut([chunk_xsize, chunk_ysize, chunk_zsize]).into_iter()
+			.map(|view| ZArray3DViewMut{view}).collect();
+	}
+}
+
+/// This struct is an immutable view of a box-shaped region of a *ZArray3D* (see
+/// *ZArray3D.view(x_range, y_range)*), which uses local coordinates where the first corner of
+/// the region is (0, 0, 0).
+#[derive(Debug)]
+pub struct ZArray3DView<'a, T, const E: usize = DEFAULT_PATCH_EDGE> {
+	view: ZArrayNDView<'a, T, 3, E>
+}
+
+impl<'a, T, const E: usize> Clone for ZArray3DView<'a, T, E> {
+	fn clone(&self) -> Self {
+		ZArray3DView{view: self.view.clone()}
+	}
+}
+
+impl<'a, T, const E: usize> ZArray3DView<'a, T, E> {
+	/// Gets the (x, y, z) size of this view
+	pub fn dimensions(&self) -> (usize, usize, usize) {
+		let [width, height, depth] = self.view.dimensions();
+		return (width, height, depth);
+	}
+
+	/// Gets the x dimension size of this view
+	pub fn width(&self) -> usize {
+		return self.view.dimensions()[0];
+	}
+
+	/// Gets the y dimension size of this view
+	pub fn height(&self) -> usize {
+		return self.view.dimensions()[1];
+	}
+
+	/// Gets the z dimension size of this view
+	pub fn depth(&self) -> usize {
+		return self.view.dimensions()[2];
+	}
+
+	/// Gets the (x, y, z) coordinate in the 3D array of the origin of this view
+	pub fn origin(&self) -> (usize, usize, usize) {
+		let [x, y, z] = self.view.origin();
+		return (x, y, z);
+	}
+
+	/// Gets a value from the view, or returns a *LookUpError* if the provided local coordinate
+	/// is out of bounds
+	/// # Parameters
+	/// * **x** - x dimension coordinate (relative to the origin of the view)
+	/// * **y** - y dimension coordinate (relative to the origin of the view)
+	/// * **z** - z dimension coordinate (relative to the origin of the view)
+	/// # Returns
+	/// Returns a Result type that holds either the returned data value (as a reference) from
+	/// the 3D array, or a *LookUpError* signalling that the coordinate is out of bounds
+	pub fn get(&self, x: usize, y: usize, z: usize) -> Result<&'a T, LookUpError> {
+		return self.view.get([x, y, z]);
+	}
+
+	/// Gets a value from the view as an Option that is None if the local coordinate is out of
+	/// bounds (of the view, not the 3D array).
+	/// # Parameters
+	/// * **x** - x dimension coordinate (relative to the origin of the view)
+	/// * **y** - y dimension coordinate (relative to the origin of the view)
+	/// * **z** - z dimension coordinate (relative to the origin of the view)
+	/// # Returns
+	/// Returns an Option type that holds either the returned data value (as a reference) from
+	/// the 3D array, or *None* signalling that the coordinate is out of bounds
+	pub fn bounded_get(&self, x: isize, y: isize, z: isize) -> Option<&'a T> {
+		return self.view.bounded_get([x, y, z]);
+	}
+
+	/// Gets a value from the view, wrapping around the edges of the view when the local
+	/// coordinates are negative or outside the size of this view.
+	/// # Parameters
+	/// * **x** - x dimension coordinate (relative to the origin of the view)
+	/// * **y** - y dimension coordinate (relative to the origin of the view)
+	/// * **z** - z dimension coordinate (relative to the origin of the view)
+	/// # Returns
+	/// Returns a reference to the data stored at the provided coordinate (wrapping all
+	/// dimensions)
+	pub fn wrapped_get(&self, x: isize, y: isize, z: isize) -> &'a T {
+		return self.view.wrapped_get([x, y, z]);
+	}
+
+	/// Creates an iterator that iterates through the view in Z-order, with local coordinates
+	/// # Returns
+	/// A new ZArray3DViewIterator instance
+	pub fn iter(&self) -> ZArray3DViewIterator<'a, T, E> {
+		ZArray3DViewIterator{iter: self.view.iter()}
+	}
+
+	/// Borrows a box-shaped region of this view as a smaller view. The region is clipped to
+	/// the bounds of this view.
+	/// # Parameters
+	/// * **x_range** - range of local x dimension coordinates
+	/// * **y_range** - range of local y dimension coordinates
+	/// * **z_range** - range of local z dimension coordinates
+	/// # Returns
+	/// A new ZArray3DView instance
+	pub fn view(&self, x_range: impl RangeBounds<usize>, y_range: impl RangeBounds<usize>,
+		z_range: impl RangeBounds<usize>) -> ZArray3DView<'a, T, E> {
+		let (x1, x2) = clamp_range(&x_range, self.width());
+		let (y1, y2) = clamp_range(&y_range, self.height());
+		let (z1, z2) = clamp_range(&z_range, self.depth());
+		ZArray3DView{view: self.view.view([x1, y1, z1], [x2, y2, z2])}
+	}
+}
+
+/// This struct is a mutable view of a box-shaped region of a *ZArray3D* (see
+/// *ZArray3D.view_mut(x_range, y_range)*), which uses local coordinates where the first
+/// corner of the region is (0, 0, 0). Views that do not overlap (eg from
+/// *ZArray3D.chunks_mut(chunk_xsize, chunk_ysize, chunk_zsize)*) can be used at the same time,
+/// including on different threads.
+#[derive(Debug)]
+pub struct ZArray3DViewMut<'a, T, const E: usize = DEFAULT_PATCH_EDGE> {
+	view: ZArrayNDViewMut<'a, T, 3, E>
+}
+
+impl<'a, T, const E: usize> ZArray3DViewMut<'a, T, E> {
+	/// Gets the (x, y, z) size of this view
+	pub fn dimensions(&self) -> (usize, usize, usize) {
+		let [width, height, depth] = self.view.dimensions();
+		return (width, height, depth);
+	}
+
+	/// Gets the x dimension size of this view
+	pub fn width(&self) -> usize {
+		return self.view.dimensions()[0];
+	}
+
+	/// Gets the y dimension size of this view
+	pub fn height(&self) -> usize {
+		return self.view.dimensions()[1];
+	}
+
+	/// Gets the z dimension size of this view
+	pub fn depth(&self) -> usize {
+		return self.view.dimensions()[2];
+	}
+
+	/// Gets the (x, y, z) coordinate in the 3D array of the origin of this view
+	pub fn origin(&self) -> (usize, usize, usize) {
+		let [x, y, z] = self.view.origin();
+		return (x, y, z);
+	}
+
+	/// Borrows this view as an immutable view
+	pub fn as_view(&self) -> ZArray3DView<'_, T, E> {
+		ZArray3DView{view: self.view.as_view()}
+	}
+
+	/// Gets a value from the view, or returns a *LookUpError* if the provided local coordinate
+	/// is out of bounds
+	/// # Parameters
+	/// * **x** - x dimension coordinate (relative to the origin of the view)
+	/// * **y** - y dimension coordinate (relative to the origin of the view)
+	/// * **z** - z dimension coordinate (relative to the origin of the view)
+	/// # Returns
+	/// Returns a Result type that holds either the returned data value (as a reference) from
+	/// the 3D array, or a *LookUpError* signalling that the coordinate is out of bounds
+	pub fn get(&self, x: usize, y: usize, z: usize) -> Result<&T, LookUpError> {
+		return self.view.get([x, y, z]);
+	}
+
+	/// Gets a mutable reference to a value in the view, or returns a *LookUpError* if the
+	/// provided local coordinate is out of bounds
+	/// # Parameters
+	/// * **x** - x dimension coordinate (relative to the origin of the view)
+	/// * **y** - y dimension coordinate (relative to the origin of the view)
+	/// * **z** - z dimension coordinate (relative to the origin of the view)
+	/// # Returns
+	/// Returns a Result type that holds either a mutable reference to the data value in the
+	/// 3D array, or a *LookUpError* signalling that the coordinate is out of bounds
+	pub fn get_mut(&mut self, x: usize, y: usize, z: usize) -> Result<&mut T, LookUpError> {
+		return self.view.get_mut([x, y, z]);
+	}
+
+	/// Sets a value in the view, or returns a *LookUpError* if the provided local coordinate
+	/// is out of bounds
+	/// # Parameters
+	/// * **x** - x dimension coordinate (relative to the origin of the view)
+	/// * **y** - y dimension coordinate (relative to the origin of the view)
+	/// * **z** - z dimension coordinate (relative to the origin of the view)
+	/// * **new_val** - value to store in the 3D array at (x, y, z) in the view
+	/// # Returns
+	/// Returns a Result type that is either empty or a *LookUpError* signalling that the
+	/// coordinate is out of bounds
+	pub fn set(&mut self, x: usize, y: usize, z: usize, new_val: T) -> Result<(), LookUpError> {
+		return self.view.set([x, y, z], new_val);
+	}
+
+	/// Gets a value from the view as an Option that is None if the local coordinate is out of
+	/// bounds (of the view, not the 3D array).
+	/// # Parameters
+	/// * **x** - x dimension coordinate (relative to the origin of the view)
+	/// * **y** - y dimension coordinate (relative to the origin of the view)
+	/// * **z** - z dimension coordinate (relative to the origin of the view)
+	/// # Returns
+	/// Returns an Option type that holds either the returned data value (as a reference) from
+	/// the 3D array, or *None* signalling that the coordinate is out of bounds
+	pub fn bounded_get(&self, x: isize, y: isize, z: isize) -> Option<&T> {
+		return self.view.bounded_get([x, y, z]);
+	}
+
+	/// Gets a mutable reference to a value in the view as an Option that is None if the local
+	/// coordinate is out of bounds (of the view, not the 3D array).
+	/// # Parameters
+	/// * **x** - x dimension coordinate (relative to the origin of the view)
+	/// * **y** - y dimension coordinate (relative to the origin of the view)
+	/// * **z** - z dimension coordinate (relative to the origin of the view)
+	/// # Returns
+	/// Returns an Option type that holds either a mutable reference to the data value in the
+	/// 3D array, or *None* signalling that the coordinate is out of bounds
+	pub fn bounded_get_mut(&mut self, x: isize, y: isize, z: isize) -> Option<&mut T> {
+		return self.view.bounded_get_mut([x, y, z]);
+	}
+
+	/// Sets a value in the view if and only if the provided local coordinate is in bounds (of
+	/// the view, not the 3D array). Otherwise this method does nothing.
+	/// # Parameters
+	/// * **x** - x dimension coordinate (relative to the origin of the view)
+	/// * **y** - y dimension coordinate (relative to the origin of the view)
+	/// * **z** - z dimension coordinate (relative to the origin of the view)
+	/// * **new_val** - value to store in the 3D array at (x, y, z) in the view
+	pub fn bounded_set(&mut self, x: isize, y: isize, z: isize, new_val: T) {
+		self.view.bounded_set([x, y, z], new_val);
+	}
+
+	/// Gets a value from the view, wrapping around the edges of the view when the local
+	/// coordinates are negative or outside the size of this view.
+	/// # Parameters
+	/// * **x** - x dimension coordinate (relative to the origin of the view)
+	/// * **y** - y dimension coordinate (relative to the origin of the view)
+	/// * **z** - z dimension coordinate (relative to the origin of the view)
+	/// # Returns
+	/// Returns a reference to the data stored at the provided coordinate (wrapping all
+	/// dimensions)
+	pub fn wrapped_get(&self, x: isize, y: isize, z: isize) -> &T {
+		return self.view.wrapped_get([x, y, z]);
+	}
+
+	/// Gets a mutable reference to a value in the view, wrapping around the edges of the view
+	/// when the local coordinates are negative or outside the size of this view.
+	/// # Parameters
+	/// * **x** - x dimension coordinate (relative to the origin of the view)
+	/// * **y** - y dimension coordinate (relative to the origin of the view)
+	/// * **z** - z dimension coordinate (relative to the origin of the view)
+	/// # Returns
+	/// Returns a mutable reference to the data stored at the provided coordinate (wrapping
+	/// all dimensions)
+	pub fn wrapped_get_mut(&mut self, x: isize, y: isize, z: isize) -> &mut T {
+		return self.view.wrapped_get_mut([x, y, z]);
+	}
+
+	/// Sets a value in the view at the provided local coordinate, wrapping around the edges
+	/// of the view if the coordinate is negative or out of bounds.
+	/// # Parameters
+	/// * **x** - x dimension coordinate (relative to the origin of the view)
+	/// * **y** - y dimension coordinate (relative to the origin of the view)
+	/// * **z** - z dimension coordinate (relative to the origin of the view)
+	/// * **new_val** - value to store in the 3D array at (x, y, z) in the view, wrapping around
+	/// all dimensions
+	pub fn wrapped_set(&mut self, x: isize, y: isize, z: isize, new_val: T) {
+		self.view.wrapped_set([x, y, z], new_val);
+	}
+
+	/// Creates an iterator that iterates through the view in Z-order, with local coordinates
+	/// # Returns
+	/// A new ZArray3DViewIterator instance
+	pub fn iter(&self) -> ZArray3DViewIterator<'_, T, E> {
+		ZArray3DViewIterator{iter: self.view.iter()}
+	}
+
+	/// Creates an iterator that iterates through the view in Z-order, with local coordinates,
+	/// providing mutable access to each value
+	/// # Returns
+	/// A new ZArray3DViewIteratorMut instance
+	pub fn iter_mut(&mut self) -> ZArray3DViewIteratorMut<'_, T, E> {
+		ZArray3DViewIteratorMut{iter: self.view.iter_mut()}
+	}
+
+	/// Fills a region of this view with a given value, or returns a *LookUpError* if the
+	/// provided local coordinates go out of bounds (of the view, not the 3D array).
+	/// # Parameters
+	/// * **x1** - the first x dimension coordinate (inclusive)
+	/// * **y1** - the first y dimension coordinate (inclusive)
+	/// * **z1** - the first z dimension coordinate (inclusive)
+	/// * **x2** - the second x dimension coordinate (exclusive)
+	/// * **y2** - the second y dimension coordinate (exclusive)
+	/// * **z2** - the second z dimension coordinate (exclusive)
+	/// * **new_val** - value to store in the view in the bounding box defined by
+	/// (x1, y1, z1) -> (x2, y2, z2)
+	/// # Returns
+	/// Returns a Result type that is either empty or a *LookUpError* signalling that a
+	/// coordinate is out of bounds
+	pub fn fill(&mut self, x1: usize, y1: usize, z1: usize, x2: usize, y2: usize, z2: usize, new_val: impl Borrow<T>)
+		-> Result<(), LookUpError> where T: Clone {
+		return self.view.fill([x1, y1, z1], [x2, y2, z2], new_val);
+	}
+
+	/// Mutably borrows a box-shaped region of this view as a smaller view. The region is
+	/// clipped to the bounds of this view.
+	/// # Parameters
+	/// * **x_range** - range of local x dimension coordinates
+	/// * **y_range** - range of local y dimension coordinates
+	/// * **z_range** - range of local z dimension coordinates
+	/// # Returns
+	/// A new ZArray3DViewMut instance
+	pub fn view_mut(&mut self, x_range: impl RangeBounds<usize>, y_range: impl RangeBounds<usize>,
+		z_range: impl RangeBounds<usize>)
+		-> ZArray3DViewMut<'_, T, E> {
+		let (x1, x2) = clamp_range(&x_range, self.width());
+		let (y1, y2) = clamp_range(&y_range, self.height());
+		let (z1, z2) = clamp_range(&z_range, self.depth());
+		ZArray3DViewMut{view: self.view.view_mut([x1, y1, z1], [x2, y2, z2])}
+	}
+
+	/// Divides this view into two disjoint mutable views at a local x coordinate
+	/// # Parameters
+	/// * **x** - local x dimension coordinate where the second view starts
+	/// # Returns
+	/// Returns a tuple of the view of the x coordinates before *x* and the view of the rest
+	/// of this view
+	/// # Panics
+	/// Panics if *x* is greater than the x dimension size of this view
+	pub fn split_at_x_mut(self, x: usize) -> (ZArray3DViewMut<'a, T, E>, ZArray3DViewMut<'a, T, E>) {
+		let (first, second) = self.view.split_at_mut(0, x);
+		return (ZArray3DViewMut{view: first}, ZArray3DViewMut{view: second});
+	}
 
+	/// Divides this view into two disjoint mutable views at a local y coordinate
+	/// # Parameters
+	/// * **y** - local y dimension coordinate where the second view starts
+	/// # Returns
+	/// Returns a tuple of the view of the y coordinates before *y* and the view of the rest
+	/// of this view
+	/// # Panics
+	/// Panics if *y* is greater than the y dimension size of this view
+	pub fn split_at_y_mut(self, y: usize) -> (ZArray3DViewMut<'a, T, E>, ZArray3DViewMut<'a, T, E>) {
+		let (first, second) = self.view.split_at_mut(1, y);
+		return (ZArray3DViewMut{view: first}, ZArray3DViewMut{view: second});
+	}
 
-/// This struct is used by `ZArray2DIterator` to present values to the consumer of the
+	/// Divides this view into two disjoint mutable views at a local z coordinate
+	/// # Parameters
+	/// * **z** - local z dimension coordinate where the second view starts
+	/// # Returns
+	/// Returns a tuple of the view of the z coordinates before *z* and the view of the rest
+	/// of this view
+	/// # Panics
+	/// Panics if *z* is greater than the z dimension size of this view
+	pub fn split_at_z_mut(self, z: usize) -> (ZArray3DViewMut<'a, T, E>, ZArray3DViewMut<'a, T, E>) {
+		let (first, second) = self.view.split_at_mut(2, z);
+		return (ZArray3DViewMut{view: first}, ZArray3DViewMut{view: second});
+	}
+}
+
+/// Iterator that iterates through a view of a 3D array in Z-order
+pub struct ZArray3DViewIterator<'a, T, const E: usize = DEFAULT_PATCH_EDGE> {
+	iter: ZArrayNDViewIterator<'a, T, 3, E>
+}
+
+impl<'a, T, const E: usize> Iterator for ZArray3DViewIterator<'a, T, E> {
+	type Item = ZArray3DIteratorItem<'a, T>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		let item = self.iter.next()?;
+		Some(ZArray3DIteratorItem{x: item.coord[0], y: item.coord[1], z: item.coord[2], value: item.value})
+	}
+}
+
+/// Iterator that iterates through a view of a 3D array in Z-order, providing mutable access
+/// to each value
+pub struct ZArray3DViewIteratorMut<'a, T, const E: usize = DEFAULT_PATCH_EDGE> {
+	iter: ZArrayNDViewIteratorMut<'a, T, 3, E>
+}
+
+impl<'a, T, const E: usize> Iterator for ZArray3DViewIteratorMut<'a, T, E> {
+	type Item = ZArray3DIteratorItemMut<'a, T>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		let item = self.iter.next()?;
+		Some(ZArray3DIteratorItemMut{x: item.coord[0], y: item.coord[1], z: item.coord[2], value: item.value})
+	}
+}
+
+/// This struct is used by `ZArray3DIterator` to present values to the consumer of the
 /// iterator
 #[derive(Debug)]
 pub struct ZArray3DIteratorItem<'a, T> {
@@ -657,68 +1689,297 @@ pub struct ZArray3DIteratorItem<'a, T> {
 	pub value: &'a T
 }
 
-/// private state management enum
-enum IterState {
-	Start, Processing, Done
-}
 /// Iterator that iterates through the array
-pub struct ZArray3DIterator<'a, T> {
-	/// array to iterate over
-	array: &'a ZArray3D<T>,
-	patch: usize,
-	index: usize,
-	state: IterState
-}
-
-impl<'a, T> ZArray3DIterator<'a, T> {
-	fn new(array: &'a ZArray3D<T>) -> ZArray3DIterator<'a, T> {
-		if array.xsize == 0 || array.ysize == 0 || array.zsize == 0 {
-			ZArray3DIterator{array, patch: 0, index: 0, state: IterState::Done} // make a "done" iterator for empty arrays
-		} else {
-			ZArray3DIterator{array, patch: 0, index: 0, state: IterState::Start}
-		}
+pub struct ZArray3DIterator<'a, T, const E: usize = DEFAULT_PATCH_EDGE> {
+	iter: ZArrayNDIterator<'a, T, 3, E>
+}
+
+impl<'a, T, const E: usize> Iterator for ZArray3DIterator<'a, T, E> {
+	type Item = ZArray3DIteratorItem<'a, T>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		let item = self.iter.next()?;
+		Some(ZArray3DIteratorItem{x: item.coord[0], y: item.coord[1], z: item.coord[2], value: item.value})
 	}
 }
 
-impl<'a, T> Iterator for ZArray3DIterator<'a, T> {
+/// Iterator that iterates through a box-shaped volume of the array in raster order (X changes
+/// fastest, then Y, then Z)
+pub struct ZArray3DRasterIterator<'a, T, const E: usize = DEFAULT_PATCH_EDGE> {
+	iter: ZArrayNDRasterIterator<'a, T, 3, E>
+}
+
+impl<'a, T, const E: usize> Iterator for ZArray3DRasterIterator<'a, T, E> {
 	type Item = ZArray3DIteratorItem<'a, T>;
 
 	fn next(&mut self) -> Option<Self::Item> {
-		match &self.state {
-			IterState::Done=> None,
-			IterState::Start=> {
-				self.state = IterState::Processing;
-				Some(ZArray3DIteratorItem{x: 0, y: 0, z: 0, value: &self.array.patches[0].contents[0]})
-			},
-			IterState::Processing => {
-				let mut x ; let mut y ; let mut z ;
-				loop {
-					self.index += 1;
-					if self.index >= 512 {
-						self.index  = 0;
-						self.patch += 1;
-					}
-					let zyx_lower_pits = REVERSE_ZLUT[self.index];
-					x = ((self.patch % self.array.pxsize) << 3) |  (zyx_lower_pits & 0x07) as usize;
-					y = (((self.patch / self.array.pxsize) % self.array.pysize ) << 3) | ((zyx_lower_pits >> 3) & 0x07) as usize;
-					z = ( (self.patch / (self.array.pysize * self.array.pxsize)) << 3) | ((zyx_lower_pits >> 6) & 0x07) as usize;
-					if x < self.array.xsize && y < self.array.ysize && z < self.array.zsize {
-						break;
-					}
-					if self.patch >= self.array.patches.len() {
-						self.state = IterState::Done;
-						return None;
+		let item = self.iter.next()?;
+		Some(ZArray3DIteratorItem{x: item.coord[0], y: item.coord[1], z: item.coord[2], value: item.value})
+	}
+
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		self.iter.size_hint()
+	}
+}
+
+impl<'a, T, const E: usize> ExactSizeIterator for ZArray3DRasterIterator<'a, T, E> {}
+
+/// Iterator that iterates through a box-shaped region of the array in Z-order
+pub struct ZArray3DRegionIterator<'a, T, const E: usize = DEFAULT_PATCH_EDGE> {
+	iter: ZArrayNDRegionIterator<'a, T, 3, E>
+}
+
+impl<'a, T, const E: usize> Iterator for ZArray3DRegionIterator<'a, T, E> {
+	type Item = ZArray3DIteratorItem<'a, T>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		let item = self.iter.next()?;
+		Some(ZArray3DIteratorItem{x: item.coord[0], y: item.coord[1], z: item.coord[2], value: item.value})
+	}
+}
+
+/// Iterator that iterates through a box-shaped region of the array in Z-order, providing
+/// mutable access to each value
+pub struct ZArray3DRegionIteratorMut<'a, T, const E: usize = DEFAULT_PATCH_EDGE> {
+	iter: ZArrayNDRegionIteratorMut<'a, T, 3, E>
+}
+
+impl<'a, T, const E: usize> Iterator for ZArray3DRegionIteratorMut<'a, T, E> {
+	type Item = ZArray3DIteratorItemMut<'a, T>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		let item = self.iter.next()?;
+		Some(ZArray3DIteratorItemMut{x: item.coord[0], y: item.coord[1], z: item.coord[2], value: item.value})
+	}
+}
+
+/// This enum specifies the shape of the neighborhood around a cell for
+/// *ZArray3D.neighbors(...)*
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Neighborhood<'a> {
+	/// All cells within the radius in the X, Y, and Z directions (26-connectivity for
+	/// radius 1)
+	Moore,
+	/// All cells within the radius in Manhattan distance (6-connectivity for radius 1)
+	VonNeumann,
+	/// All cells within the radius in the X, Y, and Z directions and within twice the radius
+	/// in Manhattan distance (18-connectivity for radius 1, ie cells that share a face or an
+	/// edge with the center cell)
+	FacesAndEdges,
+	/// A custom list of (dx, dy, dz) offsets relative to the center cell
+	Custom(&'a [(isize, isize, isize)])
+}
+
+/// This struct is used by `ZArray3DNeighborIterator` to present neighbors to the consumer of
+/// the iterator
+#[derive(Debug)]
+pub struct ZArray3DNeighborItem<'a, T> {
+	/// x-dimension coordinate of the neighbor (before any wrapping is applied)
+	pub x: isize,
+	/// y-dimension coordinate of the neighbor (before any wrapping is applied)
+	pub y: isize,
+	/// z-dimension coordinate of the neighbor (before any wrapping is applied)
+	pub z: isize,
+	/// reference to value of the neighbor
+	pub value: &'a T
+}
+
+/// private enum for generating the offsets of a neighborhood
+enum Offsets<'a> {
+	/// cube stencil, filtered by Manhattan distance
+	Stencil { radius: isize, max_manhattan: isize, next: (isize, isize, isize) },
+	Custom(Iter<'a, (isize, isize, isize)>)
+}
+
+impl<'a> Iterator for Offsets<'a> {
+	type Item = (isize, isize, isize);
+
+	fn next(&mut self) -> Option<Self::Item> {
+		match self {
+			Offsets::Custom(iter) => iter.next().copied(),
+			Offsets::Stencil { radius, max_manhattan, next } => loop {
+				let (dx, dy, dz) = *next;
+				if dz > *radius {
+					return None;
+				}
+				next.0 += 1;
+				if next.0 > *radius {
+					next.0 = -*radius;
+					next.1 += 1;
+					if next.1 > *radius {
+						next.1 = -*radius;
+						next.2 += 1;
 					}
 				}
-				Some(ZArray3DIteratorItem{x, y, z, value: &self.array.patches[self.patch].contents[self.index]})
+				if (dx, dy, dz) != (0, 0, 0) && dx.abs() + dy.abs() + dz.abs() <= *max_manhattan {
+					return Some((dx, dy, dz));
+				}
 			}
 		}
 	}
 }
 
+/// Iterator that iterates through the neighbors of a cell
+pub struct ZArray3DNeighborIterator<'a, T, const E: usize = DEFAULT_PATCH_EDGE> {
+	array: &'a ZArray3D<T, E>,
+	x: isize,
+	y: isize,
+	z: isize,
+	edge_mode: &'a EdgeMode<T>,
+	offsets: Offsets<'a>,
+	/// data patch that contains the whole neighborhood, if there is one
+	patch: Option<&'a [T]>,
+}
+
+impl<'a, T, const E: usize> ZArray3DNeighborIterator<'a, T, E> {
+	fn new(array: &'a ZArray3D<T, E>, x: usize, y: usize, z: usize, kind: Neighborhood<'a>,
+		radius: usize, edge_mode: &'a EdgeMode<T>) -> ZArray3DNeighborIterator<'a, T, E> {
+		let (x, y, z) = (x as isize, y as isize, z as isize);
+		// bounding box of the neighborhood, relative to the center
+		let (offsets, min, max) = match kind {
+			Neighborhood::Custom(list) => {
+				let min = list.iter().fold((0, 0, 0), |a, b| (a.0.min(b.0), a.1.min(b.1), a.2.min(b.2)));
+				let max = list.iter().fold((0, 0, 0), |a, b| (a.0.max(b.0), a.1.max(b.1), a.2.max(b.2)));
+				(Offsets::Custom(list.iter()), min, max)
+			},
+			_ => {
+				let r = radius as isize;
+				let max_manhattan = match kind {
+					Neighborhood::VonNeumann => r,
+					Neighborhood::FacesAndEdges => 2 * r,
+					_ => 3 * r
+				};
+				(Offsets::Stencil { radius: r, max_manhattan, next: (-r, -r, -r) }, (-r, -r, -r), (r, r, r))
+			}
+		};
+		let patch = array.array.patch_spanning([x + min.0, y + min.1, z + min.2],
+			[x + max.0, y + max.1, z + max.2]);
+		ZArray3DNeighborIterator{array, x, y, z, edge_mode, offsets, patch}
+	}
+}
+
+impl<'a, T, const E: usize> Iterator for ZArray3DNeighborIterator<'a, T, E> {
+	type Item = ZArray3DNeighborItem<'a, T>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		loop {
+			let (dx, dy, dz) = self.offsets.next()?;
+			let (x, y, z) = (self.x + dx, self.y + dy, self.z + dz);
+			let value = match self.patch {
+				Some(patch) => Ok(&patch[zorder_index::<3, E>(&[x as usize, y as usize, z as usize])]),
+				None => self.array.sample(x, y, z, self.edge_mode),
+			};
+			if let Ok(value) = value {
+				return Some(ZArray3DNeighborItem{x, y, z, value});
+			}
+		}
+	}
+}
+
+
+/// Iterator that iterates through a box-shaped region of signed coordinates in raster order,
+/// mapping out-of-bounds coordinates according to the topology of the array
+pub struct ZArray3DBoundedRegionIterator<'a, T, const E: usize = DEFAULT_PATCH_EDGE> {
+	iter: ZArrayNDBoundedRegionIterator<'a, T, 3, E>
+}
+
+impl<'a, T, const E: usize> Iterator for ZArray3DBoundedRegionIterator<'a, T, E> {
+	type Item = ZArray3DIteratorItem<'a, T>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		let item = self.iter.next()?;
+		Some(ZArray3DIteratorItem{x: item.coord[0], y: item.coord[1], z: item.coord[2], value: item.value})
+	}
+}
+
+/// This struct is used by `ZArray3DIteratorMut` to present mutable values to the consumer of
+/// the iterator
+#[derive(Debug)]
+pub struct ZArray3DIteratorItemMut<'a, T> {
+	/// x-dimension coordinate
+	pub x: usize,
+	/// y-dimension coordinate
+	pub y: usize,
+	/// z-dimension coordinate
+	pub z: usize,
+	/// mutable reference to value at this coordinate
+	pub value: &'a mut T
+}
+
+/// Iterator that iterates through the array in Z-order, providing mutable access to each value
+pub struct ZArray3DIteratorMut<'a, T, const E: usize = DEFAULT_PATCH_EDGE> {
+	iter: ZArrayNDIteratorMut<'a, T, 3, E>
+}
+
+impl<'a, T, const E: usize> Iterator for ZArray3DIteratorMut<'a, T, E> {
+	type Item = ZArray3DIteratorItemMut<'a, T>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		let item = self.iter.next()?;
+		Some(ZArray3DIteratorItemMut{x: item.coord[0], y: item.coord[1], z: item.coord[2], value: item.value})
+	}
+}
+
+/// This struct is used by `ZArray3DIntoIterator` to present owned values to the consumer of
+/// the iterator
+#[derive(Debug)]
+pub struct ZArray3DIntoIteratorItem<T> {
+	/// x-dimension coordinate
+	pub x: usize,
+	/// y-dimension coordinate
+	pub y: usize,
+	/// z-dimension coordinate
+	pub z: usize,
+	/// value that was stored at this coordinate
+	pub value: T
+}
+
+/// Iterator that consumes the array, moving each value out in Z-order. Values stored in the
+/// padding outside of the array's dimensions are dropped.
+pub struct ZArray3DIntoIterator<T, const E: usize = DEFAULT_PATCH_EDGE> {
+	iter: ZArrayNDIntoIterator<T, 3, E>
+}
+
+impl<T, const E: usize> Iterator for ZArray3DIntoIterator<T, E> {
+	type Item = ZArray3DIntoIteratorItem<T>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		let item = self.iter.next()?;
+		Some(ZArray3DIntoIteratorItem{x: item.coord[0], y: item.coord[1], z: item.coord[2], value: item.value})
+	}
+}
+
+impl<T, const E: usize> IntoIterator for ZArray3D<T, E> {
+	type Item = ZArray3DIntoIteratorItem<T>;
+	type IntoIter = ZArray3DIntoIterator<T, E>;
+
+	/// Consumes the 3D array and iterates through its values in Z-order
+	fn into_iter(self) -> Self::IntoIter {
+		ZArray3DIntoIterator{iter: self.array.into_iter()}
+	}
+}
+
+impl<'a, T, const E: usize> IntoIterator for &'a ZArray3D<T, E> {
+	type Item = ZArray3DIteratorItem<'a, T>;
+	type IntoIter = ZArray3DIterator<'a, T, E>;
+
+	fn into_iter(self) -> Self::IntoIter {
+		self.iter()
+	}
+}
+
+impl<'a, T, const E: usize> IntoIterator for &'a mut ZArray3D<T, E> {
+	type Item = ZArray3DIteratorItemMut<'a, T>;
+	type IntoIter = ZArray3DIteratorMut<'a, T, E>;
+
+	fn into_iter(self) -> Self::IntoIter {
+		self.iter_mut()
+	}
+}
+
 #[test]
 fn print_3d_zorder() {
-	let mut array = ZArray3D::new(8, 8, 8, 0usize);
+	let mut array = ZArray3D::<_>::new(8, 8, 8, 0usize);
 	for z in 0..8usize {
 		for y in 0..8usize {
 			for x in 0..8usize {
@@ -727,8 +1988,8 @@ fn print_3d_zorder() {
 		}
 	}
 	println!("[");
-	for i in 0..array.patches[0].contents.len() {
-		print!("{:?}, ", array.patches[0].contents[i]);
+	for (i, item) in array.iter().enumerate() {
+		print!("{:?}, ", item.value);
 		if i % 16 == 15{println!()}
 	}
 	println!("]");
@@ -0,0 +1,2698 @@
+//! This module is used for storing N-dimensional data arrays (from 1 to 4 dimensions), and
+//! internally uses Z-index arrays to improve data localization and alignment to the CPU
+//! cache-line fetches. It holds the implementation that is shared by
+//! [ZArray2D](crate::z2d::ZArray2D) and [ZArray3D](crate::z3d::ZArray3D), and can be used
+//! directly for 1D data or for 4D data (eg 3D plus time).
+//! # How It Works
+//! When you initialize a zarray::znd::ZArrayND struct, it creates an array of data patches that
+//! are 8 values long in every dimension (ie 8, 8x8, 8x8x8, or 8x8x8x8 values), using Z-curve
+//! indexing within that patch, such that the lowest bit of the first dimension is the lowest bit
+//! of the index within the patch. When you call a getter or setter method, it finds the
+//! corresponding data patch and then looks up (or sets) the data from within the patch.
+//! Coordinates are given as arrays of N values, eg `[x, y, z, t]`.
+//!
+//! The length of the data patches can be changed with the optional third generic parameter,
+//! which may be 4, 8 (the default), or 16. Smaller patches suit large element types (eg a
+//! 4x4x4 patch of `u64` values is 512 bytes, while an 8x8x8 patch is 4 KB), while larger
+//! patches suit small element types in higher dimensions. For example, `ZArrayND<u64, 3, 4>`
+//! is a 3D array of 4x4x4 patches. Because the patch length cannot be inferred from the
+//! arguments of a constructor, the type of the array must be spelled out when it is created.
+//! # Example Usage
+//! An example of a simple diffusion step over a 4D array of 3D plus time data:
+//! ```
+//! use zarray::znd::ZArrayND;
+//! let mut data: ZArrayND<f32, 4> = ZArrayND::new([16, 16, 16, 4], 0f32);
+//! data.fill([4, 4, 4, 0], [12, 12, 12, 1], 1f32).unwrap();
+//! for t in 1..4 {
+//!   for z in 0..16isize { for y in 0..16isize { for x in 0..16isize {
+//!     let mut sum = 0f32;
+//!     for (dx, dy, dz) in [(-1, 0, 0), (1, 0, 0), (0, -1, 0), (0, 1, 0), (0, 0, -1), (0, 0, 1)] {
+//!       sum += *data.bounded_get([x + dx, y + dy, z + dz, t - 1]).unwrap_or(&0f32);
+//!     }
+//!     data.set([x as usize, y as usize, z as usize, t as usize], sum / 6f32).unwrap();
+//!   } } }
+//! }
+//! ```
+
+use core::fmt::{Debug, Formatter};
+use core::hash::{Hash, Hasher};
+use core::marker::PhantomData;
+use core::borrow::Borrow;
+use core::ops::{Add, Index, IndexMut, Mul};
+use core::iter::Enumerate;
+use core::sync::atomic::{AtomicUsize, Ordering};
+use core::slice::{ChunksExact, ChunksExactMut, Iter, IterMut};
+use alloc::boxed::Box;
+use alloc::sync::Arc;
+use alloc::vec;
+use alloc::vec::Vec;
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+use crate::{AxisTopology, BuildError, EdgeMode, ErrorCoord, LookUpError, MAX_RANK};
+
+/// default length of the data patches in every dimension (ie 8, 8x8, 8x8x8, or 8x8x8x8 values)
+pub const DEFAULT_PATCH_EDGE: usize = 8;
+/// largest supported length of the data patches, which sets the size of the look-up tables
+const MAX_PATCH_EDGE: usize = 16;
+
+/// Private struct holding the Z-order layout of data patches that are E values long in each of
+/// N dimensions, including the look-up tables for converting between coordinates and indices
+pub(crate) struct PatchShape<const N: usize, const E: usize>;
+
+impl<const N: usize, const E: usize> PatchShape<N, E> {
+	/// number of bits of each coordinate that are used to find a value within a data patch
+	pub(crate) const BITS: usize = {
+		assert!(E == 4 || E == 8 || E == 16, "the patch edge of a ZArrayND must be 4, 8, or 16");
+		E.trailing_zeros() as usize
+	};
+	/// bit mask for the bits of a coordinate that are used within a data patch
+	pub(crate) const MASK: usize = E - 1;
+	/// number of values in each data patch
+	pub(crate) const SIZE: usize = 1 << (Self::BITS * N);
+	/// look-up table that spreads the bits of a coordinate out to every Nth bit of the index
+	/// within a data patch
+	const ZLUT: [usize; MAX_PATCH_EDGE] = spread_lut(N, Self::BITS);
+	/// reverse look-up table that splits each group of N interleaved bits of an index within a
+	/// data patch into one bit for each dimension
+	const REVERSE_ZLUT: [[usize; MAX_RANK]; 1 << MAX_RANK] = compact_lut(N);
+}
+
+/// function for generating the Z-order look-up table of a patch shape at compile time
+const fn spread_lut(rank: usize, bits: usize) -> [usize; MAX_PATCH_EDGE] {
+	let mut lut = [0; MAX_PATCH_EDGE];
+	let mut c = 0;
+	while c < MAX_PATCH_EDGE {
+		let mut bit = 0;
+		while bit < bits {
+			lut[c] |= ((c >> bit) & 1) << (bit * rank);
+			bit += 1;
+		}
+		c += 1;
+	}
+	return lut;
+}
+
+/// function for generating the reverse Z-order look-up table of a patch shape at compile time
+const fn compact_lut(rank: usize) -> [[usize; MAX_RANK]; 1 << MAX_RANK] {
+	let mut lut = [[0; MAX_RANK]; 1 << MAX_RANK];
+	let mut group = 0;
+	while group < lut.len() {
+		let mut d = 0;
+		while d < rank {
+			lut[group][d] = (group >> d) & 1;
+			d += 1;
+		}
+		group += 1;
+	}
+	return lut;
+}
+
+/// Private struct for holding a data patch of E values in each of N dimensions, in Z-order, for
+/// the arrays that allocate their patches one at a time (a *ZArrayND* keeps all of its patches
+/// in one *Vec*)
+#[derive(Debug, Clone)]
+pub(crate) struct Patch<T> {
+	contents: Box<[T]>
+}
+
+impl<T> Patch<T> {
+	/// Creates a patch that holds the given contents
+	pub(crate) fn new(contents: Box<[T]>) -> Patch<T> {
+		return Patch { contents };
+	}
+	/// Gets the values of this patch, in Z-order
+	pub(crate) fn contents(&self) -> &[T] {
+		return &self.contents;
+	}
+	/// Gets mutable access to the values of this patch, in Z-order
+	pub(crate) fn contents_mut(&mut self) -> &mut [T] {
+		return &mut self.contents;
+	}
+	/// data patch getter
+	/// # Parameters
+	/// * **coord** - coordinate (only the lowest bits of each dimension are used, rest of bits
+	/// are ignored)
+	/// # Returns
+	/// Returns a reference to the value stored in the patch at the location given by the lowest
+	/// bits of each dimension of the coordinate
+	pub(crate) fn get<const N: usize, const E: usize>(&self, coord: &[usize; N]) -> &T {
+		return &self.contents()[zorder_index::<N, E>(coord)];
+	}
+	/// mutable data patch getter
+	/// # Parameters
+	/// * **coord** - coordinate (only the lowest bits of each dimension are used, rest of bits
+	/// are ignored)
+	/// # Returns
+	/// Returns a mutable reference to the value stored in the patch at the location given by
+	/// the lowest bits of each dimension of the coordinate
+	pub(crate) fn get_mut<const N: usize, const E: usize>(&mut self, coord: &[usize; N]) -> &mut T {
+		return &mut self.contents_mut()[zorder_index::<N, E>(coord)];
+	}
+}
+
+/// function for converting the lowest bits of a coordinate into the index of a value within a
+/// data patch, by interleaving the bits of each dimension (the first dimension is the lowest bit)
+pub(crate) fn zorder_index<const N: usize, const E: usize>(coord: &[usize; N]) -> usize {
+	let mut index = 0;
+	for (d, c) in coord.iter().enumerate() {
+		index |= PatchShape::<N, E>::ZLUT[c & PatchShape::<N, E>::MASK] << d;
+	}
+	return index;
+}
+
+/// function for getting the coordinate of a value in a data patch from its index within the
+/// patch (the reverse of *zorder_index(coord)*)
+pub(crate) fn patch_coord<const N: usize, const E: usize>(origin: &[usize; N], index: usize) -> [usize; N] {
+	let mut coord = *origin;
+	let group_mask = (1 << N) - 1;
+	for bit in 0..PatchShape::<N, E>::BITS {
+		let bits = &PatchShape::<N, E>::REVERSE_ZLUT[(index >> (bit * N)) & group_mask];
+		for (c, b) in coord.iter_mut().zip(bits) {
+			*c |= b << bit;
+		}
+	}
+	return coord;
+}
+
+/// function for getting the number of data patches along each dimension
+pub(crate) fn patch_dims<const N: usize, const E: usize>(dims: &[usize; N]) -> [usize; N] {
+	return dims.map(|d| (d + PatchShape::<N, E>::MASK) >> PatchShape::<N, E>::BITS);
+}
+
+/// function for getting the number of values in the data patches of an array, or None if it
+/// overflows (the dimensions, padded to whole data patches, must also fit in an isize)
+pub(crate) fn checked_data_len<const N: usize, const E: usize>(dims: &[usize; N]) -> Option<usize> {
+	if !dims.iter().all(|d| d.checked_add(PatchShape::<N, E>::MASK).is_some_and(|d| d <= isize::MAX as usize)) {
+		return None;
+	}
+	return patch_dims::<N, E>(dims).iter().try_fold(PatchShape::<N, E>::SIZE, |n, p| n.checked_mul(*p));
+}
+
+/// function for converting coordinate to index of data patch in the array of patches
+pub(crate) fn patch_index<const N: usize, const E: usize>(coord: &[usize; N], pdims: &[usize; N]) -> usize {
+	let mut pindex = 0;
+	for d in (0..N).rev() {
+		pindex = pindex * pdims[d] + (coord[d] >> PatchShape::<N, E>::BITS);
+	}
+	return pindex;
+}
+
+/// function for getting the coordinate of the first value of a data patch
+pub(crate) fn patch_origin<const N: usize, const E: usize>(pindex: usize, pdims: &[usize; N]) -> [usize; N] {
+	let mut origin = [0; N];
+	let mut remainder = pindex;
+	for (o, p) in origin.iter_mut().zip(pdims) {
+		*o = (remainder % p) << PatchShape::<N, E>::BITS;
+		remainder /= p;
+	}
+	return origin;
+}
+
+/// function for checking whether a box (inclusive start, exclusive end) contains no coordinates
+pub(crate) fn is_empty_box<C: PartialOrd, const N: usize>(start: &[C; N], end: &[C; N]) -> bool {
+	return start.iter().zip(end).any(|(s, e)| s >= e);
+}
+
+/// function for stepping a coordinate through a box (inclusive start, exclusive end) in raster
+/// order, where the first dimension changes fastest
+/// # Returns
+/// Returns false if the coordinate was the last one in the box (in which case it is reset to
+/// the start of the box)
+pub(crate) fn next_in_box<C, const N: usize>(coord: &mut [C; N], start: &[C; N], end: &[C; N]) -> bool
+	where C: Copy + PartialOrd + Add<Output=C> + From<u8> {
+	for d in 0..N {
+		coord[d] = coord[d] + C::from(1);
+		if coord[d] < end[d] {
+			return true;
+		}
+		coord[d] = start[d];
+	}
+	return false;
+}
+
+/// function for counting the coordinates in a box (inclusive start, exclusive end)
+fn box_volume<const N: usize>(start: &[usize; N], end: &[usize; N]) -> usize {
+	return start.iter().zip(end).map(|(s, e)| e.saturating_sub(*s)).product();
+}
+
+/// This is primary struct for z-indexed N-dimensional arrays. Create new instances with
+/// ZArrayND::new([x_size, y_size, ...], initial_value)
+#[derive(Debug, Clone)]
+pub struct ZArrayND<T, const N: usize, const E: usize = DEFAULT_PATCH_EDGE> {
+	// for heap allocated data
+	/// size of each dimension
+	dims: [usize; N],
+	/// number of data patches along each dimension
+	pdims: [usize; N],
+	/// values of every data patch in Z-order, with patch i at i * SIZE..(i + 1) * SIZE
+	data: Vec<T>,
+	/// how out-of-bounds coordinates are handled on each axis
+	topology: [AxisTopology; N],
+	/// which data patches changed since a snapshot was taken
+	changes: ChangeTracker,
+}
+
+/// source of the numbers that tell arrays apart for *ChangeTracker*
+static NEXT_ARRAY_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// Private struct for keeping track of the changes to the data patches of an array, so that a
+/// snapshot only copies the patches that changed since the previous snapshot. A clone gets a
+/// new array number, because its changes are not the changes of the original array.
+#[derive(Debug)]
+struct ChangeTracker {
+	/// number that is unique to this array
+	array_id: usize,
+	/// number of changes made to the array
+	revision: u64,
+	/// revision of the last change to each data patch
+	patch_revisions: Vec<u64>,
+}
+
+impl ChangeTracker {
+	fn new(patch_count: usize) -> ChangeTracker {
+		return ChangeTracker { array_id: NEXT_ARRAY_ID.fetch_add(1, Ordering::Relaxed), revision: 0,
+			patch_revisions: vec![0; patch_count] };
+	}
+}
+
+impl Clone for ChangeTracker {
+	fn clone(&self) -> Self {
+		return ChangeTracker { array_id: NEXT_ARRAY_ID.fetch_add(1, Ordering::Relaxed), revision: self.revision,
+			patch_revisions: self.patch_revisions.clone() };
+	}
+}
+
+/// This struct holds the values of a *ZArrayND* at the time that the snapshot was taken (see
+/// *ZArrayND.snapshot()*), in reference-counted data patches. Cloning a snapshot and taking a
+/// snapshot with *ZArrayND.snapshot_since(previous)* share the data patches instead of copying
+/// them.
+#[derive(Debug, Clone)]
+pub struct ZArrayNDSnapshot<T, const N: usize, const E: usize = DEFAULT_PATCH_EDGE> {
+	/// number of the array that the snapshot was taken of
+	array_id: usize,
+	/// revision of that array when the snapshot was taken
+	revision: u64,
+	dims: [usize; N],
+	topology: [AxisTopology; N],
+	patches: Vec<Arc<[T]>>,
+}
+
+impl<T, const N: usize, const E: usize> ZArrayNDSnapshot<T, N, E> {
+	/// Gets the size of the array in each dimension when the snapshot was taken
+	/// # Returns
+	/// Returns an array of the size of each dimension
+	pub fn dimensions(&self) -> [usize; N] {
+		return self.dims;
+	}
+}
+
+impl<T, const N: usize, const E: usize> ZArrayNDSnapshot<T, N, E> where T: Clone {
+	/// Copies the values of this snapshot into a new array
+	/// # Returns
+	/// Returns a new array with the values (and topology) of this snapshot
+	pub fn to_array(&self) -> ZArrayND<T, N, E> {
+		return ZArrayND::from_patch_fn(self.dims, |pindex| self.patches[pindex].iter().cloned())
+			.with_topology(self.topology);
+	}
+}
+
+// only the in-bounds values are compared and hashed, because the padding at the end of the
+// data patches depends on how the array was made (eg cropping keeps the old values)
+impl<T, const N: usize, const E: usize> PartialEq for ZArrayND<T, N, E> where T: PartialEq{
+	fn eq(&self, other: &Self) -> bool {
+		self.dims == other.dims
+		&& self.topology == other.topology
+		&& self.iter().map(|item| item.value).eq(other.iter().map(|item| item.value))
+	}
+}
+impl<T, const N: usize, const E: usize> Eq for ZArrayND<T, N, E> where T: Eq{}
+impl<T, const N: usize, const E: usize> Hash for ZArrayND<T, N, E> where T: Hash{
+	fn hash<H: Hasher>(&self, state: &mut H) {
+		self.dims.hash(state);
+		self.topology.hash(state);
+		for item in self.iter() {
+			item.value.hash(state);
+		}
+	}
+}
+
+impl<T, const N: usize, const E: usize> AsRef<ZArrayND<T, N, E>> for ZArrayND<T, N, E> {
+	fn as_ref(&self) -> &ZArrayND<T, N, E> {
+		self
+	}
+}
+impl<T, const N: usize, const E: usize> AsMut<ZArrayND<T, N, E>> for ZArrayND<T, N, E> {
+	fn as_mut(&mut self) -> &mut ZArrayND<T, N, E> {
+		self
+	}
+}
+
+impl<T, const N: usize, const E: usize> Index<[usize; N]> for ZArrayND<T, N, E> {
+	type Output = T;
+	/// Gets a reference to the value at the given coordinate, panicking with the *LookUpError*
+	/// message if the coordinate is out of bounds
+	fn index(&self, index: [usize; N]) -> &Self::Output {
+		match self.get(index) {
+			Ok(v) => v,
+			Err(e) => panic!("{}", e)
+		}
+	}
+}
+impl<T, const N: usize, const E: usize> IndexMut<[usize; N]> for ZArrayND<T, N, E> {
+	/// Gets a mutable reference to the value at the given coordinate, panicking with the
+	/// *LookUpError* message if the coordinate is out of bounds
+	fn index_mut(&mut self, index: [usize; N]) -> &mut Self::Output {
+		match self.get_mut(index) {
+			Ok(v) => v,
+			Err(e) => panic!("{}", e)
+		}
+	}
+}
+
+impl<T, const N: usize, const E: usize> ZArrayND<T, N, E> where T: Default {
+	/// Create a Z-index N-dimensional array of values, initially filled with the default values
+	/// # Parameters
+	/// * **dims** - size of this array in each dimension
+	/// # Returns
+	/// Returns an initialized *ZArrayND* struct filled with default values
+	pub fn new_with_default(dims: [usize; N]) -> ZArrayND<T, N, E> {
+		return ZArrayND::from_patch_fn(dims, |_| (0..Self::PATCH_SIZE).map(|_| T::default()));
+	}
+
+	/// Create a Z-index N-dimensional array from an iterator of values in row-major order (ie
+	/// the first dimension changes fastest, then the second, and so on). The iterator must
+	/// provide exactly as many values as there are cells in the array.
+	/// # Parameters
+	/// * **dims** - size of this array in each dimension
+	/// * **values** - iterator of the values to store, in row-major order
+	/// # Returns
+	/// Returns a Result type that holds either the new *ZArrayND* or a
+	/// *BuildError::SizeMismatch* if the number of values does not match the dimensions
+	pub fn from_iter_row_major(dims: [usize; N], values: impl IntoIterator<Item=T>)
+		-> Result<ZArrayND<T, N, E>, BuildError> {
+		let mut array = ZArrayND::new_with_default(dims);
+		let expected = box_volume(&[0; N], &dims);
+		let mut values = values.into_iter();
+		let mut coord = [0; N];
+		for i in 0..expected {
+			match values.next() {
+				Some(v) => array.set_unchecked(coord, v),
+				None => return Err(BuildError::SizeMismatch { expected, actual: i })
+			}
+			next_in_box(&mut coord, &[0; N], &dims);
+		}
+		let extra = values.count();
+		if extra > 0 {
+			return Err(BuildError::SizeMismatch { expected, actual: expected + extra });
+		}
+		return Ok(array);
+	}
+
+	/// Create a Z-index N-dimensional array from an iterator of coordinate and value pairs, in
+	/// any order. The iterator must provide exactly one value for every coordinate of the array.
+	/// # Parameters
+	/// * **dims** - size of this array in each dimension
+	/// * **values** - iterator of (coordinate, value) pairs
+	/// # Returns
+	/// Returns a Result type that holds either the new *ZArrayND* or a *BuildError* if a
+	/// coordinate is out of bounds, provided twice, or missing
+	pub fn from_iter_coords(dims: [usize; N], values: impl IntoIterator<Item=([usize; N], T)>)
+		-> Result<ZArrayND<T, N, E>, BuildError> {
+		let mut array = ZArrayND::new_with_default(dims);
+		let expected = box_volume(&[0; N], &dims);
+		let mut visited = vec![false; expected];
+		let mut count = 0;
+		for (coord, v) in values {
+			*array.get_mut(coord)? = v;
+			let i = coord.iter().zip(&dims).rev().fold(0, |i, (c, d)| i * d + c);
+			if visited[i] {
+				return Err(BuildError::DuplicateCoord(ErrorCoord::new(&coord)));
+			}
+			visited[i] = true;
+			count += 1;
+		}
+		if count != expected {
+			return Err(BuildError::SizeMismatch { expected, actual: count });
+		}
+		return Ok(array);
+	}
+}
+
+impl<T, const N: usize, const E: usize> ZArrayND<T, N, E> where T: Copy {
+	/// Create a Z-index N-dimensional array of values, initially filled with the provided
+	/// default value
+	/// # Parameters
+	/// * **dims** - size of this array in each dimension
+	/// * **default_val** - initial fill value (it must implement the Copy trait)
+	/// # Returns
+	/// Returns an initialized *ZArrayND* struct filled with *default_val*
+	pub fn new(dims: [usize; N], default_val: T) -> ZArrayND<T, N, E> {
+		return ZArrayND::from_data(dims, vec![default_val; Self::data_len(&dims)]);
+	}
+}
+
+impl<T, const N: usize, const E: usize> ZArrayND<T, N, E> where T: Clone {
+	/// Fills a box-shaped region of this array with a given value, or returns a *LookUpError*
+	/// if the provided coordinates go out of bounds. If you just want to ignore any
+	/// out-of-bounds coordinates, then you should use the *bounded_fill(start, end)* method
+	/// instead. If you want access to wrap-around, then use the *wrapped_fill(start, end)*
+	/// method.
+	/// # Parameters
+	/// * **start** - the first corner of the region (inclusive)
+	/// * **end** - the second corner of the region (exclusive)
+	/// * **new_val** - value to store in the array in the bounding box defined by
+	/// start -> end
+	/// # Returns
+	/// Returns a Result type that is either empty or a *LookUpError* signalling that a
+	/// coordinate is out of bounds
+	pub fn fill(&mut self, start: [usize; N], end: [usize; N], new_val: impl Borrow<T>)
+				-> Result<(), LookUpError> {
+		if is_empty_box(&start, &end) {
+			return Ok(());
+		}
+		let mut coord = start;
+		loop {
+			self.set(coord, new_val.borrow().clone())?;
+			if !next_in_box(&mut coord, &start, &end) {
+				return Ok(());
+			}
+		}
+	}
+
+	/// Fills a box-shaped region of this array with a given value, wrapping the axese when
+	/// coordinates go out of bounds.
+	/// # Parameters
+	/// * **start** - the first corner of the region (inclusive)
+	/// * **end** - the second corner of the region (exclusive)
+	/// * **new_val** - value to store in the array in the bounding box defined by
+	/// start -> end with wrapped axese
+	pub fn wrapped_fill(&mut self, start: [isize; N], end: [isize; N], new_val: impl Borrow<T>) {
+		self.for_each_in_box(start, end, |array, coord| array.wrapped_set(coord, new_val.borrow().clone()));
+	}
+
+	/// Fills a box-shaped region of this array with a given value, ignoring any coordinates
+	/// that go out of bounds. Coordinates outside of a wrapped or clamped axis are handled by
+	/// the topology of this array (see *set_topology(topology)*), so on a cylindrical map the
+	/// region can cross the edge of the wrapped axis.
+	/// # Parameters
+	/// * **start** - the first corner of the region (inclusive)
+	/// * **end** - the second corner of the region (exclusive)
+	/// * **new_val** - value to store in the array in the bounding box defined by
+	/// start -> end
+	pub fn bounded_fill(&mut self, start: [isize; N], end: [isize; N], new_val: impl Borrow<T>) {
+		self.for_each_in_box(start, end, |array, coord| array.bounded_set(coord, new_val.borrow().clone()));
+	}
+
+	/// Fills a box-shaped region of this array with a given value, using the provided edge
+	/// mode to handle coordinates that are negative or outside the size of this array.
+	/// # Parameters
+	/// * **start** - the first corner of the region (inclusive)
+	/// * **end** - the second corner of the region (exclusive)
+	/// * **edge_mode** - how to handle out-of-bounds coordinates
+	/// * **new_val** - value to store in the array in the bounding box defined by
+	/// start -> end
+	/// # Returns
+	/// Returns a Result type that is either empty or a *LookUpError* if a coordinate is out
+	/// of bounds and the edge mode is *EdgeMode::Error*
+	pub fn sample_fill(&mut self, start: [isize; N], end: [isize; N], edge_mode: &EdgeMode<T>,
+		new_val: impl Borrow<T>) -> Result<(), LookUpError> {
+		if is_empty_box(&start, &end) {
+			return Ok(());
+		}
+		let mut coord = start;
+		loop {
+			self.sample_set(coord, edge_mode, new_val.borrow().clone())?;
+			if !next_in_box(&mut coord, &start, &end) {
+				return Ok(());
+			}
+		}
+	}
+
+	/// private function for changing the size of this array, moving every value by the given
+	/// offset. Whole data patches are copied if the offset is a multiple of the patch length
+	/// on every axis, otherwise the values are re-packed into new patches.
+	/// # Parameters
+	/// * **offset** - how far each value moves along each axis
+	/// * **new_dims** - new size of this array in each dimension
+	/// * **fill** - value for new cells (must not be None unless every new cell was in
+	/// bounds before)
+	/// # Panics
+	/// Panics if the new size overflows (including the padding to whole data patches)
+	fn reshape(&mut self, offset: [isize; N], new_dims: [usize; N], fill: Option<&T>) {
+		assert!(checked_data_len::<N, E>(&new_dims).is_some(), "array size {:?} is too large", new_dims);
+		let old_dims = self.dims;
+		let old_pdims = self.pdims;
+		// gets the old coordinate of a new coordinate, if it was in bounds
+		let source = |coord: &[usize; N]| -> Option<[usize; N]> {
+			let mut old = [0; N];
+			for d in 0..N {
+				let c = coord[d] as isize - offset[d];
+				if c < 0 || c >= old_dims[d] as isize {
+					return None;
+				}
+				old[d] = c as usize;
+			}
+			return Some(old);
+		};
+		let new_pdims = patch_dims::<N, E>(&new_dims);
+		let aligned = offset.iter().all(|o| o & PatchShape::<N, E>::MASK as isize == 0);
+		let new = if aligned {
+			// copy whole patches to their new places in the patch grid
+			ZArrayND::from_patch_fn(new_dims, |pindex| {
+				let origin = patch_origin::<N, E>(pindex, &new_pdims);
+				let old = source(&origin);
+				// cells that were padding in the old array may be in bounds now
+				let was_edge = old.is_some_and(|old| (0..N).any(|d| old[d] + E > old_dims[d]));
+				let patch = old.map(|old| self.patch(patch_index::<N, E>(&old, &old_pdims)));
+				(0..Self::PATCH_SIZE).map(move |index| match patch {
+					Some(patch) if !was_edge => patch[index].clone(),
+					Some(patch) => {
+						let coord = patch_coord::<N, E>(&origin, index);
+						if coord.iter().zip(&new_dims).all(|(c, d)| c < d) && source(&coord).is_none() {
+							fill.expect("fill value for new cells").clone()
+						} else {
+							patch[index].clone()
+						}
+					},
+					None => fill.expect("fill value for new cells").clone()
+				})
+			})
+		} else {
+			// copy the values into new patches, filling the padding with the nearest values
+			let old_array = &*self;
+			ZArrayND::from_patch_fn(new_dims, |pindex| {
+				let origin = patch_origin::<N, E>(pindex, &new_pdims);
+				(0..Self::PATCH_SIZE).map(move |index| {
+					let coord = patch_coord::<N, E>(&origin, index);
+					match (source(&coord), fill) {
+						(Some(old), _) => old_array.get_unchecked(old).clone(),
+						(None, Some(fill)) => fill.clone(),
+						(None, None) => old_array.get_unchecked(core::array::from_fn(|d| {
+							(coord[d] as isize - offset[d]).clamp(0, old_dims[d] as isize - 1) as usize
+						})).clone()
+					}
+				})
+			})
+		};
+		let topology = self.topology;
+		*self = new.with_topology(topology);
+	}
+
+	/// Changes the size of this array, keeping the existing values at their coordinates.
+	/// Values outside the new size are dropped and new cells are set to the fill value. Whole
+	/// data patches are copied, so this is much faster than copying one value at a time.
+	/// # Parameters
+	/// * **new_dims** - new size of this array in each dimension
+	/// * **fill** - value for the new cells
+	/// # Panics
+	/// Panics if the new size overflows *usize* (including the padding to whole data patches)
+	pub fn resize(&mut self, new_dims: [usize; N], fill: impl Borrow<T>) {
+		self.reshape([0; N], new_dims, Some(fill.borrow()));
+	}
+
+	/// Cuts this array down to a box-shaped region, such that the first corner of the region
+	/// becomes the origin. Whole data patches are copied if the first corner is a multiple of
+	/// the patch length (eg 8) on every axis, otherwise the values are re-packed.
+	/// # Parameters
+	/// * **start** - the first corner of the region to keep (inclusive)
+	/// * **dims** - size of the region in each dimension
+	/// # Returns
+	/// Returns a Result type that is either empty or a *LookUpError* signalling that the region
+	/// goes out of bounds (in which case this array is unchanged)
+	pub fn crop(&mut self, start: [usize; N], dims: [usize; N]) -> Result<(), LookUpError> {
+		if (0..N).any(|d| start[d].saturating_add(dims[d]) > self.dims[d]) {
+			let last = core::array::from_fn(|d| start[d].saturating_add(dims[d].saturating_sub(1)) as isize);
+			return Err(self.lookup_error(last));
+		}
+		self.reshape(start.map(|s| -(s as isize)), dims, None);
+		return Ok(());
+	}
+
+	/// Adds cells around the edges of this array, moving the existing values away from the
+	/// origin by the size of the padding before them. Whole data patches are copied if the
+	/// padding before the values is a multiple of the patch length (eg 8) on every axis,
+	/// otherwise the values are re-packed.
+	/// # Parameters
+	/// * **before** - number of cells to add before the first coordinate of each axis
+	/// * **after** - number of cells to add after the last coordinate of each axis
+	/// * **fill** - value for the new cells
+	/// # Panics
+	/// Panics if the new size overflows *usize* (including the padding to whole data patches)
+	pub fn pad(&mut self, before: [usize; N], after: [usize; N], fill: impl Borrow<T>) {
+		let new_dims: [usize; N] = core::array::from_fn(|d| before[d].checked_add(self.dims[d])
+			.and_then(|n| n.checked_add(after[d])).expect("padded array size overflows"));
+		self.reshape(before.map(|b| b as isize), new_dims, Some(fill.borrow()));
+	}
+
+	/// Takes a snapshot of this array, which copies the values into reference-counted data
+	/// patches. Use *snapshot_since(previous)* to take the following snapshots, which only copy
+	/// the patches that changed.
+	/// # Returns
+	/// Returns a snapshot of the values (and topology) of this array
+	pub fn snapshot(&self) -> ZArrayNDSnapshot<T, N, E> {
+		return self.snapshot_sharing(None);
+	}
+
+	/// Takes a snapshot of this array that shares the data patches that have not changed since
+	/// a previous snapshot of this array, so it only copies the patches that changed. This is
+	/// much cheaper than *clone()* for keeping the previous states of a large array (eg for
+	/// undo). If the previous snapshot was taken of another array (including a clone of this
+	/// one), then every patch is copied.
+	/// # Parameters
+	/// * **previous** - an earlier snapshot of this array
+	/// # Returns
+	/// Returns a snapshot of the values (and topology) of this array
+	pub fn snapshot_since(&self, previous: &ZArrayNDSnapshot<T, N, E>) -> ZArrayNDSnapshot<T, N, E> {
+		return self.snapshot_sharing(Some(previous));
+	}
+
+	/// private function for taking a snapshot, sharing the unchanged patches of the previous
+	/// snapshot (if any)
+	fn snapshot_sharing(&self, previous: Option<&ZArrayNDSnapshot<T, N, E>>) -> ZArrayNDSnapshot<T, N, E> {
+		let patches = self.data.chunks_exact(Self::PATCH_SIZE).enumerate().map(|(pindex, patch)| match previous {
+			Some(previous) if self.unchanged_since(previous, pindex) => previous.patches[pindex].clone(),
+			_ => Arc::from(patch)
+		}).collect();
+		return ZArrayNDSnapshot { array_id: self.changes.array_id, revision: self.changes.revision, dims: self.dims,
+			topology: self.topology, patches };
+	}
+
+	/// Sets the values (and topology) of this array back to those of a snapshot, copying only
+	/// the data patches that changed since the snapshot was taken (or every patch, if the
+	/// snapshot was taken of another array).
+	/// # Parameters
+	/// * **snapshot** - the snapshot to restore
+	pub fn restore(&mut self, snapshot: &ZArrayNDSnapshot<T, N, E>) {
+		if snapshot.array_id != self.changes.array_id || snapshot.dims != self.dims {
+			*self = snapshot.to_array();
+			return;
+		}
+		for pindex in 0..self.patch_count() {
+			if !self.unchanged_since(snapshot, pindex) {
+				self.touch(pindex);
+				self.patch_mut(pindex).clone_from_slice(&snapshot.patches[pindex]);
+			}
+		}
+		self.topology = snapshot.topology;
+	}
+}
+
+impl<T, const N: usize, const E: usize> ZArrayND<T, N, E> where T: Clone {
+	/// Create a Z-index N-dimensional array from a slice of values in raster order (aka
+	/// row-major order, the first dimension changes fastest), copying the values one data
+	/// patch at a time
+	/// # Parameters
+	/// * **dims** - size of this array in each dimension
+	/// * **values** - the values in raster order
+	/// # Returns
+	/// Returns a Result type that holds either the new *ZArrayND* or a
+	/// *BuildError::SizeMismatch* if the number of values does not match the dimensions
+	pub fn from_row_major(dims: [usize; N], values: &[T]) -> Result<ZArrayND<T, N, E>, BuildError> {
+		let expected = box_volume(&[0; N], &dims);
+		if values.len() != expected {
+			return Err(BuildError::SizeMismatch { expected, actual: values.len() });
+		}
+		let mut strides = [1; N];
+		for d in 1..N {
+			strides[d] = strides[d - 1] * dims[d - 1];
+		}
+		let pdims = patch_dims::<N, E>(&dims);
+		return Ok(ZArrayND::from_patch_fn(dims, |pindex| {
+			let origin = patch_origin::<N, E>(pindex, &pdims);
+			(0..Self::PATCH_SIZE).map(move |index| {
+				// the padding is filled with the nearest values
+				let coord = patch_coord::<N, E>(&origin, index);
+				let offset: usize = (0..N).map(|d| coord[d].min(dims[d] - 1) * strides[d]).sum();
+				values[offset].clone()
+			})
+		}));
+	}
+
+	/// Create a Z-index N-dimensional array from a *Vec* of values in raster order, moving the
+	/// values into the data patches instead of copying them (private, used when deserializing
+	/// and by the conversions from nested *Vec*s and images). The number of values must match
+	/// the dimensions.
+	pub(crate) fn from_row_major_vec(dims: [usize; N], values: Vec<T>) -> ZArrayND<T, N, E> {
+		debug_assert_eq!(values.len(), box_volume(&[0; N], &dims));
+		let mut strides = [1; N];
+		for d in 1..N {
+			strides[d] = strides[d - 1] * dims[d - 1];
+		}
+		let pdims = patch_dims::<N, E>(&dims);
+		let mut values: Vec<Option<T>> = values.into_iter().map(Some).collect();
+		return ZArrayND::from_patch_fn(dims, |pindex| {
+			let origin = patch_origin::<N, E>(pindex, &pdims);
+			let mut contents: Vec<Option<T>> = (0..Self::PATCH_SIZE).map(|index| {
+				let coord = patch_coord::<N, E>(&origin, index);
+				if (0..N).any(|d| coord[d] >= dims[d]) {
+					return None;
+				}
+				let offset: usize = (0..N).map(|d| coord[d] * strides[d]).sum();
+				values[offset].take()
+			}).collect();
+			// the padding is filled with the nearest values, which are always in the same patch
+			for index in 0..Self::PATCH_SIZE {
+				if contents[index].is_none() {
+					let coord = patch_coord::<N, E>(&origin, index);
+					let nearest: [usize; N] = core::array::from_fn(|d| coord[d].min(dims[d] - 1));
+					contents[index] = contents[zorder_index::<N, E>(&nearest)].clone();
+				}
+			}
+			contents.into_iter().map(|v| v.expect("every value of a data patch is filled"))
+		});
+	}
+
+	/// Copies the values of this array into a *Vec* in raster order (aka row-major order, the
+	/// first dimension changes fastest), one row of data patches at a time
+	/// # Returns
+	/// Returns a *Vec* of all the values in this array
+	pub fn to_row_major(&self) -> Vec<T> {
+		let mut values = Vec::with_capacity(box_volume(&[0; N], &self.dims));
+		if is_empty_box(&[0; N], &self.dims) {
+			return values;
+		}
+		// visit each row (all coordinates of the first dimension) in raster order
+		let mut row_end = self.dims;
+		row_end[0] = 1;
+		let mut row = [0; N];
+		loop {
+			let row_index = zorder_index::<N, E>(&row);
+			for px in 0..self.pdims[0] {
+				let mut origin = row;
+				origin[0] = px << PatchShape::<N, E>::BITS;
+				let patch = self.patch(patch_index::<N, E>(&origin, &self.pdims));
+				let end = (origin[0] + E).min(self.dims[0]);
+				for x in origin[0]..end {
+					values.push(patch[row_index | PatchShape::<N, E>::ZLUT[x & PatchShape::<N, E>::MASK]].clone());
+				}
+			}
+			if !next_in_box(&mut row, &[0; N], &row_end) {
+				return values;
+			}
+		}
+	}
+}
+
+impl<T, const N: usize, const E: usize> ZArrayND<T, N, E> where T: Clone {
+	/// private function for placing a 2D coordinate on a plane of this array
+	fn plane_coord(axes: [usize; 2], fixed: [usize; N], coord: &[usize; 2]) -> [usize; N] {
+		let mut plane_coord = fixed;
+		plane_coord[axes[0]] = coord[0];
+		plane_coord[axes[1]] = coord[1];
+		return plane_coord;
+	}
+
+	/// Copies a plane of this array into a new 2D array, one data patch at a time
+	/// # Parameters
+	/// * **axes** - the axes of this array that become the first and second axes of the plane
+	/// * **fixed** - position of the plane on the other axes (must be in bounds, the values for
+	/// the plane axes are ignored)
+	/// # Returns
+	/// Returns a 2D array of the values of the plane
+	pub(crate) fn copy_plane(&self, axes: [usize; 2], fixed: [usize; N]) -> ZArrayND<T, 2, E> {
+		let dims = [self.dims[axes[0]], self.dims[axes[1]]];
+		let pdims = patch_dims::<2, E>(&dims);
+		return ZArrayND::from_patch_fn(dims, |pindex| {
+			// each 2D patch lies within a single patch of this array
+			let origin = patch_origin::<2, E>(pindex, &pdims);
+			let patch = self.patch(patch_index::<N, E>(&Self::plane_coord(axes, fixed, &origin), &self.pdims));
+			(0..PatchShape::<2, E>::SIZE).map(move |index| {
+				patch[zorder_index::<N, E>(&Self::plane_coord(axes, fixed, &patch_coord::<2, E>(&origin, index)))].clone()
+			})
+		});
+	}
+
+	/// Copies the values of a 2D array into a plane of this array, one data patch at a time
+	/// # Parameters
+	/// * **axes** - the axes of this array that are the first and second axes of the plane
+	/// * **fixed** - position of the plane on the other axes (must be in bounds, the values for
+	/// the plane axes are ignored)
+	/// * **plane** - the values to copy, which must have the same size as the plane
+	pub(crate) fn paste_plane(&mut self, axes: [usize; 2], fixed: [usize; N], plane: &ZArrayND<T, 2, E>) {
+		for (pindex, source) in plane.data.chunks_exact(PatchShape::<2, E>::SIZE).enumerate() {
+			let origin = patch_origin::<2, E>(pindex, &plane.pdims);
+			let target = patch_index::<N, E>(&Self::plane_coord(axes, fixed, &origin), &self.pdims);
+			self.touch(target);
+			let patch = self.patch_mut(target);
+			for (index, value) in source.iter().enumerate() {
+				let coord = patch_coord::<2, E>(&origin, index);
+				if coord[0] < plane.dims[0] && coord[1] < plane.dims[1] {
+					patch[zorder_index::<N, E>(&Self::plane_coord(axes, fixed, &coord))] = value.clone();
+				}
+			}
+		}
+	}
+}
+
+impl<T, const N: usize, const E: usize> ZArrayND<T, N, E> {
+	/// number of values in each data patch
+	const PATCH_SIZE: usize = PatchShape::<N, E>::SIZE;
+	/// compile-time check that the number of dimensions is supported
+	const RANK_CHECK: () = assert!(N > 0 && N <= MAX_RANK, "ZArrayND supports 1 to 4 dimensions");
+
+	/// private function for getting the number of values needed to hold the data patches of an
+	/// array of the given size
+	fn data_len(dims: &[usize; N]) -> usize {
+		return patch_dims::<N, E>(dims).iter().product::<usize>() * Self::PATCH_SIZE;
+	}
+
+	/// private constructor that builds the patches with the given function (which takes the
+	/// index of the patch and returns its contents in Z-order)
+	pub(crate) fn from_patch_fn<I>(dims: [usize; N], mut patch_fn: impl FnMut(usize) -> I) -> ZArrayND<T, N, E>
+		where I: IntoIterator<Item=T> {
+		let len = Self::data_len(&dims);
+		let mut data = Vec::with_capacity(len);
+		for pindex in 0..len / Self::PATCH_SIZE {
+			data.extend(patch_fn(pindex));
+		}
+		return ZArrayND::from_data(dims, data);
+	}
+
+	/// private constructor that takes the values of every patch in Z-order (the number of
+	/// values must match the patches of the dimensions)
+	fn from_data(dims: [usize; N], data: Vec<T>) -> ZArrayND<T, N, E> {
+		#[allow(clippy::let_unit_value)]
+		let _ = Self::RANK_CHECK;
+		assert_eq!(data.len(), Self::data_len(&dims), "every data patch must hold {} values", Self::PATCH_SIZE);
+		let pdims = patch_dims::<N, E>(&dims);
+		let patch_count = data.len() / Self::PATCH_SIZE;
+		return ZArrayND { dims, pdims, data, topology: [AxisTopology::default(); N],
+			changes: ChangeTracker::new(patch_count) };
+	}
+
+	/// private function for getting the values of a data patch, in Z-order
+	fn patch(&self, pindex: usize) -> &[T] {
+		return &self.data[pindex * Self::PATCH_SIZE..(pindex + 1) * Self::PATCH_SIZE];
+	}
+
+	/// private function for getting mutable access to the values of a data patch, in Z-order
+	/// (the change must be recorded with *touch(pindex)*)
+	fn patch_mut(&mut self, pindex: usize) -> &mut [T] {
+		return &mut self.data[pindex * Self::PATCH_SIZE..(pindex + 1) * Self::PATCH_SIZE];
+	}
+
+	/// private function for getting the index in the data of the value at a coordinate
+	fn data_index(&self, coord: &[usize; N]) -> usize {
+		return patch_index::<N, E>(coord, &self.pdims) * Self::PATCH_SIZE + zorder_index::<N, E>(coord);
+	}
+
+	/// Create a Z-index N-dimensional array of values, initially filled with the provided
+	/// constructor function. Note that the constructor function may be called for coordinates
+	/// that are outside the requested dimensions in order to initialize memory in 8-value
+	/// blocks. To avoid this, use only dimensions that are multiples of 8.
+	/// # Parameters
+	/// * **dims** - size of this array in each dimension
+	/// * **constructor** - function which takes in the coordinate and returns a value of type T
+	/// # Returns
+	/// Returns an initialized *ZArrayND* struct filled with the constructed values
+	pub fn new_with_constructor(dims: [usize; N], constructor: impl Fn([usize; N]) -> T) -> ZArrayND<T, N, E> {
+		let pdims = patch_dims::<N, E>(&dims);
+		let constructor = &constructor;
+		return ZArrayND::from_patch_fn(dims, |pindex| {
+			let origin = patch_origin::<N, E>(pindex, &pdims);
+			(0..Self::PATCH_SIZE).map(move |i| constructor(patch_coord::<N, E>(&origin, i)))
+		});
+	}
+
+	/// Gets the size of this array in each dimension
+	/// # Returns
+	/// Returns an array of the size of each dimension
+	pub fn dimensions(&self) -> [usize; N] {
+		return self.dims;
+	}
+
+	/// Gets the number of data patches in this array (private, used by unit tests)
+	#[allow(dead_code)]
+	pub(crate) fn patch_count(&self) -> usize {
+		return self.data.len() / Self::PATCH_SIZE;
+	}
+
+	/// private function for recording a change to a data patch (call it before the patch is
+	/// mutated)
+	fn touch(&mut self, pindex: usize) {
+		self.changes.revision += 1;
+		self.changes.patch_revisions[pindex] = self.changes.revision;
+	}
+
+	/// private function for recording a change to every data patch
+	fn touch_all(&mut self) {
+		self.changes.revision += 1;
+		self.changes.patch_revisions.fill(self.changes.revision);
+	}
+
+	/// private function for recording a change to the data patches that overlap a box-shaped
+	/// region (inclusive start, exclusive end, must be in bounds)
+	fn touch_region(&mut self, start: [usize; N], end: [usize; N]) {
+		let mut region = PatchRegion::<N, E>::new(start, end);
+		let mut pindex = 0;
+		while let Some(skip) = region.next_patch(&self.pdims) {
+			pindex += skip;
+			self.touch(pindex);
+			pindex += 1;
+		}
+	}
+
+	/// private function for checking whether a data patch is the same as it was when a snapshot
+	/// of this array was taken
+	fn unchanged_since<S>(&self, snapshot: &ZArrayNDSnapshot<S, N, E>, pindex: usize) -> bool {
+		return snapshot.array_id == self.changes.array_id && snapshot.dims == self.dims
+			&& self.changes.patch_revisions[pindex] <= snapshot.revision;
+	}
+
+	/// Sets the topology of this array, which determines how each axis handles out-of-bounds
+	/// coordinates: *get(coord)*, *set(coord, val)*, the *bounded_get(coord)* family of methods,
+	/// *bounded_iter_region(start, end)*, and the methods that take an *EdgeMode* (such as
+	/// *sample(...)*, *stencil(...)* and the neighbor iterators) all wrap or clamp coordinates
+	/// on the wrapped or clamped axes. The edge mode only applies to the bounded axes. All axes
+	/// are *AxisTopology::Bounded* by default.
+	/// # Parameters
+	/// * **topology** - topology of each axis
+	pub fn set_topology(&mut self, topology: [AxisTopology; N]) {
+		self.topology = topology;
+	}
+
+	/// Same as *set_topology(topology)*, but consumes and returns this array so that it can be
+	/// chained with a constructor
+	/// # Parameters
+	/// * **topology** - topology of each axis
+	/// # Returns
+	/// Returns this array with the new topology
+	pub fn with_topology(mut self, topology: [AxisTopology; N]) -> ZArrayND<T, N, E> {
+		self.set_topology(topology);
+		return self;
+	}
+
+	/// Gets the topology of this array
+	/// # Returns
+	/// Returns the topology of each axis
+	pub fn topology(&self) -> [AxisTopology; N] {
+		return self.topology;
+	}
+
+	/// private function for checking that a coordinate is in bounds
+	fn in_bounds(&self, coord: &[usize; N]) -> bool {
+		return coord.iter().zip(&self.dims).fold(true, |inside, (c, d)| inside & (c < d));
+	}
+
+	/// private function for building the error for an out-of-bounds coordinate
+	fn lookup_error(&self, coord: [isize; N]) -> LookUpError {
+		return LookUpError::new(&coord, &self.dims);
+	}
+
+	/// private function for mapping an unsigned coordinate into the bounds of this array
+	/// according to the topology of each axis, if it is in bounds on every bounded axis
+	fn topo_coord_unsigned(&self, coord: [usize; N]) -> Result<[usize; N], LookUpError> {
+		let mut resolved = coord;
+		for (d, r) in resolved.iter_mut().enumerate() {
+			if *r >= self.dims[d] {
+				*r = match (self.topology[d], self.dims[d]) {
+					(AxisTopology::Bounded, _) | (_, 0) => return Err(self.lookup_error(coord.map(|c| c as isize))),
+					(AxisTopology::Wrap, size) => *r % size,
+					(AxisTopology::Clamp, size) => size - 1,
+				};
+			}
+		}
+		return Ok(resolved);
+	}
+
+	/// Gets a value from the array, or returns a *LookUpError* if the provided coordinate
+	/// is out of bounds. Coordinates past the end of a wrapped or clamped axis are handled by
+	/// the topology of this array (see *set_topology(topology)*). If you are using a default
+	/// value for out-of-bounds coordinates, then you should use the *bounded_get(coord)*
+	/// method instead. If you want access to wrap-around, then use the *wrapped_get(coord)*
+	/// method.
+	/// # Parameters
+	/// * **coord** - coordinate of the value
+	/// # Returns
+	/// Returns a Result type that holds either the returned data value (as a reference) from
+	/// the array, or a *LookUpError* signalling that the coordinate is out of bounds
+	pub fn get(&self, coord: [usize; N]) -> Result<&T, LookUpError> {
+		if self.in_bounds(&coord) {
+			return Ok(self.get_unchecked(coord));
+		}
+		return self.get_outside(coord);
+	}
+
+	/// private function for *get(coord)* with a coordinate that is outside of this array
+	/// (kept out of line so that the in-bounds look-ups stay short)
+	#[cold]
+	#[inline(never)]
+	fn get_outside(&self, coord: [usize; N]) -> Result<&T, LookUpError> {
+		return Ok(self.get_unchecked(self.topo_coord_unsigned(coord)?));
+	}
+
+	/// Gets a mutable reference to a value in the array, or returns a *LookUpError* if the
+	/// provided coordinate is out of bounds (coordinates past the end of a wrapped or clamped
+	/// axis are handled by the topology of this array).
+	/// # Parameters
+	/// * **coord** - coordinate of the value
+	/// # Returns
+	/// Returns a Result type that holds either a mutable reference to the data value in the
+	/// array, or a *LookUpError* signalling that the coordinate is out of bounds
+	pub fn get_mut(&mut self, coord: [usize; N]) -> Result<&mut T, LookUpError> {
+		if self.in_bounds(&coord) {
+			return Ok(self.get_unchecked_mut(coord));
+		}
+		return self.get_mut_outside(coord);
+	}
+
+	/// private function for *get_mut(coord)* with a coordinate that is outside of this array
+	/// (kept out of line so that the in-bounds look-ups stay short)
+	#[cold]
+	#[inline(never)]
+	fn get_mut_outside(&mut self, coord: [usize; N]) -> Result<&mut T, LookUpError> {
+		let coord = self.topo_coord_unsigned(coord)?;
+		return Ok(self.get_unchecked_mut(coord));
+	}
+
+	/// Sets a value in the array, or returns a *LookUpError* if the provided coordinate
+	/// is out of bounds (coordinates past the end of a wrapped or clamped axis are handled by
+	/// the topology of this array). If you want out-of-bound coordinates to result in a no-op, then use
+	/// the *bounded_set(coord, val)* method instead.
+	/// # Parameters
+	/// * **coord** - coordinate of the value
+	/// * **new_val** - value to store in the array at the coordinate
+	/// # Returns
+	/// Returns a Result type that is either empty or a *LookUpError* signalling that the
+	/// coordinate is out of bounds
+	pub fn set(&mut self, coord: [usize; N], new_val: T) -> Result<(), LookUpError> {
+		*self.get_mut(coord)? = new_val;
+		Ok(())
+	}
+
+	/// Gets a value from the array without bounds checking
+	/// # Parameters
+	/// * **coord** - coordinate of the value
+	/// # Returns
+	/// Returns a data value (as a reference) from the array
+	pub fn get_unchecked(&self, coord: [usize; N]) -> &T {
+		return &self.data[self.data_index(&coord)];
+	}
+
+	/// Gets a mutable reference to a value in the array without bounds checking
+	/// # Parameters
+	/// * **coord** - coordinate of the value
+	/// # Returns
+	/// Returns a mutable reference to the data value in the array
+	pub fn get_unchecked_mut(&mut self, coord: [usize; N]) -> &mut T {
+		let index = self.data_index(&coord);
+		self.touch(index / Self::PATCH_SIZE);
+		return &mut self.data[index];
+	}
+
+	/// Sets a value in the array without bounds checking
+	/// # Parameters
+	/// * **coord** - coordinate of the value
+	/// * **new_val** - value to store in the array at the coordinate
+	pub fn set_unchecked(&mut self, coord: [usize; N], new_val: T) {
+		*self.get_unchecked_mut(coord) = new_val;
+	}
+
+	/// private function for wrapping a coordinate into the bounds of this array
+	fn wrap_coord(&self, coord: [isize; N]) -> [usize; N] {
+		let mut wrapped = [0; N];
+		for (d, w) in wrapped.iter_mut().enumerate() {
+			*w = coord[d].rem_euclid(self.dims[d] as isize) as usize;
+		}
+		return wrapped;
+	}
+
+	/// Gets a value from the array, wrapping around each axis when the coordinates are negative
+	/// or outside the size of this array. Good for when you want tiling behavior.
+	/// # Parameters
+	/// * **coord** - coordinate of the value
+	/// # Returns
+	/// Returns a reference to the data stored at the provided coordinate (wrapping all
+	/// dimensions)
+	pub fn wrapped_get(&self, coord: [isize; N]) -> &T {
+		return self.get_unchecked(self.wrap_coord(coord));
+	}
+
+	/// Gets a mutable reference to a value in the array, wrapping around each axis when the
+	/// coordinates are negative or outside the size of this array.
+	/// # Parameters
+	/// * **coord** - coordinate of the value
+	/// # Returns
+	/// Returns a mutable reference to the data stored at the provided coordinate (wrapping
+	/// all dimensions)
+	pub fn wrapped_get_mut(&mut self, coord: [isize; N]) -> &mut T {
+		return self.get_unchecked_mut(self.wrap_coord(coord));
+	}
+
+	/// Sets a value in the array at the provided coordinate, wrapping each axis if the
+	/// coordinate is negative or out of bounds.
+	/// # Parameters
+	/// * **coord** - coordinate of the value
+	/// * **new_val** - value to store in the array at the coordinate, wrapping around all
+	/// dimensions
+	pub fn wrapped_set(&mut self, coord: [isize; N], new_val: T) {
+		self.set_unchecked(self.wrap_coord(coord), new_val);
+	}
+
+	/// private function for converting a signed coordinate to an unsigned coordinate, if it is
+	/// in bounds
+	fn bounded_coord(&self, coord: [isize; N]) -> Option<[usize; N]> {
+		let mut bounded = [0; N];
+		for (d, b) in bounded.iter_mut().enumerate() {
+			if coord[d] < 0 || coord[d] >= self.dims[d] as isize {
+				return None;
+			}
+			*b = coord[d] as usize;
+		}
+		return Some(bounded);
+	}
+
+	/// Gets a value from the array as an Option that is None if the coordinate is out of
+	/// bounds. Coordinates outside of a wrapped or clamped axis are handled by the topology of
+	/// this array (see *set_topology(topology)*), so only the bounded axes can be out of
+	/// bounds.
+	/// # Parameters
+	/// * **coord** - coordinate of the value
+	/// # Returns
+	/// Returns an Option type that holds either the returned data value (as a reference) from
+	/// the array, or *None* signalling that the coordinate is out of bounds (which can be
+	/// combined with .unwrap_or(default_value) to implement an out-of-bounds default)
+	pub fn bounded_get(&self, coord: [isize; N]) -> Option<&T> {
+		return Some(self.get_unchecked(self.resolve_coord(coord, &EdgeMode::Error)?));
+	}
+
+	/// Gets a mutable reference to a value in the array as an Option that is None if the
+	/// coordinate is out of bounds (coordinates outside of a wrapped or clamped axis are
+	/// handled by the topology of this array).
+	/// # Parameters
+	/// * **coord** - coordinate of the value
+	/// # Returns
+	/// Returns an Option type that holds either a mutable reference to the data value in the
+	/// array, or *None* signalling that the coordinate is out of bounds
+	pub fn bounded_get_mut(&mut self, coord: [isize; N]) -> Option<&mut T> {
+		return Some(self.get_unchecked_mut(self.resolve_coord(coord, &EdgeMode::Error)?));
+	}
+
+	/// Sets a value in the array if and only if the provided coordinate is in bounds
+	/// (coordinates outside of a wrapped or clamped axis are handled by the topology of this
+	/// array). Otherwise this method does nothing if the coordiante is out of bounds.
+	/// # Parameters
+	/// * **coord** - coordinate of the value
+	/// * **new_val** - value to store in the array at the coordinate
+	pub fn bounded_set(&mut self, coord: [isize; N], new_val: T) {
+		if let Some(coord) = self.resolve_coord(coord, &EdgeMode::Error) {
+			self.set_unchecked(coord, new_val);
+		}
+	}
+
+	/// private function for mapping a coordinate into the bounds of this array, using the
+	/// topology for the wrapped and clamped axes and the edge mode for the bounded axes
+	fn resolve_coord(&self, coord: [isize; N], edge_mode: &EdgeMode<T>) -> Option<[usize; N]> {
+		// negative coordinates become too big to be in bounds
+		if coord.iter().zip(&self.dims).fold(true, |inside, (c, d)| inside & ((*c as usize) < *d)) {
+			return Some(coord.map(|c| c as usize));
+		}
+		return self.resolve_outside(coord, edge_mode);
+	}
+
+	/// private function for mapping a coordinate that is outside of this array, for
+	/// *resolve_coord(coord, edge_mode)* (kept out of line so that the in-bounds look-ups stay
+	/// short)
+	#[cold]
+	#[inline(never)]
+	fn resolve_outside(&self, coord: [isize; N], edge_mode: &EdgeMode<T>) -> Option<[usize; N]> {
+		let mut resolved = [0; N];
+		for (d, r) in resolved.iter_mut().enumerate() {
+			*r = match self.topology[d] {
+				AxisTopology::Bounded => edge_mode.resolve(coord[d], self.dims[d])?,
+				topology => topology.resolve(coord[d], self.dims[d])?
+			};
+		}
+		return Some(resolved);
+	}
+
+	/// Gets a value from the array, using the provided edge mode to handle coordinates that
+	/// are negative or outside the size of this array (eg *EdgeMode::Clamp* to clamp-to-edge
+	/// or *EdgeMode::Mirror* to mirror the array at its edges). The edge mode only applies to
+	/// the bounded axes, as the wrapped and clamped axes are handled by the topology of this
+	/// array (see *set_topology(topology)*).
+	/// # Parameters
+	/// * **coord** - coordinate of the value
+	/// * **edge_mode** - how to handle out-of-bounds coordinates
+	/// # Returns
+	/// Returns a Result type that holds either the returned data value (as a reference) or a
+	/// *LookUpError* if the coordinate is out of bounds and the edge mode is *EdgeMode::Error*
+	pub fn sample<'a>(&'a self, coord: [isize; N], edge_mode: &'a EdgeMode<T>) -> Result<&'a T, LookUpError> {
+		match (self.resolve_coord(coord, edge_mode), edge_mode) {
+			(Some(resolved), _) => Ok(self.get_unchecked(resolved)),
+			(None, EdgeMode::Default(v)) => Ok(v),
+			(None, _) => Err(self.lookup_error(coord))
+		}
+	}
+
+	/// Gets a mutable reference to a value in the array, using the provided edge mode to
+	/// handle coordinates that are negative or outside the size of this array.
+	/// # Parameters
+	/// * **coord** - coordinate of the value
+	/// * **edge_mode** - how to handle out-of-bounds coordinates
+	/// # Returns
+	/// Returns a Result type that holds either a mutable reference to the data value or a
+	/// *LookUpError* if the coordinate is out of bounds and the edge mode is *EdgeMode::Error*
+	/// or *EdgeMode::Default* (as there is no value in the array to mutate)
+	pub fn sample_mut(&mut self, coord: [isize; N], edge_mode: &EdgeMode<T>) -> Result<&mut T, LookUpError> {
+		match self.resolve_coord(coord, edge_mode) {
+			Some(resolved) => Ok(self.get_unchecked_mut(resolved)),
+			None => Err(self.lookup_error(coord))
+		}
+	}
+
+	/// Sets a value in the array, using the provided edge mode to handle coordinates that are
+	/// negative or outside the size of this array. Writes to out-of-bounds coordinates are
+	/// ignored with *EdgeMode::Default*.
+	/// # Parameters
+	/// * **coord** - coordinate of the value
+	/// * **edge_mode** - how to handle out-of-bounds coordinates
+	/// * **new_val** - value to store in the array
+	/// # Returns
+	/// Returns a Result type that is either empty or a *LookUpError* if the coordinate is out
+	/// of bounds and the edge mode is *EdgeMode::Error*
+	pub fn sample_set(&mut self, coord: [isize; N], edge_mode: &EdgeMode<T>, new_val: T) -> Result<(), LookUpError> {
+		match (self.resolve_coord(coord, edge_mode), edge_mode) {
+			(Some(resolved), _) => {
+				self.set_unchecked(resolved, new_val);
+				Ok(())
+			},
+			(None, EdgeMode::Default(_)) => Ok(()), // no-op
+			(None, _) => Err(self.lookup_error(coord))
+		}
+	}
+
+	/// private function for calling a function on every coordinate of a box in raster order
+	fn for_each_in_box(&mut self, start: [isize; N], end: [isize; N], mut f: impl FnMut(&mut Self, [isize; N])) {
+		if is_empty_box(&start, &end) {
+			return;
+		}
+		let mut coord = start;
+		loop {
+			f(self, coord);
+			if !next_in_box(&mut coord, &start, &end) {
+				return;
+			}
+		}
+	}
+
+	/// Creates an iterator that iterates through the array in Z-order
+	/// # Returns
+	/// A new ZArrayNDIterator instance
+	pub fn iter(&self) -> ZArrayNDIterator<'_, T, N, E> {
+		ZArrayNDIterator{
+			dims: self.dims,
+			pdims: self.pdims,
+			patches: self.data.chunks_exact(Self::PATCH_SIZE).enumerate(),
+			origin: [0; N],
+			contents: Iter::default().enumerate()
+		}
+	}
+
+	/// Creates an iterator that iterates through the array in Z-order, providing mutable
+	/// access to each value
+	/// # Returns
+	/// A new ZArrayNDIteratorMut instance
+	pub fn iter_mut(&mut self) -> ZArrayNDIteratorMut<'_, T, N, E> {
+		self.touch_all();
+		ZArrayNDIteratorMut{
+			dims: self.dims,
+			pdims: self.pdims,
+			patches: self.data.chunks_exact_mut(Self::PATCH_SIZE).enumerate(),
+			origin: [0; N],
+			contents: IterMut::default().enumerate()
+		}
+	}
+
+	/// Creates an iterator that iterates through the array in raster order (aka row-major
+	/// order, the first dimension changes fastest, then the second, and so on). This is slower
+	/// than Z-order iteration with *iter()*, but is useful when the order of the values matters
+	/// (eg for exporting data).
+	/// # Returns
+	/// A new ZArrayNDRasterIterator instance
+	pub fn iter_raster(&self) -> ZArrayNDRasterIterator<'_, T, N, E> {
+		ZArrayNDRasterIterator::new(self, [0; N], self.dims)
+	}
+
+	/// Creates an iterator that iterates through a box-shaped region of the array in raster
+	/// order (the first dimension changes fastest). The region is clipped to the bounds of this
+	/// array.
+	/// # Parameters
+	/// * **start** - the first corner of the region (inclusive)
+	/// * **end** - the second corner of the region (exclusive)
+	/// # Returns
+	/// A new ZArrayNDRasterIterator instance
+	pub fn iter_raster_region(&self, start: [usize; N], end: [usize; N]) -> ZArrayNDRasterIterator<'_, T, N, E> {
+		let end = self.clip(end);
+		ZArrayNDRasterIterator::new(self, start, end)
+	}
+
+	/// private function for clipping the end of a region to the bounds of this array
+	fn clip(&self, end: [usize; N]) -> [usize; N] {
+		let mut clipped = end;
+		for (c, d) in clipped.iter_mut().zip(&self.dims) {
+			*c = (*c).min(*d);
+		}
+		return clipped;
+	}
+
+	/// Creates an iterator that iterates through a box-shaped region of the array in Z-order,
+	/// visiting only the data patches that overlap the region. The region is clipped to the
+	/// bounds of this array (use *bounded_iter_region(start, end)* for a region that crosses
+	/// the edges of wrapped or clamped axes).
+	/// # Parameters
+	/// * **start** - the first corner of the region (inclusive)
+	/// * **end** - the second corner of the region (exclusive)
+	/// # Returns
+	/// A new ZArrayNDRegionIterator instance
+	pub fn iter_region(&self, start: [usize; N], end: [usize; N]) -> ZArrayNDRegionIterator<'_, T, N, E> {
+		let region = PatchRegion::new(start, self.clip(end));
+		ZArrayNDRegionIterator{pdims: self.pdims, patches: self.data.chunks_exact(Self::PATCH_SIZE), region,
+			contents: Iter::default().enumerate()}
+	}
+
+	/// Creates an iterator that iterates through a box-shaped region of the array in Z-order,
+	/// providing mutable access to each value and visiting only the data patches that overlap
+	/// the region. The region is clipped to the bounds of this array.
+	/// # Parameters
+	/// * **start** - the first corner of the region (inclusive)
+	/// * **end** - the second corner of the region (exclusive)
+	/// # Returns
+	/// A new ZArrayNDRegionIteratorMut instance
+	pub fn iter_region_mut(&mut self, start: [usize; N], end: [usize; N]) -> ZArrayNDRegionIteratorMut<'_, T, N, E> {
+		let end = self.clip(end);
+		self.touch_region(start, end);
+		let region = PatchRegion::new(start, end);
+		ZArrayNDRegionIteratorMut{pdims: self.pdims, patches: self.data.chunks_exact_mut(Self::PATCH_SIZE), region,
+			contents: IterMut::default().enumerate()}
+	}
+
+	/// Creates an iterator that iterates through a box-shaped region of signed coordinates in
+	/// raster order, like *bounded_get(coord)*: coordinates outside of a wrapped or clamped
+	/// axis are handled by the topology of this array (see *set_topology(topology)*) and
+	/// coordinates that are out of bounds on a bounded axis are skipped. The coordinates of
+	/// the items are the in-bounds coordinates that were looked up (so a clamped axis may
+	/// visit the same cell more than once).
+	/// # Parameters
+	/// * **start** - the first corner of the region (inclusive)
+	/// * **end** - the second corner of the region (exclusive)
+	/// # Returns
+	/// A new ZArrayNDBoundedRegionIterator instance
+	pub fn bounded_iter_region(&self, start: [isize; N], end: [isize; N]) -> ZArrayNDBoundedRegionIterator<'_, T, N, E> {
+		ZArrayNDBoundedRegionIterator{array: self, start, end, next: start, done: is_empty_box(&start, &end)}
+	}
+
+	/// Gets the data patch that holds every coordinate in the box from *min* to *max*
+	/// (inclusive), if there is one. Used by neighbor iterators to skip bounds checking.
+	pub(crate) fn patch_spanning(&self, min: [isize; N], max: [isize; N]) -> Option<&[T]> {
+		let min = self.bounded_coord(min)?;
+		let max = self.bounded_coord(max)?;
+		if min.iter().zip(&max).any(|(a, b)| a >> PatchShape::<N, E>::BITS != b >> PatchShape::<N, E>::BITS) {
+			return None;
+		}
+		return Some(self.patch(patch_index::<N, E>(&min, &self.pdims)));
+	}
+
+	/// Applies a function to the Z-array to mutate it in-place
+	/// # Parameters
+	/// * **transform_fn** - Function that takes the coordinate and a reference to the old value
+	/// and returns the new value
+	pub fn transform(&mut self, transform_fn: impl Fn([usize; N], &T) -> T) {
+		for item in self.iter_mut() {
+			*item.value = transform_fn(item.coord, item.value);
+		}
+	}
+
+	/// Returns a vector of all valid coordinates in this array in Z-order
+	pub fn coords(&self) -> Vec<[usize; N]> {
+		return self.iter().map(|item| item.coord).collect();
+	}
+}
+
+impl<T, const N: usize, const E: usize> ZArrayND<T, N, E> {
+	/// private function for building the region of a view (with the pointer to the data of this
+	/// array), clipping the region to the bounds of this array
+	fn view_region(&self, start: [usize; N], end: [usize; N]) -> ViewRegion<T, N, E> {
+		let end = self.clip(end);
+		return ViewRegion::new(self.data.as_ptr() as *mut T, self.pdims, start, end);
+	}
+
+	/// private function for building the region of a mutable view (with the pointer to the data
+	/// of this array), clipping the region to the bounds of this array
+	fn view_region_mut(&mut self, start: [usize; N], end: [usize; N]) -> ViewRegion<T, N, E> {
+		let end = self.clip(end);
+		self.touch_region(start, end);
+		return ViewRegion::new(self.data.as_mut_ptr(), self.pdims, start, end);
+	}
+
+	/// Borrows a box-shaped region of this array as a view, which uses local coordinates
+	/// (the start of the region is the origin of the view). The region is clipped to the
+	/// bounds of this array.
+	/// # Parameters
+	/// * **start** - the first corner of the region (inclusive)
+	/// * **end** - the second corner of the region (exclusive)
+	/// # Returns
+	/// A new ZArrayNDView instance
+	pub fn view(&self, start: [usize; N], end: [usize; N]) -> ZArrayNDView<'_, T, N, E> {
+		return ZArrayNDView { region: self.view_region(start, end), marker: PhantomData };
+	}
+
+	/// Mutably borrows a box-shaped region of this array as a view, which uses local
+	/// coordinates (the start of the region is the origin of the view). The region is clipped
+	/// to the bounds of this array.
+	/// # Parameters
+	/// * **start** - the first corner of the region (inclusive)
+	/// * **end** - the second corner of the region (exclusive)
+	/// # Returns
+	/// A new ZArrayNDViewMut instance
+	pub fn view_mut(&mut self, start: [usize; N], end: [usize; N]) -> ZArrayNDViewMut<'_, T, N, E> {
+		return ZArrayNDViewMut { region: self.view_region_mut(start, end), marker: PhantomData };
+	}
+
+	/// Divides this array into two disjoint mutable views along one axis, which can be
+	/// modified at the same time (eg on different threads)
+	/// # Parameters
+	/// * **axis** - the dimension to split
+	/// * **at** - coordinate along the axis where the second view starts
+	/// # Returns
+	/// Returns a tuple of the view of all coordinates before *at* on the axis and the view of
+	/// the rest of this array
+	/// # Panics
+	/// Panics if *axis* is not less than N or *at* is greater than the size of the axis
+	pub fn split_at_mut(&mut self, axis: usize, at: usize) -> (ZArrayNDViewMut<'_, T, N, E>, ZArrayNDViewMut<'_, T, N, E>) {
+		let dims = self.dims;
+		return self.view_mut([0; N], dims).split_at_mut(axis, at);
+	}
+
+	/// Divides this array into disjoint mutable views of the given size (the views at the
+	/// far edges of the array are smaller if the array size is not a multiple of the chunk
+	/// size), which can be modified at the same time (eg on different threads)
+	/// # Parameters
+	/// * **chunk_dims** - size of each view in each dimension
+	/// # Returns
+	/// Returns a list of the views, in raster order of their origins (the first dimension
+	/// changes fastest)
+	/// # Panics
+	/// Panics if any of the chunk dimensions is 0
+	pub fn chunks_mut(&mut self, chunk_dims: [usize; N]) -> Vec<ZArrayNDViewMut<'_, T, N, E>> {
+		assert!(chunk_dims.iter().all(|c| *c > 0), "chunk dimensions must not be zero");
+		let dims = self.dims;
+		let region = self.view_region_mut([0; N], dims);
+		let mut chunks = Vec::new();
+		if is_empty_box(&[0; N], &dims) {
+			return chunks;
+		}
+		let counts: [usize; N] = core::array::from_fn(|d| dims[d].div_ceil(chunk_dims[d]));
+		let mut chunk = [0; N];
+		loop {
+			let start: [usize; N] = core::array::from_fn(|d| chunk[d] * chunk_dims[d]);
+			let end: [usize; N] = core::array::from_fn(|d| (start[d] + chunk_dims[d]).min(dims[d]));
+			chunks.push(ZArrayNDViewMut { region: region.sub_region(start, end), marker: PhantomData });
+			if !next_in_box(&mut chunk, &[0; N], &counts) {
+				return chunks;
+			}
+		}
+	}
+}
+
+impl<T, const N: usize, const E: usize> ZArrayND<T, N, E> {
+	/// Applies a stencil function to every cell of this array and writes the results to the
+	/// output array (which must have the same dimensions), eg for blurring or cellular
+	/// automata. The stencil function is given a *StencilView* of the neighborhood of each
+	/// cell. The array is processed one data patch at a time: the patch and the halo of cells
+	/// around it are looked up once (using the edge mode for the cells that are out of bounds)
+	/// and then the neighborhoods are read directly from that buffer, which is much faster than
+	/// looking up every neighbor of every cell.
+	/// # Parameters
+	/// * **output** - array to write the results to
+	/// * **radius** - size of the neighborhood in each direction
+	/// * **edge_mode** - how to handle neighbors that are outside of this array (they read as
+	/// None with *EdgeMode::Error*)
+	/// * **stencil_fn** - function that takes the view of a cell's neighborhood and returns
+	/// the output value for that cell
+	/// # Panics
+	/// Panics if the output array does not have the same dimensions as this array
+	pub fn stencil<U>(&self, output: &mut ZArrayND<U, N, E>, radius: usize, edge_mode: &EdgeMode<T>,
+		mut stencil_fn: impl FnMut(&StencilView<'_, T, N>) -> U) {
+		let strides = Self::halo_strides(radius);
+		self.for_each_halo(output, radius, edge_mode, |value| value, |halo, center, coord| {
+			stencil_fn(&StencilView{halo, strides, center, radius, coord})
+		});
+	}
+
+	/// Convolves this array with a kernel of weights and writes the results to the output
+	/// array (which must have the same dimensions), eg for a box or Gaussian blur. Each output
+	/// value is the sum of the neighboring values multiplied by their weights. Neighbors that
+	/// are out of bounds with *EdgeMode::Error* are left out of the sum (ie treated as
+	/// `U::default()`). Like *stencil(...)*, this processes the array one data patch at a time.
+	/// # Parameters
+	/// * **output** - array to write the results to
+	/// * **kernel** - weights of the neighborhood in raster order (the first dimension changes
+	/// fastest), which must be 2r+1 values long in each dimension for a radius of r (eg 9
+	/// values for a 3x3 kernel)
+	/// * **edge_mode** - how to handle neighbors that are outside of this array
+	/// # Panics
+	/// Panics if the output array does not have the same dimensions as this array, or if the
+	/// length of the kernel is not (2r+1)^N
+	pub fn convolve<U>(&self, output: &mut ZArrayND<U, N, E>, kernel: &[U], edge_mode: &EdgeMode<T>)
+		where T: Copy + Into<U>, U: Copy + Default + Add<Output=U> + Mul<Output=U> {
+		let radius = (0..).find(|r| (2 * r + 1usize).pow(N as u32) >= kernel.len()).unwrap_or(0);
+		assert_eq!((2 * radius + 1).pow(N as u32), kernel.len(), "convolution kernel must be 2r+1 values long in each dimension");
+		// distance in the halo buffer from the corner of the kernel to each weighted neighbor
+		let strides = Self::halo_strides(radius);
+		let corner: usize = strides.iter().map(|stride| radius * stride).sum();
+		let mut offset = [0; N];
+		let mut deltas = Vec::with_capacity(kernel.len());
+		for _ in kernel {
+			deltas.push(offset.iter().zip(&strides).map(|(o, stride)| o * stride).sum::<usize>());
+			next_in_box(&mut offset, &[0; N], &[2 * radius + 1; N]);
+		}
+		// neighbors that are left out are read as the default value (ie zero)
+		let fetch = |value: Option<&T>| value.map_or(U::default(), |v| (*v).into());
+		self.for_each_halo(output, radius, edge_mode, fetch, |halo, center, _| {
+			let window = &halo[center - corner..];
+			return kernel.iter().zip(&deltas).fold(U::default(), |sum, (weight, delta)| sum + window[*delta] * *weight);
+		});
+	}
+
+	/// private function for getting the distance between neighbors in each dimension of the
+	/// halo buffer of a data patch
+	fn halo_strides(radius: usize) -> [usize; N] {
+		let mut strides = [1; N];
+		for d in 1..N {
+			strides[d] = strides[d - 1] * (E + 2 * radius);
+		}
+		return strides;
+	}
+
+	/// private function that does the work of *stencil(...)*: for each data patch, it fills a
+	/// buffer (in raster order) with the values of the patch and the halo of cells around it
+	/// (converted with the fetch function), then calls the cell function with the buffer, the
+	/// index of each cell in the buffer and the coordinate of the cell, and stores the result
+	/// in the output array
+	fn for_each_halo<'a, U, H: Clone>(&'a self, output: &mut ZArrayND<U, N, E>, radius: usize,
+		edge_mode: &'a EdgeMode<T>, fetch: impl Fn(Option<&'a T>) -> H,
+		mut cell_fn: impl FnMut(&[H], usize, [usize; N]) -> U) {
+		assert_eq!(self.dims, output.dims, "stencil output array must have the same dimensions as the input array");
+		output.touch_all();
+		let strides = Self::halo_strides(radius);
+		let side = E + 2 * radius;
+		let mut halo: Vec<H> = vec![fetch(None); strides[N - 1] * side];
+		// the layout of the buffer is the same for every patch, so the position of each value
+		// of a patch in the buffer and the positions of the halo cells are only worked out once
+		let layout: Vec<([usize; N], usize)> = (0..Self::PATCH_SIZE).map(|index| {
+			let local = patch_coord::<N, E>(&[0; N], index);
+			(local, local.iter().zip(&strides).map(|(l, stride)| (l + radius) * stride).sum())
+		}).collect();
+		let mut ring: Vec<([isize; N], usize)> = Vec::new();
+		let mut local = [0usize; N];
+		loop {
+			if local.iter().any(|l| *l < radius || *l >= radius + E) {
+				let offset = local.map(|l| l as isize - radius as isize);
+				ring.push((offset, local.iter().zip(&strides).map(|(l, stride)| l * stride).sum()));
+			}
+			if !next_in_box(&mut local, &[0; N], &[side; N]) {
+				break;
+			}
+		}
+		let patches = self.data.chunks_exact(Self::PATCH_SIZE).zip(output.data.chunks_exact_mut(Self::PATCH_SIZE));
+		for (pindex, (patch, out_patch)) in patches.enumerate() {
+			let origin = patch_origin::<N, E>(pindex, &self.pdims);
+			// patches on the far edges of the array hold padding that must not be read
+			let inside = origin.iter().zip(&self.dims).all(|(o, d)| o + E <= *d);
+			let to_coord = |local: &[usize; N]| -> [usize; N] {
+				let mut coord = origin;
+				for (c, l) in coord.iter_mut().zip(local) {
+					*c += l;
+				}
+				return coord;
+			};
+			for ((local, index), value) in layout.iter().zip(patch) {
+				let coord = to_coord(local);
+				halo[*index] = if inside || self.in_bounds(&coord) {
+					fetch(Some(value))
+				} else {
+					fetch(self.sample(coord.map(|c| c as isize), edge_mode).ok())
+				};
+			}
+			for (offset, index) in &ring {
+				let mut coord = origin.map(|o| o as isize);
+				for (c, o) in coord.iter_mut().zip(offset) {
+					*c += o;
+				}
+				halo[*index] = fetch(self.sample(coord, edge_mode).ok());
+			}
+			for ((local, index), value) in layout.iter().zip(out_patch) {
+				let coord = to_coord(local);
+				if inside || self.in_bounds(&coord) {
+					*value = cell_fn(&halo, *index, coord);
+				}
+			}
+		}
+	}
+}
+
+/// This struct is used by *stencil(...)* to present the neighborhood of a cell to the stencil
+/// function, with the neighbors addressed by their offset from the cell
+pub struct StencilView<'a, T, const N: usize> {
+	/// values of the patch and its halo in raster order
+	halo: &'a [Option<&'a T>],
+	/// distance between neighbors in each dimension of the halo
+	strides: [usize; N],
+	/// index of the cell in the halo
+	center: usize,
+	radius: usize,
+	coord: [usize; N],
+}
+
+impl<'a, T, const N: usize> StencilView<'a, T, N> {
+	/// Gets the coordinate of the cell
+	pub fn coord(&self) -> [usize; N] {
+		return self.coord;
+	}
+
+	/// Gets the size of the neighborhood in each direction
+	pub fn radius(&self) -> usize {
+		return self.radius;
+	}
+
+	/// Gets the value of the cell
+	pub fn center(&self) -> &'a T {
+		return self.halo[self.center].expect("center of stencil is always in bounds");
+	}
+
+	/// Gets a neighbor of the cell
+	/// # Parameters
+	/// * **offset** - offset of the neighbor from the cell
+	/// # Returns
+	/// Returns the value of the neighbor, or None if the offset is outside the radius of the
+	/// stencil or if the neighbor is out of bounds with *EdgeMode::Error*
+	pub fn get(&self, offset: [isize; N]) -> Option<&'a T> {
+		let mut index = self.center as isize;
+		for (o, stride) in offset.iter().zip(&self.strides) {
+			if o.unsigned_abs() > self.radius {
+				return None;
+			}
+			index += o * *stride as isize;
+		}
+		return self.halo[index as usize];
+	}
+
+	/// Iterates through the neighbors of the cell (all cells within the radius in every
+	/// dimension, not including the cell itself) in raster order, leaving out neighbors that
+	/// are out of bounds with *EdgeMode::Error*
+	/// # Returns
+	/// An iterator of (offset, value) pairs
+	pub fn neighbors(&self) -> impl Iterator<Item=([isize; N], &'a T)> + '_ {
+		let r = self.radius as isize;
+		let mut offset = [-r; N];
+		let mut done = self.radius == 0;
+		return core::iter::from_fn(move || {
+			while !done {
+				let current = offset;
+				done = !next_in_box(&mut offset, &[-r; N], &[r + 1; N]);
+				if current.iter().any(|o| *o != 0) {
+					if let Some(value) = self.get(current) {
+						return Some((current, value));
+					}
+				}
+			}
+			return None;
+		});
+	}
+}
+
+#[cfg(feature = "rayon")]
+impl<T, const N: usize, const E: usize> ZArrayND<T, N, E> {
+	/// private function for iterating over the in-bounds coordinates and values of one data
+	/// patch, used by the parallel iterators
+	fn patch_items<V>(dims: [usize; N], pdims: [usize; N], pindex: usize, contents: impl Iterator<Item=V>)
+		-> impl Iterator<Item=([usize; N], V)> {
+		let origin = patch_origin::<N, E>(pindex, &pdims);
+		return contents.enumerate().filter_map(move |(index, value)| {
+			let coord = patch_coord::<N, E>(&origin, index);
+			coord.iter().zip(&dims).all(|(c, d)| c < d).then_some((coord, value))
+		});
+	}
+
+	/// Creates a parallel iterator (using *rayon*) over the values of the array, which splits
+	/// the work between threads one data patch at a time
+	/// # Returns
+	/// A rayon *ParallelIterator* of *ZArrayNDIteratorItem* instances
+	pub fn par_iter(&self) -> impl ParallelIterator<Item=ZArrayNDIteratorItem<'_, T, N>> where T: Sync {
+		let (dims, pdims) = (self.dims, self.pdims);
+		return self.data.par_chunks_exact(Self::PATCH_SIZE).enumerate().flat_map_iter(move |(pindex, patch)| {
+			Self::patch_items(dims, pdims, pindex, patch.iter())
+				.map(|(coord, value)| ZArrayNDIteratorItem{coord, value})
+		});
+	}
+
+	/// Creates a parallel iterator (using *rayon*) over the values of the array, providing
+	/// mutable access to each value, which splits the work between threads one data patch at
+	/// a time
+	/// # Returns
+	/// A rayon *ParallelIterator* of *ZArrayNDIteratorItemMut* instances
+	pub fn par_iter_mut(&mut self) -> impl ParallelIterator<Item=ZArrayNDIteratorItemMut<'_, T, N>> where T: Send {
+		self.touch_all();
+		let (dims, pdims) = (self.dims, self.pdims);
+		return self.data.par_chunks_exact_mut(Self::PATCH_SIZE).enumerate().flat_map_iter(move |(pindex, patch)| {
+			Self::patch_items(dims, pdims, pindex, patch.iter_mut())
+				.map(|(coord, value)| ZArrayNDIteratorItemMut{coord, value})
+		});
+	}
+
+	/// Applies a function to the Z-array to mutate it in-place, like *transform(transform_fn)*,
+	/// but using all available threads (with *rayon*)
+	/// # Parameters
+	/// * **transform_fn** - Function that takes the coordinate and a reference to the old value
+	/// and returns the new value
+	pub fn par_transform(&mut self, transform_fn: impl Fn([usize; N], &T) -> T + Sync) where T: Send {
+		self.par_iter_mut().for_each(|item| *item.value = transform_fn(item.coord, item.value));
+	}
+
+	/// Returns a vector of all valid coordinates in this array in Z-order, like *coords()*,
+	/// but using all available threads (with *rayon*)
+	pub fn par_coords(&self) -> Vec<[usize; N]> {
+		let (dims, pdims) = (self.dims, self.pdims);
+		return (0..self.patch_count()).into_par_iter().flat_map_iter(move |pindex| {
+			Self::patch_items(dims, pdims, pindex, 0..Self::PATCH_SIZE).map(|(coord, _)| coord)
+		}).collect();
+	}
+}
+
+#[test]
+fn check_patch_count_nd() {
+	assert_eq!(ZArrayND::<u8, 1>::new([1], 0u8).patch_count(), 1);
+	assert_eq!(ZArrayND::<u8, 1>::new([17], 0u8).patch_count(), 3);
+	assert_eq!(ZArrayND::<u8, 2>::new([8, 9], 0u8).patch_count(), 2);
+	assert_eq!(ZArrayND::<u8, 3>::new([9, 9, 9], 0u8).patch_count(), 8);
+	assert_eq!(ZArrayND::<u8, 4>::new([9, 8, 8, 9], 0u8).patch_count(), 4);
+	assert_eq!(ZArrayND::<u8, 2>::new([0, 8], 0u8).patch_count(), 0);
+	assert_eq!(ZArrayND::<u8, 2, 4>::new([8, 9], 0u8).patch_count(), 6);
+	assert_eq!(ZArrayND::<u8, 3, 16>::new([17, 16, 16], 0u8).patch_count(), 2);
+}
+
+#[test]
+fn check_snapshot_sharing_nd() {
+	let shared = |a: &ZArrayNDSnapshot<u8, 2>, b: &ZArrayNDSnapshot<u8, 2>| {
+		a.patches.iter().zip(&b.patches).filter(|(a, b)| Arc::ptr_eq(a, b)).count()
+	};
+	let mut arr = ZArrayND::<u8, 2>::new([20, 20], 0u8);
+	let first = arr.snapshot();
+	assert_eq!(shared(&first, &arr.snapshot_since(&first)), 9);
+	arr.set([1, 1], 5).unwrap();
+	arr.set([2, 1], 6).unwrap();
+	let second = arr.snapshot_since(&first);
+	assert_eq!(shared(&first, &second), 8);
+	// a mutable view only changes the patches that it overlaps
+	arr.view_mut([0, 0], [8, 16]).set([0, 9], 1).unwrap();
+	let third = arr.snapshot_since(&second);
+	assert_eq!(shared(&second, &third), 7);
+	// restoring only copies the patches that changed, which are then changed again
+	arr.restore(&first);
+	assert_eq!(*arr.get([1, 1]).unwrap(), 0);
+	assert_eq!(*arr.get([0, 9]).unwrap(), 0);
+	assert_eq!(shared(&third, &arr.snapshot_since(&third)), 7);
+	// a clone does not share the changes of the original
+	let clone = arr.clone();
+	assert_eq!(shared(&first, &clone.snapshot_since(&first)), 0);
+	assert_eq!(clone.snapshot_since(&first).to_array(), arr);
+}
+
+#[test]
+fn check_zorder_index_nd() {
+	// must match the Z-order of the 2D and 3D look-up tables
+	assert_eq!(zorder_index::<2, 8>(&[0b101, 0b011]), crate::z2d::zorder_4bit_to_8bit(0b101, 0b011) as usize);
+	assert_eq!(zorder_index::<3, 8>(&[0b110, 0b011, 0b101]),
+		crate::z3d::zorder_4bit_to_12bit(0b110, 0b011, 0b101) as usize);
+	assert_eq!(zorder_index::<2, 16>(&[0b1101, 0b1011]), crate::z2d::zorder_4bit_to_8bit(0b1101, 0b1011) as usize);
+	assert_eq!(zorder_index::<2, 4>(&[0b101, 0b011]), crate::z2d::zorder_4bit_to_8bit(0b001, 0b011) as usize);
+	for i in 0..4096 {
+		assert_eq!(zorder_index::<4, 8>(&patch_coord::<4, 8>(&[0; 4], i)), i);
+	}
+	for i in 0..4096 {
+		assert_eq!(zorder_index::<3, 16>(&patch_coord::<3, 16>(&[0; 3], i)), i);
+	}
+	for i in 0..256 {
+		assert_eq!(zorder_index::<4, 4>(&patch_coord::<4, 4>(&[0; 4], i)), i);
+	}
+}
+
+/// This struct is used by `ZArrayNDIterator` to present values to the consumer of the iterator
+#[derive(Debug)]
+pub struct ZArrayNDIteratorItem<'a, T, const N: usize> {
+	/// coordinate of the value
+	pub coord: [usize; N],
+	/// reference to value at this coordinate
+	pub value: &'a T
+}
+
+/// Iterator that iterates through the array in Z-order
+pub struct ZArrayNDIterator<'a, T, const N: usize, const E: usize = DEFAULT_PATCH_EDGE> {
+	dims: [usize; N],
+	pdims: [usize; N],
+	/// remaining patches to iterate over
+	patches: Enumerate<ChunksExact<'a, T>>,
+	/// coordinate of the first value of the current patch
+	origin: [usize; N],
+	/// remaining values in the current patch
+	contents: Enumerate<Iter<'a, T>>
+}
+
+impl<'a, T, const N: usize, const E: usize> Iterator for ZArrayNDIterator<'a, T, N, E> {
+	type Item = ZArrayNDIteratorItem<'a, T, N>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		loop {
+			match self.contents.next() {
+				Some((index, value)) => {
+					let coord = patch_coord::<N, E>(&self.origin, index);
+					if coord.iter().zip(&self.dims).all(|(c, d)| c < d) {
+						return Some(ZArrayNDIteratorItem{coord, value});
+					}
+				},
+				None => {
+					// move on to the next patch (or finish if there are no more patches)
+					let (pindex, patch) = self.patches.next()?;
+					self.origin = patch_origin::<N, E>(pindex, &self.pdims);
+					self.contents = patch.iter().enumerate();
+				}
+			}
+		}
+	}
+}
+
+/// This struct is used by `ZArrayNDIteratorMut` to present mutable values to the consumer of
+/// the iterator
+#[derive(Debug)]
+pub struct ZArrayNDIteratorItemMut<'a, T, const N: usize> {
+	/// coordinate of the value
+	pub coord: [usize; N],
+	/// mutable reference to value at this coordinate
+	pub value: &'a mut T
+}
+
+/// Iterator that iterates through the array in Z-order, providing mutable access to each value
+pub struct ZArrayNDIteratorMut<'a, T, const N: usize, const E: usize = DEFAULT_PATCH_EDGE> {
+	dims: [usize; N],
+	pdims: [usize; N],
+	/// remaining patches to iterate over
+	patches: Enumerate<ChunksExactMut<'a, T>>,
+	/// coordinate of the first value of the current patch
+	origin: [usize; N],
+	/// remaining values in the current patch
+	contents: Enumerate<IterMut<'a, T>>
+}
+
+impl<'a, T, const N: usize, const E: usize> Iterator for ZArrayNDIteratorMut<'a, T, N, E> {
+	type Item = ZArrayNDIteratorItemMut<'a, T, N>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		loop {
+			match self.contents.next() {
+				Some((index, value)) => {
+					let coord = patch_coord::<N, E>(&self.origin, index);
+					if coord.iter().zip(&self.dims).all(|(c, d)| c < d) {
+						return Some(ZArrayNDIteratorItemMut{coord, value});
+					}
+				},
+				None => {
+					// move on to the next patch (or finish if there are no more patches)
+					let (pindex, patch) = self.patches.next()?;
+					self.origin = patch_origin::<N, E>(pindex, &self.pdims);
+					self.contents = patch.iter_mut().enumerate();
+				}
+			}
+		}
+	}
+}
+
+/// This struct is used by `ZArrayNDIntoIterator` to present owned values to the consumer of
+/// the iterator
+#[derive(Debug)]
+pub struct ZArrayNDIntoIteratorItem<T, const N: usize> {
+	/// coordinate of the value
+	pub coord: [usize; N],
+	/// value that was stored at this coordinate
+	pub value: T
+}
+
+/// Iterator that consumes the array, moving each value out in Z-order. Values stored in the
+/// padding outside of the array's dimensions are dropped.
+pub struct ZArrayNDIntoIterator<T, const N: usize, const E: usize = DEFAULT_PATCH_EDGE> {
+	dims: [usize; N],
+	pdims: [usize; N],
+	/// remaining values of the data patches
+	values: Enumerate<alloc::vec::IntoIter<T>>,
+	/// coordinate of the first value of the current patch
+	origin: [usize; N],
+}
+
+impl<T, const N: usize, const E: usize> Iterator for ZArrayNDIntoIterator<T, N, E> {
+	type Item = ZArrayNDIntoIteratorItem<T, N>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		loop {
+			let (i, value) = self.values.next()?;
+			let index = i % PatchShape::<N, E>::SIZE;
+			if index == 0 {
+				// moved on to the next patch
+				self.origin = patch_origin::<N, E>(i / PatchShape::<N, E>::SIZE, &self.pdims);
+			}
+			let coord = patch_coord::<N, E>(&self.origin, index);
+			if coord.iter().zip(&self.dims).all(|(c, d)| c < d) {
+				return Some(ZArrayNDIntoIteratorItem{coord, value});
+			}
+		}
+	}
+}
+
+impl<T, const N: usize, const E: usize> IntoIterator for ZArrayND<T, N, E> {
+	type Item = ZArrayNDIntoIteratorItem<T, N>;
+	type IntoIter = ZArrayNDIntoIterator<T, N, E>;
+
+	/// Consumes the array and iterates through its values in Z-order
+	fn into_iter(self) -> Self::IntoIter {
+		ZArrayNDIntoIterator{
+			dims: self.dims,
+			pdims: self.pdims,
+			values: self.data.into_iter().enumerate(),
+			origin: [0; N],
+		}
+	}
+}
+
+impl<'a, T, const N: usize, const E: usize> IntoIterator for &'a ZArrayND<T, N, E> {
+	type Item = ZArrayNDIteratorItem<'a, T, N>;
+	type IntoIter = ZArrayNDIterator<'a, T, N, E>;
+
+	fn into_iter(self) -> Self::IntoIter {
+		self.iter()
+	}
+}
+
+impl<'a, T, const N: usize, const E: usize> IntoIterator for &'a mut ZArrayND<T, N, E> {
+	type Item = ZArrayNDIteratorItemMut<'a, T, N>;
+	type IntoIter = ZArrayNDIteratorMut<'a, T, N, E>;
+
+	fn into_iter(self) -> Self::IntoIter {
+		self.iter_mut()
+	}
+}
+
+/// Iterator that iterates through a box-shaped area of the array in raster order (the first
+/// dimension changes fastest)
+pub struct ZArrayNDRasterIterator<'a, T, const N: usize, const E: usize = DEFAULT_PATCH_EDGE> {
+	/// array to iterate over
+	array: &'a ZArrayND<T, N, E>,
+	start: [usize; N],
+	end: [usize; N],
+	/// next coordinate to visit
+	next: [usize; N],
+	/// number of coordinates left to visit
+	remaining: usize,
+}
+
+impl<'a, T, const N: usize, const E: usize> ZArrayNDRasterIterator<'a, T, N, E> {
+	/// start inclusive to end exclusive, must be within the bounds of the array
+	fn new(array: &'a ZArrayND<T, N, E>, start: [usize; N], end: [usize; N]) -> ZArrayNDRasterIterator<'a, T, N, E> {
+		ZArrayNDRasterIterator{array, start, end, next: start, remaining: box_volume(&start, &end)}
+	}
+}
+
+impl<'a, T, const N: usize, const E: usize> Iterator for ZArrayNDRasterIterator<'a, T, N, E> {
+	type Item = ZArrayNDIteratorItem<'a, T, N>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		if self.remaining == 0 {
+			return None;
+		}
+		self.remaining -= 1;
+		let coord = self.next;
+		next_in_box(&mut self.next, &self.start, &self.end);
+		Some(ZArrayNDIteratorItem{coord, value: self.array.get_unchecked(coord)})
+	}
+
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		(self.remaining, Some(self.remaining))
+	}
+}
+
+impl<'a, T, const N: usize, const E: usize> ExactSizeIterator for ZArrayNDRasterIterator<'a, T, N, E> {}
+
+/// private struct for walking through the patches that overlap a box-shaped region
+struct PatchRegion<const N: usize, const E: usize> {
+	/// region bounds (inclusive start, exclusive end)
+	start: [usize; N],
+	end: [usize; N],
+	/// patch grid bounds (inclusive start, exclusive end)
+	pstart: [usize; N],
+	pend: [usize; N],
+	/// position in the patch grid of the current patch (None before the first patch)
+	current: Option<[usize; N]>,
+	/// patch index of the patch after the current one
+	next_pindex: usize,
+}
+
+impl<const N: usize, const E: usize> PatchRegion<N, E> {
+	fn new(start: [usize; N], end: [usize; N]) -> PatchRegion<N, E> {
+		if is_empty_box(&start, &end) {
+			// empty region
+			return PatchRegion{start, end, pstart: [0; N], pend: [0; N], current: None, next_pindex: 0};
+		}
+		let pstart = start.map(|s| s >> PatchShape::<N, E>::BITS);
+		let pend = end.map(|e| ((e - 1) >> PatchShape::<N, E>::BITS) + 1);
+		PatchRegion{start, end, pstart, pend, current: None, next_pindex: 0}
+	}
+
+	/// moves on to the next patch in the region
+	/// # Returns
+	/// The number of patches to skip over in the patch slice to reach the next patch in the
+	/// region, or None if there are no more patches
+	fn next_patch(&mut self, pdims: &[usize; N]) -> Option<usize> {
+		let pcoord = match self.current {
+			None if is_empty_box(&self.pstart, &self.pend) => return None,
+			None => self.pstart,
+			Some(mut pcoord) => {
+				if !next_in_box(&mut pcoord, &self.pstart, &self.pend) {
+					return None;
+				}
+				pcoord
+			}
+		};
+		self.current = Some(pcoord);
+		let pindex = patch_index::<N, E>(&pcoord.map(|p| p << PatchShape::<N, E>::BITS), pdims);
+		let skip = pindex - self.next_pindex;
+		self.next_pindex = pindex + 1;
+		return Some(skip);
+	}
+
+	/// converts an index within the current patch to a coordinate
+	/// # Returns
+	/// The coordinate, or None if the coordinate is outside of the region
+	fn coord(&self, index: usize) -> Option<[usize; N]> {
+		let pcoord = self.current?;
+		let coord = patch_coord::<N, E>(&pcoord.map(|p| p << PatchShape::<N, E>::BITS), index);
+		if (0..N).any(|d| coord[d] < self.start[d] || coord[d] >= self.end[d]) {
+			return None;
+		}
+		return Some(coord);
+	}
+}
+
+/// Iterator that iterates through a box-shaped region of the array in Z-order
+pub struct ZArrayNDRegionIterator<'a, T, const N: usize, const E: usize = DEFAULT_PATCH_EDGE> {
+	pdims: [usize; N],
+	/// remaining patches of the array
+	patches: ChunksExact<'a, T>,
+	region: PatchRegion<N, E>,
+	/// remaining values in the current patch
+	contents: Enumerate<Iter<'a, T>>
+}
+
+impl<'a, T, const N: usize, const E: usize> Iterator for ZArrayNDRegionIterator<'a, T, N, E> {
+	type Item = ZArrayNDIteratorItem<'a, T, N>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		loop {
+			match self.contents.next() {
+				Some((index, value)) => {
+					if let Some(coord) = self.region.coord(index) {
+						return Some(ZArrayNDIteratorItem{coord, value});
+					}
+				},
+				None => {
+					// jump to the next patch in the region (or finish if there are no more)
+					let skip = self.region.next_patch(&self.pdims)?;
+					self.contents = self.patches.nth(skip)?.iter().enumerate();
+				}
+			}
+		}
+	}
+}
+
+/// Iterator that iterates through a box-shaped region of the array in Z-order, providing
+/// mutable access to each value
+pub struct ZArrayNDRegionIteratorMut<'a, T, const N: usize, const E: usize = DEFAULT_PATCH_EDGE> {
+	pdims: [usize; N],
+	/// remaining patches of the array
+	patches: ChunksExactMut<'a, T>,
+	region: PatchRegion<N, E>,
+	/// remaining values in the current patch
+	contents: Enumerate<IterMut<'a, T>>
+}
+
+impl<'a, T, const N: usize, const E: usize> Iterator for ZArrayNDRegionIteratorMut<'a, T, N, E> {
+	type Item = ZArrayNDIteratorItemMut<'a, T, N>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		loop {
+			match self.contents.next() {
+				Some((index, value)) => {
+					if let Some(coord) = self.region.coord(index) {
+						return Some(ZArrayNDIteratorItemMut{coord, value});
+					}
+				},
+				None => {
+					// jump to the next patch in the region (or finish if there are no more)
+					let skip = self.region.next_patch(&self.pdims)?;
+					self.contents = self.patches.nth(skip)?.iter_mut().enumerate();
+				}
+			}
+		}
+	}
+}
+
+/// Iterator that iterates through a box-shaped region of signed coordinates in raster order,
+/// mapping out-of-bounds coordinates according to the topology of the array
+pub struct ZArrayNDBoundedRegionIterator<'a, T, const N: usize, const E: usize = DEFAULT_PATCH_EDGE> {
+	/// array to iterate over
+	array: &'a ZArrayND<T, N, E>,
+	start: [isize; N],
+	end: [isize; N],
+	/// next coordinate to visit
+	next: [isize; N],
+	done: bool,
+}
+
+impl<'a, T, const N: usize, const E: usize> Iterator for ZArrayNDBoundedRegionIterator<'a, T, N, E> {
+	type Item = ZArrayNDIteratorItem<'a, T, N>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		while !self.done {
+			let coord = self.next;
+			self.done = !next_in_box(&mut self.next, &self.start, &self.end);
+			if let Some(coord) = self.array.resolve_coord(coord, &EdgeMode::Error) {
+				return Some(ZArrayNDIteratorItem{coord, value: self.array.get_unchecked(coord)});
+			}
+		}
+		return None;
+	}
+}
+
+/// private struct for locating the box-shaped region of a view within its array, which holds
+/// a pointer to the data of the array (shared between views that are split from the same
+/// region)
+struct ViewRegion<T, const N: usize, const E: usize> {
+	/// pointer to the first value of the data of the array
+	data: *mut T,
+	/// number of data patches along each dimension of the array
+	array_pdims: [usize; N],
+	/// coordinate of the first data patch in the patch grid of the array
+	pstart: [usize; N],
+	/// number of data patches along each dimension
+	pdims: [usize; N],
+	/// coordinate of the region relative to the first data patch
+	origin: [usize; N],
+	/// size of the region in each dimension
+	dims: [usize; N],
+}
+
+impl<T, const N: usize, const E: usize> Clone for ViewRegion<T, N, E> {
+	fn clone(&self) -> Self {
+		return ViewRegion { data: self.data, array_pdims: self.array_pdims, pstart: self.pstart,
+			pdims: self.pdims, origin: self.origin, dims: self.dims };
+	}
+}
+
+impl<T, const N: usize, const E: usize> ViewRegion<T, N, E> {
+	/// Creates the region from start (inclusive) to end (exclusive)
+	/// # Parameters
+	/// * **data** - pointer to the first value of the data of the array
+	/// * **array_pdims** - number of data patches along each dimension of the array
+	/// * **start** - the first corner of the region (inclusive)
+	/// * **end** - the second corner of the region (exclusive, must be in bounds)
+	fn new(data: *mut T, array_pdims: [usize; N], start: [usize; N], end: [usize; N]) -> ViewRegion<T, N, E> {
+		let dims: [usize; N] = core::array::from_fn(|d| end[d].saturating_sub(start[d]));
+		if is_empty_box(&start, &end) {
+			return ViewRegion { data, array_pdims, pstart: [0; N], pdims: [0; N], origin: [0; N], dims };
+		}
+		let pstart = start.map(|s| s >> PatchShape::<N, E>::BITS);
+		let pend = end.map(|e| ((e - 1) >> PatchShape::<N, E>::BITS) + 1);
+		let pdims = core::array::from_fn(|d| pend[d] - pstart[d]);
+		let origin = core::array::from_fn(|d| start[d] - (pstart[d] << PatchShape::<N, E>::BITS));
+		return ViewRegion { data, array_pdims, pstart, pdims, origin, dims };
+	}
+
+	/// Gets the pointer to the first value of a data patch that overlaps this region
+	/// # Parameters
+	/// * **coord** - coordinate of any value of the patch, relative to the first data patch of
+	/// this region
+	fn patch_pointer(&self, coord: &[usize; N]) -> *mut T {
+		let coord: [usize; N] = core::array::from_fn(|d| coord[d] + (self.pstart[d] << PatchShape::<N, E>::BITS));
+		let pindex = patch_index::<N, E>(&coord, &self.array_pdims);
+		// SAFETY: the patch overlaps the region, so it is one of the data patches of the array
+		return unsafe { self.data.add(pindex * PatchShape::<N, E>::SIZE) };
+	}
+
+	/// Creates a region within this region (in local coordinates), which shares the pointers
+	/// to the data patches. The sub-region is clipped to the bounds of this region.
+	fn sub_region(&self, start: [usize; N], end: [usize; N]) -> ViewRegion<T, N, E> {
+		let end: [usize; N] = core::array::from_fn(|d| end[d].min(self.dims[d]));
+		let mut region = self.clone();
+		region.dims = core::array::from_fn(|d| end[d].saturating_sub(start[d]));
+		if !is_empty_box(&start, &end) {
+			region.origin = core::array::from_fn(|d| self.origin[d] + start[d]);
+		}
+		return region;
+	}
+
+	/// Gets the coordinate of the first value of this region in its array
+	fn array_origin(&self) -> [usize; N] {
+		return core::array::from_fn(|d| self.origin[d] + (self.pstart[d] << PatchShape::<N, E>::BITS));
+	}
+
+	/// Gets the pointer to a value in this region, or None if the local coordinate is out of
+	/// bounds
+	fn pointer(&self, coord: [usize; N]) -> Option<*mut T> {
+		if coord.iter().zip(&self.dims).any(|(c, d)| c >= d) {
+			return None;
+		}
+		let coord: [usize; N] = core::array::from_fn(|d| self.origin[d] + coord[d]);
+		// SAFETY: the index is inside the patch
+		return Some(unsafe { self.patch_pointer(&coord).add(zorder_index::<N, E>(&coord)) });
+	}
+
+	/// Gets the pointer to a value in this region from a signed local coordinate, or None if
+	/// the coordinate is out of bounds
+	fn bounded_pointer(&self, coord: [isize; N]) -> Option<*mut T> {
+		if coord.iter().any(|c| *c < 0) {
+			return None;
+		}
+		return self.pointer(coord.map(|c| c as usize));
+	}
+
+	/// Gets the pointer to a value in this region, wrapping the local coordinate around the
+	/// bounds of this region
+	fn wrapped_pointer(&self, coord: [isize; N]) -> *mut T {
+		let coord = core::array::from_fn(|d| coord[d].rem_euclid(self.dims[d] as isize) as usize);
+		return self.pointer(coord).expect("wrapped coordinate is in bounds");
+	}
+
+	/// Builds the error for an out-of-bounds local coordinate
+	fn lookup_error(&self, coord: [usize; N]) -> LookUpError {
+		return LookUpError::new(&coord.map(|c| c as isize), &self.dims);
+	}
+
+	/// Splits this region into two regions along an axis
+	fn split(self, axis: usize, at: usize) -> (ViewRegion<T, N, E>, ViewRegion<T, N, E>) {
+		assert!(axis < N, "axis {} is out of range for {} dimensions", axis, N);
+		assert!(at <= self.dims[axis], "split coordinate {} is out of bounds for axis of size {}", at, self.dims[axis]);
+		let mut first_end = self.dims;
+		first_end[axis] = at;
+		let mut second_start = [0; N];
+		second_start[axis] = at;
+		return (self.sub_region([0; N], first_end), self.sub_region(second_start, self.dims));
+	}
+
+	/// Creates an iterator over the coordinates of this region (in Z-order of the data
+	/// patches) and the pointers to their values
+	fn cells(&self) -> ViewCells<T, N, E> {
+		let end: [usize; N] = core::array::from_fn(|d| self.origin[d] + self.dims[d]);
+		return ViewCells { cells: PatchRegion::new(self.origin, end), region: self.clone(), next_pindex: 0,
+			patch: None, index: 0 };
+	}
+}
+
+/// private iterator over the coordinates of a view region and the pointers to their values
+struct ViewCells<T, const N: usize, const E: usize> {
+	/// patches and cells of the region
+	cells: PatchRegion<N, E>,
+	region: ViewRegion<T, N, E>,
+	/// index of the patch after the current one
+	next_pindex: usize,
+	/// pointer to the first value of the current patch
+	patch: Option<*mut T>,
+	/// index of the next value in the current patch
+	index: usize,
+}
+
+impl<T, const N: usize, const E: usize> Iterator for ViewCells<T, N, E> {
+	type Item = ([usize; N], *mut T);
+
+	fn next(&mut self) -> Option<Self::Item> {
+		loop {
+			match self.patch {
+				Some(patch) if self.index < PatchShape::<N, E>::SIZE => {
+					let index = self.index;
+					self.index += 1;
+					if let Some(coord) = self.cells.coord(index) {
+						let local = core::array::from_fn(|d| coord[d] - self.region.origin[d]);
+						// SAFETY: the index is inside the patch
+						return Some((local, unsafe { patch.add(index) }));
+					}
+				},
+				_ => {
+					// jump to the next patch in the region (or finish if there are no more)
+					let pindex = self.next_pindex + self.cells.next_patch(&self.region.pdims)?;
+					self.next_pindex = pindex + 1;
+					let porigin = patch_origin::<N, E>(pindex, &self.region.pdims);
+					self.patch = Some(self.region.patch_pointer(&porigin));
+					self.index = 0;
+				}
+			}
+		}
+	}
+}
+
+/// This struct is an immutable view of a box-shaped region of a *ZArrayND* (see
+/// *ZArrayND.view(start, end)*). The view uses local coordinates, where the first corner of
+/// the region is the origin.
+pub struct ZArrayNDView<'a, T, const N: usize, const E: usize = DEFAULT_PATCH_EDGE> {
+	region: ViewRegion<T, N, E>,
+	marker: PhantomData<&'a T>,
+}
+
+impl<'a, T, const N: usize, const E: usize> Clone for ZArrayNDView<'a, T, N, E> {
+	fn clone(&self) -> Self {
+		return ZArrayNDView { region: self.region.clone(), marker: PhantomData };
+	}
+}
+
+// SAFETY: a view only gives shared access to the values of the array
+unsafe impl<'a, T, const N: usize, const E: usize> Send for ZArrayNDView<'a, T, N, E> where T: Sync {}
+unsafe impl<'a, T, const N: usize, const E: usize> Sync for ZArrayNDView<'a, T, N, E> where T: Sync {}
+
+impl<'a, T, const N: usize, const E: usize> ZArrayNDView<'a, T, N, E> {
+	/// Gets the size of this view in each dimension
+	pub fn dimensions(&self) -> [usize; N] {
+		return self.region.dims;
+	}
+
+	/// Gets the coordinate in the array of the origin of this view
+	pub fn origin(&self) -> [usize; N] {
+		return self.region.array_origin();
+	}
+
+	/// Gets a value from the view, or returns a *LookUpError* if the provided local coordinate
+	/// is out of bounds
+	/// # Parameters
+	/// * **coord** - local coordinate of the value
+	/// # Returns
+	/// Returns a Result type that holds either the returned data value (as a reference) from
+	/// the array, or a *LookUpError* signalling that the coordinate is out of bounds
+	pub fn get(&self, coord: [usize; N]) -> Result<&'a T, LookUpError> {
+		return match self.region.pointer(coord) {
+			// SAFETY: the view borrows the array for 'a
+			Some(value) => Ok(unsafe { &*value }),
+			None => Err(self.region.lookup_error(coord))
+		};
+	}
+
+	/// Gets a value from the view as an Option that is None if the local coordinate is out
+	/// of bounds (of the view, not the array).
+	/// # Parameters
+	/// * **coord** - local coordinate of the value
+	/// # Returns
+	/// Returns an Option type that holds either the returned data value (as a reference) from
+	/// the array, or *None* signalling that the coordinate is out of bounds
+	pub fn bounded_get(&self, coord: [isize; N]) -> Option<&'a T> {
+		// SAFETY: the view borrows the array for 'a
+		return self.region.bounded_pointer(coord).map(|value| unsafe { &*value });
+	}
+
+	/// Gets a value from the view, wrapping around each axis of the view when the local
+	/// coordinates are negative or outside the size of this view.
+	/// # Parameters
+	/// * **coord** - local coordinate of the value
+	/// # Returns
+	/// Returns a reference to the data stored at the provided coordinate (wrapping all
+	/// dimensions)
+	pub fn wrapped_get(&self, coord: [isize; N]) -> &'a T {
+		// SAFETY: the view borrows the array for 'a
+		return unsafe { &*self.region.wrapped_pointer(coord) };
+	}
+
+	/// Creates an iterator that iterates through the view in Z-order (of the data patches of
+	/// the array), with local coordinates
+	/// # Returns
+	/// A new ZArrayNDViewIterator instance
+	pub fn iter(&self) -> ZArrayNDViewIterator<'a, T, N, E> {
+		ZArrayNDViewIterator { cells: self.region.cells(), marker: PhantomData }
+	}
+
+	/// Borrows a box-shaped region of this view as a smaller view. The region is clipped to
+	/// the bounds of this view.
+	/// # Parameters
+	/// * **start** - the first corner of the region (inclusive, in local coordinates)
+	/// * **end** - the second corner of the region (exclusive, in local coordinates)
+	/// # Returns
+	/// A new ZArrayNDView instance
+	pub fn view(&self, start: [usize; N], end: [usize; N]) -> ZArrayNDView<'a, T, N, E> {
+		return ZArrayNDView { region: self.region.sub_region(start, end), marker: PhantomData };
+	}
+}
+
+impl<'a, T, const N: usize, const E: usize> Debug for ZArrayNDView<'a, T, N, E> {
+	fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+		return f.debug_struct("ZArrayNDView").field("origin", &self.origin()).field("dims", &self.region.dims).finish();
+	}
+}
+
+/// This struct is a mutable view of a box-shaped region of a *ZArrayND* (see
+/// *ZArrayND.view_mut(start, end)*). The view uses local coordinates, where the first corner
+/// of the region is the origin. Views that do not overlap (eg from
+/// *ZArrayND.split_at_mut(axis, at)* or *ZArrayND.chunks_mut(chunk_dims)*) can be used at the
+/// same time, including on different threads.
+pub struct ZArrayNDViewMut<'a, T, const N: usize, const E: usize = DEFAULT_PATCH_EDGE> {
+	region: ViewRegion<T, N, E>,
+	marker: PhantomData<&'a mut T>,
+}
+
+// SAFETY: a mutable view has exclusive access to the values in its region
+unsafe impl<'a, T, const N: usize, const E: usize> Send for ZArrayNDViewMut<'a, T, N, E> where T: Send {}
+unsafe impl<'a, T, const N: usize, const E: usize> Sync for ZArrayNDViewMut<'a, T, N, E> where T: Sync {}
+
+impl<'a, T, const N: usize, const E: usize> ZArrayNDViewMut<'a, T, N, E> {
+	/// Gets the size of this view in each dimension
+	pub fn dimensions(&self) -> [usize; N] {
+		return self.region.dims;
+	}
+
+	/// Gets the coordinate in the array of the origin of this view
+	pub fn origin(&self) -> [usize; N] {
+		return self.region.array_origin();
+	}
+
+	/// Borrows this view as an immutable view
+	pub fn as_view(&self) -> ZArrayNDView<'_, T, N, E> {
+		return ZArrayNDView { region: self.region.clone(), marker: PhantomData };
+	}
+
+	/// Gets a value from the view, or returns a *LookUpError* if the provided local coordinate
+	/// is out of bounds
+	/// # Parameters
+	/// * **coord** - local coordinate of the value
+	/// # Returns
+	/// Returns a Result type that holds either the returned data value (as a reference) from
+	/// the array, or a *LookUpError* signalling that the coordinate is out of bounds
+	pub fn get(&self, coord: [usize; N]) -> Result<&T, LookUpError> {
+		return match self.region.pointer(coord) {
+			// SAFETY: the view has exclusive access to its region, which is borrowed by &self
+			Some(value) => Ok(unsafe { &*value }),
+			None => Err(self.region.lookup_error(coord))
+		};
+	}
+
+	/// Gets a mutable reference to a value in the view, or returns a *LookUpError* if the
+	/// provided local coordinate is out of bounds
+	/// # Parameters
+	/// * **coord** - local coordinate of the value
+	/// # Returns
+	/// Returns a Result type that holds either a mutable reference to the data value in the
+	/// array, or a *LookUpError* signalling that the coordinate is out of bounds
+	pub fn get_mut(&mut self, coord: [usize; N]) -> Result<&mut T, LookUpError> {
+		return match self.region.pointer(coord) {
+			// SAFETY: the view has exclusive access to its region, which is borrowed by &mut self
+			Some(value) => Ok(unsafe { &mut *value }),
+			None => Err(self.region.lookup_error(coord))
+		};
+	}
+
+	/// Sets a value in the view, or returns a *LookUpError* if the provided local coordinate
+	/// is out of bounds
+	/// # Parameters
+	/// * **coord** - local coordinate of the value
+	/// * **new_val** - value to store in the array at the coordinate
+	/// # Returns
+	/// Returns a Result type that is either empty or a *LookUpError* signalling that the
+	/// coordinate is out of bounds
+	pub fn set(&mut self, coord: [usize; N], new_val: T) -> Result<(), LookUpError> {
+		*self.get_mut(coord)? = new_val;
+		Ok(())
+	}
+
+	/// Gets a value from the view as an Option that is None if the local coordinate is out
+	/// of bounds (of the view, not the array).
+	/// # Parameters
+	/// * **coord** - local coordinate of the value
+	/// # Returns
+	/// Returns an Option type that holds either the returned data value (as a reference) from
+	/// the array, or *None* signalling that the coordinate is out of bounds
+	pub fn bounded_get(&self, coord: [isize; N]) -> Option<&T> {
+		// SAFETY: the view has exclusive access to its region, which is borrowed by &self
+		return self.region.bounded_pointer(coord).map(|value| unsafe { &*value });
+	}
+
+	/// Gets a mutable reference to a value in the view as an Option that is None if the local
+	/// coordinate is out of bounds (of the view, not the array).
+	/// # Parameters
+	/// * **coord** - local coordinate of the value
+	/// # Returns
+	/// Returns an Option type that holds either a mutable reference to the data value in the
+	/// array, or *None* signalling that the coordinate is out of bounds
+	pub fn bounded_get_mut(&mut self, coord: [isize; N]) -> Option<&mut T> {
+		// SAFETY: the view has exclusive access to its region, which is borrowed by &mut self
+		return self.region.bounded_pointer(coord).map(|value| unsafe { &mut *value });
+	}
+
+	/// Sets a value in the view if and only if the provided local coordinate is in bounds (of
+	/// the view, not the array). Otherwise this method does nothing.
+	/// # Parameters
+	/// * **coord** - local coordinate of the value
+	/// * **new_val** - value to store in the array at the coordinate
+	pub fn bounded_set(&mut self, coord: [isize; N], new_val: T) {
+		if let Some(value) = self.bounded_get_mut(coord) {
+			*value = new_val;
+		}
+	}
+
+	/// Gets a value from the view, wrapping around each axis of the view when the local
+	/// coordinates are negative or outside the size of this view.
+	/// # Parameters
+	/// * **coord** - local coordinate of the value
+	/// # Returns
+	/// Returns a reference to the data stored at the provided coordinate (wrapping all
+	/// dimensions)
+	pub fn wrapped_get(&self, coord: [isize; N]) -> &T {
+		// SAFETY: the view has exclusive access to its region, which is borrowed by &self
+		return unsafe { &*self.region.wrapped_pointer(coord) };
+	}
+
+	/// Gets a mutable reference to a value in the view, wrapping around each axis of the view
+	/// when the local coordinates are negative or outside the size of this view.
+	/// # Parameters
+	/// * **coord** - local coordinate of the value
+	/// # Returns
+	/// Returns a mutable reference to the data stored at the provided coordinate (wrapping
+	/// all dimensions)
+	pub fn wrapped_get_mut(&mut self, coord: [isize; N]) -> &mut T {
+		// SAFETY: the view has exclusive access to its region, which is borrowed by &mut self
+		return unsafe { &mut *self.region.wrapped_pointer(coord) };
+	}
+
+	/// Sets a value in the view at the provided local coordinate, wrapping each axis of the
+	/// view if the coordinate is negative or out of bounds.
+	/// # Parameters
+	/// * **coord** - local coordinate of the value
+	/// * **new_val** - value to store in the array at the coordinate
+	pub fn wrapped_set(&mut self, coord: [isize; N], new_val: T) {
+		*self.wrapped_get_mut(coord) = new_val;
+	}
+
+	/// Creates an iterator that iterates through the view in Z-order (of the data patches of
+	/// the array), with local coordinates
+	/// # Returns
+	/// A new ZArrayNDViewIterator instance
+	pub fn iter(&self) -> ZArrayNDViewIterator<'_, T, N, E> {
+		ZArrayNDViewIterator { cells: self.region.cells(), marker: PhantomData }
+	}
+
+	/// Creates an iterator that iterates through the view in Z-order (of the data patches of
+	/// the array), with local coordinates, providing mutable access to each value
+	/// # Returns
+	/// A new ZArrayNDViewIteratorMut instance
+	pub fn iter_mut(&mut self) -> ZArrayNDViewIteratorMut<'_, T, N, E> {
+		ZArrayNDViewIteratorMut { cells: self.region.cells(), marker: PhantomData }
+	}
+
+	/// Fills a box-shaped region of this view with a given value, or returns a *LookUpError*
+	/// if the provided local coordinates go out of bounds (of the view, not the array).
+	/// # Parameters
+	/// * **start** - the first corner of the region (inclusive)
+	/// * **end** - the second corner of the region (exclusive)
+	/// * **new_val** - value to store in the array in the bounding box defined by
+	/// start -> end
+	/// # Returns
+	/// Returns a Result type that is either empty or a *LookUpError* signalling that a
+	/// coordinate is out of bounds
+	pub fn fill(&mut self, start: [usize; N], end: [usize; N], new_val: impl Borrow<T>) -> Result<(), LookUpError>
+		where T: Clone {
+		if is_empty_box(&start, &end) {
+			return Ok(());
+		}
+		let last = end.map(|e| e - 1);
+		if self.region.pointer(last).is_none() {
+			return Err(self.region.lookup_error(last));
+		}
+		let mut fill = self.view_mut(start, end);
+		for item in fill.iter_mut() {
+			*item.value = new_val.borrow().clone();
+		}
+		return Ok(());
+	}
+
+	/// Mutably borrows a box-shaped region of this view as a smaller view. The region is
+	/// clipped to the bounds of this view.
+	/// # Parameters
+	/// * **start** - the first corner of the region (inclusive, in local coordinates)
+	/// * **end** - the second corner of the region (exclusive, in local coordinates)
+	/// # Returns
+	/// A new ZArrayNDViewMut instance
+	pub fn view_mut(&mut self, start: [usize; N], end: [usize; N]) -> ZArrayNDViewMut<'_, T, N, E> {
+		return ZArrayNDViewMut { region: self.region.sub_region(start, end), marker: PhantomData };
+	}
+
+	/// Divides this view into two disjoint mutable views along one axis
+	/// # Parameters
+	/// * **axis** - the dimension to split
+	/// * **at** - local coordinate along the axis where the second view starts
+	/// # Returns
+	/// Returns a tuple of the view of all coordinates before *at* on the axis and the view of
+	/// the rest of this view
+	/// # Panics
+	/// Panics if *axis* is not less than N or *at* is greater than the size of the axis
+	pub fn split_at_mut(self, axis: usize, at: usize) -> (ZArrayNDViewMut<'a, T, N, E>, ZArrayNDViewMut<'a, T, N, E>) {
+		let (first, second) = self.region.split(axis, at);
+		return (ZArrayNDViewMut { region: first, marker: PhantomData },
+			ZArrayNDViewMut { region: second, marker: PhantomData });
+	}
+}
+
+impl<'a, T, const N: usize, const E: usize> Debug for ZArrayNDViewMut<'a, T, N, E> {
+	fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+		return f.debug_struct("ZArrayNDViewMut").field("origin", &self.origin()).field("dims", &self.region.dims).finish();
+	}
+}
+
+/// Iterator that iterates through a view in Z-order
+pub struct ZArrayNDViewIterator<'a, T, const N: usize, const E: usize = DEFAULT_PATCH_EDGE> {
+	cells: ViewCells<T, N, E>,
+	marker: PhantomData<&'a T>,
+}
+
+impl<'a, T, const N: usize, const E: usize> Iterator for ZArrayNDViewIterator<'a, T, N, E> {
+	type Item = ZArrayNDIteratorItem<'a, T, N>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		let (coord, value) = self.cells.next()?;
+		// SAFETY: the iterator borrows the view for 'a
+		Some(ZArrayNDIteratorItem{coord, value: unsafe { &*value }})
+	}
+}
+
+/// Iterator that iterates through a view in Z-order, providing mutable access to each value
+pub struct ZArrayNDViewIteratorMut<'a, T, const N: usize, const E: usize = DEFAULT_PATCH_EDGE> {
+	cells: ViewCells<T, N, E>,
+	marker: PhantomData<&'a mut T>,
+}
+
+impl<'a, T, const N: usize, const E: usize> Iterator for ZArrayNDViewIteratorMut<'a, T, N, E> {
+	type Item = ZArrayNDIteratorItemMut<'a, T, N>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		let (coord, value) = self.cells.next()?;
+		// SAFETY: the iterator mutably borrows the view for 'a and visits each value once
+		Some(ZArrayNDIteratorItemMut{coord, value: unsafe { &mut *value }})
+	}
+}
@@ -1,4 +1,6 @@
 //! black-box unit tests
+// the reference implementations deliberately use plain index loops over Vecs
+#![allow(clippy::needless_range_loop, clippy::unnecessary_cast, clippy::needless_return, clippy::manual_rem_euclid)]
 use zarray::z2d::ZArray2D;
 use zarray::z3d::ZArray3D;
 use rand::{rngs::StdRng, Rng, SeedableRng};
@@ -180,7 +182,7 @@ fn test_zarray2dmap_performance_neighborsum(){
 				radius, ref_time as i32);
 
 	// sum neighbors values with ZArray
-	let mut map_sums = ZArray2D::new(w, h, 0u16);
+	let mut map_sums = ZArray2D::<_>::new(w, h, 0u16);
 	let t0 = Instant::now();
 	for y in radius..h-radius {
 		for x in radius..w-radius {
@@ -482,7 +484,7 @@ fn test_zarray3dmap_performance_neighborsum(){
 		w, h, d, radius, ref_time as i32);
 
 	// sum neighbors values with ZArray
-	let mut map_sums = ZArray3D::new(w, h, d, 0u32);
+	let mut map_sums = ZArray3D::<_>::new(w, h, d, 0u32);
 	let t0 = Instant::now();
 	for z in radius..d - radius {
 		for y in radius..h - radius {
@@ -520,7 +522,7 @@ fn test_erosion_sim(){
 	let rock_hardness = 8f32;
 	let drip_power = 1.5f32;
 	let iterations = 12;
-	let mut map = ZArray3D::new(width, length, depth, air);
+	let mut map = ZArray3D::<_>::new(width, length, depth, air);
 	map.fill(0,0,5, width,length,depth, soil_hardness).unwrap();
 	map.fill(0,0,15, width,length,depth, rock_hardness).unwrap();
 	for boulder in [(34,88,6), (66,122,9), (11,154,5), (35,93,8), (72,75,12)]{
@@ -554,6 +556,13 @@ fn iter_2d_test() {
 	test_2d_iter(57,101);
 	test_2d_iter(111,51);
 }
+#[test]
+fn iter_mut_2d_test() {
+	test_2d_iter_mut(8,8);
+	test_2d_iter_mut(3,5);
+	test_2d_iter_mut(11,5);
+	test_2d_iter_mut(57,101);
+}
 fn test_2d_iter(w: usize, h: usize) {
 	let a1 = init_with_count_2d(w, h);
 	let a2 = init_with_count_2d(w, h);
@@ -561,6 +570,24 @@ fn test_2d_iter(w: usize, h: usize) {
 		assert_eq!(item.value, a2.get(item.x, item.y).expect("Out of bounds"));
 	}
 }
+fn test_2d_iter_mut(w: usize, h: usize) {
+	let mut a1 = init_with_count_2d(w, h);
+	let a2 = init_with_count_2d(w, h);
+	let mut count = 0;
+	let mut last_coord = None;
+	for item in a1.iter_mut() {
+		assert!(item.x < w && item.y < h);
+		assert_ne!(last_coord, Some((item.x, item.y)));
+		last_coord = Some((item.x, item.y));
+		*item.value *= -1;
+		count += 1;
+	}
+	assert_eq!(count, w * h);
+	for (a, b) in a1.iter().zip(a2.iter()) {
+		assert_eq!((a.x, a.y), (b.x, b.y));
+		assert_eq!(*a.value, -*b.value);
+	}
+}
 fn init_with_count_2d(w: usize, h: usize) -> ZArray2D<i32> {
 	let mut array = ZArray2D::new(w, h, 0i32);
 	let mut i: i32 = 0;
@@ -581,6 +608,13 @@ fn iter_3d_test() {
 	test_3d_iter(57,101,89);
 	test_3d_iter(111,51,101);
 }
+#[test]
+fn iter_mut_3d_test() {
+	test_3d_iter_mut(8,8,8);
+	test_3d_iter_mut(3,5,4);
+	test_3d_iter_mut(11,5,3);
+	test_3d_iter_mut(57,31,19);
+}
 fn test_3d_iter(w: usize, h: usize, l: usize) {
 	let a1 = init_with_count_3d(w, h, l);
 	let a2 = init_with_count_3d(w, h, l);
@@ -588,6 +622,21 @@ fn test_3d_iter(w: usize, h: usize, l: usize) {
 		assert_eq!(item.value, a2.get(item.x, item.y, item.z).expect("Out of bounds"));
 	}
 }
+fn test_3d_iter_mut(w: usize, h: usize, l: usize) {
+	let mut a1 = init_with_count_3d(w, h, l);
+	let a2 = init_with_count_3d(w, h, l);
+	let mut count = 0;
+	for item in a1.iter_mut() {
+		assert!(item.x < w && item.y < h && item.z < l);
+		*item.value *= -1;
+		count += 1;
+	}
+	assert_eq!(count, w * h * l);
+	for (a, b) in a1.iter().zip(a2.iter()) {
+		assert_eq!((a.x, a.y, a.z), (b.x, b.y, b.z));
+		assert_eq!(*a.value, -*b.value);
+	}
+}
 fn init_with_count_3d(w: usize, h: usize, l: usize) -> ZArray3D<i32> {
 	let mut array = ZArray3D::new(w, h, l, 0i32);
 	let mut i: i32 = 0;
@@ -606,7 +655,7 @@ fn init_with_count_3d(w: usize, h: usize, l: usize) -> ZArray3D<i32> {
 fn test_constructor_function_2d(){
 	let xsize = 13;
 	let ysize = 11;
-    let z2d = ZArray2D::new_with_constructor(xsize, ysize,  |(x, y)| {
+    let z2d = ZArray2D::<_>::new_with_constructor(xsize, ysize,  |(x, y)| {
         x as i32 + y as i32 * 1000
     });
 	for item in z2d.iter() {
@@ -621,7 +670,7 @@ fn test_constructor_function_3d(){
 	let xsize = 13;
 	let ysize = 11;
 	let zsize = 9;
-    let z3d = ZArray3D::new_with_constructor(xsize, ysize, zsize,  |(x, y, z)| {
+    let z3d = ZArray3D::<_>::new_with_constructor(xsize, ysize, zsize,  |(x, y, z)| {
         x as i32 + y as i32 * 1000 + z as i32 * 1000000
     });
 	for item in z3d.iter() {
@@ -663,3 +712,1417 @@ fn test_apply_3d(){
 		assert_eq!(*item.value, item.x as i32 + item.y as i32 * 1000 + item.z as i32 * 1000000);
 	}
 }
+
+#[test]
+fn test_index_2d(){
+	let mut map: ZArray2D<Vec<i32>> = ZArray2D::new_with_default(11, 9);
+	map[(3, 7)].push(5);
+	map[[3, 7]].push(6);
+	map[(10, 8)] = vec![1];
+	assert_eq!(map[(3, 7)], vec![5, 6]);
+	assert_eq!(map[[10, 8]], vec![1]);
+	assert_eq!(*map.get(3, 7).unwrap(), vec![5, 6]);
+	let mut counts = ZArray2D::<_>::new(5, 5, 0u32);
+	counts[(4, 0)] += 1;
+	counts[(4, 0)] += 1;
+	assert_eq!(counts[[4, 0]], 2);
+}
+
+#[test]
+fn test_index_3d(){
+	let mut map: ZArray3D<Vec<i32>> = ZArray3D::new_with_default(11, 9, 3);
+	map[(3, 7, 2)].push(5);
+	map[[3, 7, 2]].push(6);
+	map[(10, 8, 0)] = vec![1];
+	assert_eq!(map[(3, 7, 2)], vec![5, 6]);
+	assert_eq!(map[[10, 8, 0]], vec![1]);
+	assert_eq!(*map.get(3, 7, 2).unwrap(), vec![5, 6]);
+}
+
+#[test]
+#[should_panic(expected = "could not access coordinate (5, 1) because it is out of range for size (5, 5)")]
+fn test_index_2d_out_of_bounds(){
+	let mut counts = ZArray2D::<_>::new(5, 5, 0u32);
+	counts[(5, 1)] += 1;
+}
+
+#[test]
+#[should_panic(expected = "could not access coordinate (0, 0, 4) because it is out of range for size (2, 3, 4)")]
+fn test_index_3d_out_of_bounds(){
+	let counts = ZArray3D::<_>::new(2, 3, 4, 0u32);
+	let _ = counts[[0, 0, 4]];
+}
+
+#[test]
+fn test_get_mut_2d(){
+	let mut map: ZArray2D<Vec<u8>> = ZArray2D::new_with_default(10, 10);
+	map.get_mut(9, 9).unwrap().push(1);
+	assert!(map.get_mut(10, 9).is_err());
+	map.get_unchecked_mut(0, 0).push(2);
+	map.bounded_get_mut(1, 2).unwrap().push(3);
+	assert!(map.bounded_get_mut(-1, 2).is_none());
+	assert!(map.bounded_get_mut(1, 10).is_none());
+	map.wrapped_get_mut(-1, -1).push(4);
+	map.wrapped_get_mut(21, 12).push(5);
+	assert_eq!(*map.get(9, 9).unwrap(), vec![1, 4]);
+	assert_eq!(*map.get(0, 0).unwrap(), vec![2]);
+	assert_eq!(*map.get(1, 2).unwrap(), vec![3, 5]);
+}
+
+#[test]
+fn test_get_mut_3d(){
+	let mut map: ZArray3D<Vec<u8>> = ZArray3D::new_with_default(10, 10, 3);
+	map.get_mut(9, 9, 2).unwrap().push(1);
+	assert!(map.get_mut(9, 9, 3).is_err());
+	map.get_unchecked_mut(0, 0, 0).push(2);
+	map.bounded_get_mut(1, 2, 1).unwrap().push(3);
+	assert!(map.bounded_get_mut(1, 2, -1).is_none());
+	assert!(map.bounded_get_mut(10, 2, 0).is_none());
+	map.wrapped_get_mut(-1, -1, -1).push(4);
+	map.wrapped_get_mut(21, 12, 4).push(5);
+	assert_eq!(*map.get(9, 9, 2).unwrap(), vec![1, 4]);
+	assert_eq!(*map.get(0, 0, 0).unwrap(), vec![2]);
+	assert_eq!(*map.get(1, 2, 1).unwrap(), vec![3, 5]);
+}
+
+#[test]
+fn test_into_iter_2d(){
+	let (w, h) = (13, 9);
+	let array = ZArray2D::<_>::new_with_constructor(w, h, |(x, y)| format!("{},{}", x, y));
+	let mut count = 0;
+	for item in &array {
+		assert_eq!(*item.value, format!("{},{}", item.x, item.y));
+		count += 1;
+	}
+	assert_eq!(count, w * h);
+	let mut array2 = array.clone();
+	for item in &mut array2 {
+		item.value.push('!');
+	}
+	let mut count = 0;
+	for item in array2 {
+		assert!(item.x < w && item.y < h);
+		assert_eq!(item.value, format!("{},{}!", item.x, item.y));
+		count += 1;
+	}
+	assert_eq!(count, w * h);
+	// round trip through owned values
+	let copy = ZArray2D::<_>::from_iter_coords(w, h,
+		array.clone().into_iter().map(|item| ((item.x, item.y), item.value))).unwrap();
+	for item in copy.iter() {
+		assert_eq!(item.value, array.get(item.x, item.y).unwrap());
+	}
+}
+
+#[test]
+fn test_from_iter_2d(){
+	let (w, h) = (11, 7);
+	let array = ZArray2D::<_>::from_iter_row_major(w, h, 0..(w*h) as i32).unwrap();
+	for y in 0..h { for x in 0..w {
+		assert_eq!(*array.get(x, y).unwrap(), (x + y * w) as i32);
+	} }
+	assert!(matches!(ZArray2D::<_>::from_iter_row_major(w, h, 0..(w*h - 1) as i32),
+		Err(zarray::BuildError::SizeMismatch{expected: 77, actual: 76})));
+	assert!(matches!(ZArray2D::<_>::from_iter_row_major(w, h, 0..(w*h + 2) as i32),
+		Err(zarray::BuildError::SizeMismatch{expected: 77, actual: 79})));
+	let coords = ZArray2D::<_>::from_iter_coords(2, 2, vec![((1, 1), 'd'), ((0, 0), 'a'), ((1, 0), 'b'), ((0, 1), 'c')]).unwrap();
+	assert_eq!(*coords.get(1, 0).unwrap(), 'b');
+	assert!(matches!(ZArray2D::<_>::from_iter_coords(2, 2, vec![((1, 1), 'd'), ((0, 0), 'a')]),
+		Err(zarray::BuildError::SizeMismatch{expected: 4, actual: 2})));
+	assert!(matches!(ZArray2D::<_>::from_iter_coords(2, 2, vec![((1, 1), 'd'), ((1, 1), 'a')]),
+		Err(zarray::BuildError::DuplicateCoord(_))));
+	assert!(matches!(ZArray2D::<_>::from_iter_coords(2, 2, vec![((2, 1), 'd')]),
+		Err(zarray::BuildError::OutOfBounds(_))));
+}
+
+#[test]
+fn test_into_iter_3d(){
+	let (w, h, d) = (13, 9, 10);
+	let array = ZArray3D::<_>::new_with_constructor(w, h, d, |(x, y, z)| format!("{},{},{}", x, y, z));
+	let mut count = 0;
+	for item in &array {
+		assert_eq!(*item.value, format!("{},{},{}", item.x, item.y, item.z));
+		count += 1;
+	}
+	assert_eq!(count, w * h * d);
+	let mut array2 = array.clone();
+	for item in &mut array2 {
+		item.value.push('!');
+	}
+	let mut count = 0;
+	for item in array2 {
+		assert_eq!(item.value, format!("{},{},{}!", item.x, item.y, item.z));
+		count += 1;
+	}
+	assert_eq!(count, w * h * d);
+	let copy = ZArray3D::<_>::from_iter_coords(w, h, d,
+		array.clone().into_iter().map(|item| ((item.x, item.y, item.z), item.value))).unwrap();
+	for item in copy.iter() {
+		assert_eq!(item.value, array.get(item.x, item.y, item.z).unwrap());
+	}
+}
+
+#[test]
+fn test_from_iter_3d(){
+	let (w, h, d) = (11, 7, 3);
+	let array = ZArray3D::<_>::from_iter_row_major(w, h, d, 0..(w*h*d) as i32).unwrap();
+	for z in 0..d { for y in 0..h { for x in 0..w {
+		assert_eq!(*array.get(x, y, z).unwrap(), (x + y * w + z * w * h) as i32);
+	} } }
+	assert!(ZArray3D::<_>::from_iter_row_major(w, h, d, 0..(w*h*d - 1) as i32).is_err());
+	assert!(ZArray3D::<_>::from_iter_coords(1, 1, 2, vec![((0, 0, 1), 1)]).is_err());
+	assert!(ZArray3D::<_>::from_iter_coords(1, 1, 2, vec![((0, 0, 1), 1), ((0, 0, 0), 0)]).is_ok());
+}
+
+#[test]
+fn test_raster_iter_2d(){
+	for (w, h) in [(8, 8), (3, 5), (11, 5), (57, 101)] {
+		let array = init_with_count_2d(w, h);
+		let iter = array.iter_raster();
+		assert_eq!(iter.len(), w * h);
+		for (i, item) in iter.enumerate() {
+			assert_eq!((item.x, item.y), (i % w, i / w));
+			assert_eq!(*item.value, i as i32);
+		}
+		let row: Vec<i32> = array.row(2).unwrap().map(|item| *item.value).collect();
+		assert_eq!(row, (0..w as i32).map(|x| x + 2 * w as i32).collect::<Vec<i32>>());
+		let column: Vec<i32> = array.column(1).unwrap().map(|item| *item.value).collect();
+		assert_eq!(column, (0..h as i32).map(|y| 1 + y * w as i32).collect::<Vec<i32>>());
+		assert!(array.iter_rows().map(|item| (item.x, item.y)).eq(array.iter_raster().map(|item| (item.x, item.y))));
+		assert!(array.row(h).is_err());
+		assert!(array.column(w).is_err());
+	}
+}
+
+#[test]
+fn test_raster_iter_3d(){
+	for (w, h, d) in [(8, 8, 8), (3, 5, 4), (11, 5, 3), (17, 10, 9)] {
+		let array = init_with_count_3d(w, h, d);
+		let iter = array.iter_raster();
+		assert_eq!(iter.len(), w * h * d);
+		for (i, item) in iter.enumerate() {
+			assert_eq!((item.x, item.y, item.z), (i % w, (i / w) % h, i / (w * h)));
+			assert_eq!(*item.value, i as i32);
+		}
+		let (wi, hi) = (w as i32, h as i32);
+		let row: Vec<i32> = array.row(2, 1).unwrap().map(|item| *item.value).collect();
+		assert_eq!(row, (0..wi).map(|x| x + 2 * wi + wi * hi).collect::<Vec<i32>>());
+		let column: Vec<i32> = array.column(1, 2).unwrap().map(|item| *item.value).collect();
+		assert_eq!(column, (0..hi).map(|y| 1 + y * wi + 2 * wi * hi).collect::<Vec<i32>>());
+		let pillar: Vec<i32> = array.pillar(2, 3).unwrap().map(|item| *item.value).collect();
+		assert_eq!(pillar, (0..d as i32).map(|z| 2 + 3 * wi + z * wi * hi).collect::<Vec<i32>>());
+		let plane = array.plane(d - 1).unwrap();
+		assert_eq!(plane.len(), w * h);
+		for (i, item) in plane.enumerate() {
+			assert_eq!(*item.value, (i + (d - 1) * w * h) as i32);
+		}
+		assert!(array.row(h, 0).is_err());
+		assert!(array.column(0, d).is_err());
+		assert!(array.pillar(w, 0).is_err());
+		assert!(array.plane(d).is_err());
+	}
+}
+
+#[test]
+fn test_region_iter_2d(){
+	let (w, h) = (37, 29);
+	let mut array = init_with_count_2d(w, h);
+	for (xr, yr) in [((3, 10), (5, 6)), ((0, 37), (0, 29)), ((8, 16), (8, 24)), ((30, 50), (20, 40)), ((5, 5), (0, 10))] {
+		let mut visited: Vec<(usize, usize)> = array.iter_region(xr.0..xr.1, yr.0..yr.1)
+			.map(|item| {
+				assert_eq!(*item.value, (item.x + item.y * w) as i32);
+				(item.x, item.y)
+			}).collect();
+		visited.sort_by_key(|&(x, y)| (y, x));
+		let mut expected = Vec::new();
+		for y in yr.0..yr.1.min(h) { for x in xr.0..xr.1.min(w) {
+			expected.push((x, y));
+		} }
+		assert_eq!(visited, expected);
+	}
+	assert_eq!(array.iter_region(.., ..=2).count(), w * 3);
+	for item in array.iter_region_mut(10..20, 3..=4) {
+		*item.value = -1;
+	}
+	for item in array.iter() {
+		let inside = item.x >= 10 && item.x < 20 && item.y >= 3 && item.y <= 4;
+		assert_eq!(*item.value == -1, inside);
+	}
+}
+
+#[test]
+fn test_region_iter_3d(){
+	let (w, h, d) = (21, 18, 17);
+	let mut array = init_with_count_3d(w, h, d);
+	for (xr, yr, zr) in [((3, 10), (5, 6), (2, 12)), ((0, 21), (0, 18), (0, 17)), ((8, 16), (8, 24), (16, 17)), ((20, 50), (0, 40), (9, 9))] {
+		let mut visited: Vec<(usize, usize, usize)> = array.iter_region(xr.0..xr.1, yr.0..yr.1, zr.0..zr.1)
+			.map(|item| {
+				assert_eq!(*item.value, (item.x + item.y * w + item.z * w * h) as i32);
+				(item.x, item.y, item.z)
+			}).collect();
+		visited.sort_by_key(|&(x, y, z)| (z, y, x));
+		let mut expected = Vec::new();
+		for z in zr.0..zr.1.min(d) { for y in yr.0..yr.1.min(h) { for x in xr.0..xr.1.min(w) {
+			expected.push((x, y, z));
+		} } }
+		assert_eq!(visited, expected);
+	}
+	for item in array.iter_region_mut(10..20, 3..=4, 8..) {
+		*item.value = -1;
+	}
+	for item in array.iter() {
+		let inside = item.x >= 10 && item.x < 20 && item.y >= 3 && item.y <= 4 && item.z >= 8;
+		assert_eq!(*item.value == -1, inside);
+	}
+}
+
+#[test]
+fn test_neighbors_2d(){
+	use zarray::EdgeMode;
+	use zarray::z2d::Neighborhood;
+	let (w, h) = (20, 17);
+	let array = init_with_count_2d(w, h);
+	// neighborhoods inside one patch, across patches, and at the edges
+	for (cx, cy) in [(3usize, 3usize), (7, 8), (0, 0), (19, 16), (10, 0)] {
+		for radius in 1..4 {
+			for edge_mode in [EdgeMode::Error, EdgeMode::Wrap] {
+				let mut expected_moore = Vec::new();
+				let mut expected_vn = Vec::new();
+				for dy in -(radius as isize)..=radius as isize { for dx in -(radius as isize)..=radius as isize {
+					if (dx, dy) == (0, 0) { continue; }
+					let (x, y) = (cx as isize + dx, cy as isize + dy);
+					let v = match edge_mode {
+						EdgeMode::Error => array.bounded_get(x, y).copied(),
+						_ => Some(*array.wrapped_get(x, y)),
+					};
+					if let Some(v) = v {
+						expected_moore.push((x, y, v));
+						if dx.abs() + dy.abs() <= radius as isize {
+							expected_vn.push((x, y, v));
+						}
+					}
+				} }
+				let moore: Vec<(isize, isize, i32)> = array.neighbors(cx, cy, Neighborhood::Moore, radius, &edge_mode)
+					.map(|n| (n.x, n.y, *n.value)).collect();
+				assert_eq!(moore, expected_moore);
+				let vn: Vec<(isize, isize, i32)> = array.neighbors(cx, cy, Neighborhood::VonNeumann, radius, &edge_mode)
+					.map(|n| (n.x, n.y, *n.value)).collect();
+				assert_eq!(vn, expected_vn);
+			}
+		}
+	}
+	let knight_moves = [(1, 2), (2, 1), (-1, 2), (-2, 1), (1, -2), (2, -1), (-1, -2), (-2, -1)];
+	assert_eq!(array.neighbors(1, 1, Neighborhood::Custom(&knight_moves), 0, &EdgeMode::Error).count(), 4);
+	assert_eq!(array.neighbors(1, 1, Neighborhood::Custom(&knight_moves), 0, &EdgeMode::Wrap).count(), 8);
+	assert_eq!(array.neighbors(0, 0, Neighborhood::Moore, 1, &EdgeMode::Error).count(), 3);
+}
+
+#[test]
+fn test_neighbors_3d(){
+	use zarray::EdgeMode;
+	use zarray::z3d::Neighborhood;
+	let (w, h, d) = (12, 9, 10);
+	let array = init_with_count_3d(w, h, d);
+	assert_eq!(array.neighbors(3, 3, 3, Neighborhood::Moore, 1, &EdgeMode::Error).count(), 26);
+	assert_eq!(array.neighbors(3, 3, 3, Neighborhood::FacesAndEdges, 1, &EdgeMode::Error).count(), 18);
+	assert_eq!(array.neighbors(3, 3, 3, Neighborhood::VonNeumann, 1, &EdgeMode::Error).count(), 6);
+	assert_eq!(array.neighbors(0, 0, 0, Neighborhood::Moore, 1, &EdgeMode::Error).count(), 7);
+	assert_eq!(array.neighbors(0, 0, 0, Neighborhood::VonNeumann, 1, &EdgeMode::Wrap).count(), 6);
+	for (cx, cy, cz) in [(3usize, 3usize, 3usize), (7, 8, 0), (11, 8, 9)] {
+		for edge_mode in [EdgeMode::Error, EdgeMode::Wrap] {
+			let mut expected = Vec::new();
+			for dz in -2isize..=2 { for dy in -2isize..=2 { for dx in -2isize..=2 {
+				if (dx, dy, dz) == (0, 0, 0) { continue; }
+				let (x, y, z) = (cx as isize + dx, cy as isize + dy, cz as isize + dz);
+				let v = match edge_mode {
+					EdgeMode::Error => array.bounded_get(x, y, z).copied(),
+					_ => Some(*array.wrapped_get(x, y, z)),
+				};
+				if let Some(v) = v {
+					expected.push((x, y, z, v));
+				}
+			} } }
+			let moore: Vec<(isize, isize, isize, i32)> = array.neighbors(cx, cy, cz, Neighborhood::Moore, 2, &edge_mode)
+				.map(|n| (n.x, n.y, n.z, *n.value)).collect();
+			assert_eq!(moore, expected);
+		}
+	}
+	let offsets = [(0, 0, 1), (0, 0, -1), (5, 0, 0)];
+	let custom: Vec<i32> = array.neighbors(1, 1, 1, Neighborhood::Custom(&offsets), 0, &EdgeMode::Error)
+		.map(|n| *n.value).collect();
+	assert_eq!(custom, vec![*array.get(1, 1, 2).unwrap(), *array.get(1, 1, 0).unwrap(), *array.get(6, 1, 1).unwrap()]);
+}
+
+#[test]
+fn test_edge_mode_resolve(){
+	use zarray::EdgeMode;
+	let size = 4;
+	let coords: Vec<isize> = (-6..10).collect();
+	let resolve = |mode: EdgeMode<u8>| -> Vec<Option<usize>> {
+		coords.iter().map(|&c| mode.resolve(c, size)).collect()
+	};
+	let inside = |v: Vec<usize>| -> Vec<Option<usize>> { v.into_iter().map(Some).collect() };
+	assert_eq!(resolve(EdgeMode::Wrap), inside(vec![2, 3, 0, 1, 2, 3, 0, 1, 2, 3, 0, 1, 2, 3, 0, 1]));
+	assert_eq!(resolve(EdgeMode::Clamp), inside(vec![0, 0, 0, 0, 0, 0, 0, 1, 2, 3, 3, 3, 3, 3, 3, 3]));
+	assert_eq!(resolve(EdgeMode::Mirror), inside(vec![2, 3, 3, 2, 1, 0, 0, 1, 2, 3, 3, 2, 1, 0, 0, 1]));
+	assert_eq!(resolve(EdgeMode::Reflect), inside(vec![0, 1, 2, 3, 2, 1, 0, 1, 2, 3, 2, 1, 0, 1, 2, 3]));
+	let mut bounded = vec![None; 6];
+	bounded.extend(inside(vec![0, 1, 2, 3]));
+	bounded.extend(vec![None; 6]);
+	assert_eq!(resolve(EdgeMode::Error), bounded);
+	assert_eq!(resolve(EdgeMode::Default(7)), bounded);
+	assert_eq!(EdgeMode::<u8>::Reflect.resolve(-3, 1), Some(0));
+	assert_eq!(EdgeMode::<u8>::Wrap.resolve(-3, 0), None);
+}
+
+#[test]
+fn test_sample_2d(){
+	use zarray::EdgeMode;
+	use zarray::z2d::Neighborhood;
+	let (w, h) = (5, 4);
+	let mut array = init_with_count_2d(w, h);
+	assert!(array.sample(-1, 0, &EdgeMode::Error).is_err());
+	assert_eq!(*array.sample(2, 1, &EdgeMode::Error).unwrap(), 7);
+	assert_eq!(*array.sample(-1, 0, &EdgeMode::Default(-5)).unwrap(), -5);
+	assert_eq!(*array.sample(-1, 0, &EdgeMode::Wrap).unwrap(), 4);
+	assert_eq!(*array.sample(-3, 7, &EdgeMode::Clamp).unwrap(), 15);
+	assert_eq!(*array.sample(-1, -1, &EdgeMode::Mirror).unwrap(), 0);
+	assert_eq!(*array.sample(-1, -1, &EdgeMode::Reflect).unwrap(), 6);
+	*array.sample_mut(7, 0, &EdgeMode::Clamp).unwrap() = 100;
+	assert_eq!(*array.get(4, 0).unwrap(), 100);
+	assert!(array.sample_mut(7, 0, &EdgeMode::Default(0)).is_err());
+	array.sample_set(-1, 0, &EdgeMode::Wrap, 200).unwrap();
+	assert_eq!(*array.get(4, 0).unwrap(), 200);
+	assert!(array.sample_set(-1, 0, &EdgeMode::Default(0), 300).is_ok());
+	assert!(array.sample_set(-1, 0, &EdgeMode::Error, 300).is_err());
+	assert_eq!(*array.get(4, 0).unwrap(), 200);
+	array.sample_fill(-2, -2, 1, 1, &EdgeMode::Mirror, 99).unwrap();
+	assert_eq!(array.iter().filter(|item| *item.value == 99).count(), 4);
+	assert!(array.sample_fill(-2, -2, 1, 1, &EdgeMode::Error, 99).is_err());
+	// neighbors of a corner with clamped edges and a default value
+	let corner: Vec<i32> = array.neighbors(0, 0, Neighborhood::VonNeumann, 1, &EdgeMode::Clamp)
+		.map(|n| *n.value).collect();
+	assert_eq!(corner, vec![99, 99, 99, 99]);
+	let corner: Vec<i32> = array.neighbors(0, 0, Neighborhood::VonNeumann, 1, &EdgeMode::Default(-1))
+		.map(|n| *n.value).collect();
+	assert_eq!(corner, vec![-1, -1, 99, 99]);
+}
+
+#[test]
+fn test_sample_3d(){
+	use zarray::EdgeMode;
+	use zarray::z3d::Neighborhood;
+	let (w, h, d) = (5, 4, 3);
+	let mut array = init_with_count_3d(w, h, d);
+	assert!(array.sample(0, 0, 3, &EdgeMode::Error).is_err());
+	assert_eq!(*array.sample(0, 0, 3, &EdgeMode::Default(-5)).unwrap(), -5);
+	assert_eq!(*array.sample(0, 0, 3, &EdgeMode::Wrap).unwrap(), 0);
+	assert_eq!(*array.sample(0, 0, 3, &EdgeMode::Clamp).unwrap(), 40);
+	assert_eq!(*array.sample(0, 0, 3, &EdgeMode::Mirror).unwrap(), 40);
+	assert_eq!(*array.sample(0, 0, 3, &EdgeMode::Reflect).unwrap(), 20);
+	*array.sample_mut(-1, -1, -1, &EdgeMode::Wrap).unwrap() = 100;
+	assert_eq!(*array.get(4, 3, 2).unwrap(), 100);
+	assert!(array.sample_set(5, 0, 0, &EdgeMode::Error, 1).is_err());
+	array.sample_fill(-1, -1, -1, 1, 1, 1, &EdgeMode::Clamp, 77).unwrap();
+	assert_eq!(*array.get(0, 0, 0).unwrap(), 77);
+	assert_eq!(array.iter().filter(|item| *item.value == 77).count(), 1);
+	assert_eq!(array.neighbors(0, 0, 0, Neighborhood::Moore, 1, &EdgeMode::Mirror).count(), 26);
+	assert_eq!(array.neighbors(0, 0, 0, Neighborhood::Moore, 1, &EdgeMode::Default(0))
+		.filter(|n| *n.value == 0).count(), 19);
+}
+
+#[test]
+fn test_topology_2d(){
+	use zarray::{AxisTopology, EdgeMode};
+	use zarray::z2d::Neighborhood;
+	let (w, h) = (5, 4);
+	// cylinder: wraps around the X axis but not the Y axis
+	let mut array = init_with_count_2d(w, h).with_topology(AxisTopology::Wrap, AxisTopology::Bounded);
+	assert_eq!(array.topology(), (AxisTopology::Wrap, AxisTopology::Bounded));
+	assert_ne!(array, init_with_count_2d(w, h));
+	assert_eq!(*array.bounded_get(-1, 0).unwrap(), 4);
+	assert_eq!(*array.get(7, 1).unwrap(), 7);
+	assert_eq!(*array.bounded_get(7, 1).unwrap(), 7);
+	assert!(array.bounded_get(0, -1).is_none());
+	assert!(array.get(0, 4).is_err());
+	array.bounded_set(-2, 2, 100);
+	assert_eq!(*array.get(3, 2).unwrap(), 100);
+	array.bounded_set(0, 9, 100);
+	*array.bounded_get_mut(5, 3).unwrap() = 200;
+	assert_eq!(*array.get(0, 3).unwrap(), 200);
+	array.set(6, 3, 300).unwrap();
+	assert_eq!(*array.get(1, 3).unwrap(), 300);
+	let region: Vec<(usize, usize, i32)> = array.bounded_iter_region(-1..1, -1..1)
+		.map(|item| (item.x, item.y, *item.value)).collect();
+	assert_eq!(region, vec![(4, 0, 4), (0, 0, 0)]);
+	assert_eq!(array.sample(-1, 0, &EdgeMode::Error).ok(), Some(&4));
+	assert!(array.sample(0, -1, &EdgeMode::Error).is_err());
+	assert_eq!(array.sample(-1, -1, &EdgeMode::Clamp).ok(), Some(&4));
+	assert_eq!(array.neighbors(0, 0, Neighborhood::Moore, 1, &EdgeMode::Error).count(), 5);
+	array.bounded_fill(-1, -1, 1, 1, 99);
+	assert_eq!(array.iter().filter(|item| *item.value == 99).count(), 2);
+	// torus with a clamped Y axis instead
+	array.set_topology(AxisTopology::Wrap, AxisTopology::Clamp);
+	assert_eq!(*array.bounded_get(-1, -3).unwrap(), 99);
+	assert_eq!(*array.get(4, 8).unwrap(), *array.get(4, 3).unwrap());
+	assert_eq!(array.bounded_iter_region(0..2, -2..0).count(), 4);
+	assert_eq!(array.neighbors(2, 0, Neighborhood::Moore, 1, &EdgeMode::Error).count(), 8);
+}
+
+#[test]
+fn test_topology_3d(){
+	use zarray::{AxisTopology, EdgeMode};
+	let (w, h, d) = (5, 4, 3);
+	// toroidal world with a floor and ceiling
+	let mut array = init_with_count_3d(w, h, d)
+		.with_topology(AxisTopology::Wrap, AxisTopology::Wrap, AxisTopology::Bounded);
+	assert_eq!(array.topology(), (AxisTopology::Wrap, AxisTopology::Wrap, AxisTopology::Bounded));
+	assert_ne!(array, init_with_count_3d(w, h, d));
+	assert_eq!(*array.bounded_get(-1, -1, 0).unwrap(), 19);
+	assert!(array.bounded_get(0, 0, 3).is_none());
+	assert!(array.get(0, 0, 3).is_err());
+	array.bounded_set(5, 4, 2, 100);
+	assert_eq!(*array.get(0, 0, 2).unwrap(), 100);
+	*array.bounded_get_mut(-5, -4, 1).unwrap() = 200;
+	assert_eq!(*array.get(0, 0, 1).unwrap(), 200);
+	assert_eq!(*array.get(10, 8, 1).unwrap(), 200);
+	assert_eq!(array.bounded_iter_region(-1..1, -1..1, -1..1).count(), 4);
+	assert_eq!(array.bounded_iter_region(.., .., ..).count(), w * h * d);
+	assert!(array.sample(0, 0, -1, &EdgeMode::Error).is_err());
+	assert_eq!(array.sample(-1, -1, 0, &EdgeMode::Error).ok(), Some(&19));
+	array.bounded_fill(-1, -1, -1, 1, 1, 1, 99);
+	assert_eq!(array.iter().filter(|item| *item.value == 99).count(), 4);
+	assert_eq!(*array.get(4, 3, 0).unwrap(), 99);
+}
+
+#[test]
+fn test_zarraynd_1d_and_4d(){
+	use zarray::znd::ZArrayND;
+	// 1D
+	let mut line: ZArrayND<i32, 1> = ZArrayND::new_with_constructor([13], |[x]| x as i32);
+	assert_eq!(*line.get([12]).unwrap(), 12);
+	assert!(line.get([13]).is_err());
+	assert_eq!(*line.wrapped_get([-1]), 12);
+	line.fill([2], [5], 0).unwrap();
+	assert_eq!(line.iter_raster().map(|item| *item.value).collect::<Vec<i32>>(),
+		vec![0, 1, 0, 0, 0, 5, 6, 7, 8, 9, 10, 11, 12]);
+	// 4D (3D-plus-time)
+	let dims = [5, 9, 3, 10];
+	let mut array: ZArrayND<u32, 4> = ZArrayND::new(dims, 0);
+	assert_eq!(array.dimensions(), dims);
+	array.transform(|[x, y, z, t], _| (x + 10 * y + 100 * z + 1000 * t) as u32);
+	assert_eq!(array.iter().count(), 5 * 9 * 3 * 10);
+	assert_eq!(*array.get([4, 8, 2, 9]).unwrap(), 9284);
+	assert_eq!(array[[1, 2, 0, 3]], 3021);
+	assert!(array.bounded_get([0, 0, 0, 10]).is_none());
+	array.set([4, 0, 1, 7], 1).unwrap();
+	assert_eq!(*array.get([4, 0, 1, 7]).unwrap(), 1);
+	for item in array.iter() {
+		assert_eq!(*array.get(item.coord).unwrap(), *item.value);
+	}
+	let region: Vec<[usize; 4]> = array.iter_region([3, 7, 1, 8], [5, 10, 2, 9])
+		.map(|item| item.coord).collect();
+	assert_eq!(region.len(), 4);
+	let mut raster = array.iter_raster_region([0, 0, 0, 0], [2, 1, 1, 2]);
+	assert_eq!(raster.len(), 4);
+	assert_eq!(raster.next().map(|item| item.coord), Some([0, 0, 0, 0]));
+	assert_eq!(raster.next().map(|item| item.coord), Some([1, 0, 0, 0]));
+	assert_eq!(raster.next().map(|item| item.coord), Some([0, 0, 0, 1]));
+}
+
+#[test]
+fn test_zarraynd_front_ends(){
+	use zarray::znd::ZArrayND;
+	let array = init_with_count_2d(11, 6);
+	let nd: ZArrayND<i32, 2> = array.clone().into();
+	assert_eq!(nd.dimensions(), [11, 6]);
+	assert_eq!(*nd.get([3, 4]).unwrap(), *array.get(3, 4).unwrap());
+	let back: ZArray2D<i32> = nd.into();
+	assert_eq!(back, array);
+	let array = init_with_count_3d(3, 9, 4);
+	let nd: ZArrayND<i32, 3> = array.clone().into();
+	assert_eq!(*nd.get([2, 8, 3]).unwrap(), *array.get(2, 8, 3).unwrap());
+	assert_eq!(ZArray3D::from(nd), array);
+}
+
+#[test]
+fn test_patch_edge_sizes(){
+	use zarray::znd::ZArrayND;
+	let (w, h, d) = (21, 9, 17);
+	let reference = init_with_count_3d(w, h, d);
+	let small: ZArray3D<i32, 4> = ZArrayND::new_with_constructor([w, h, d], |[x, y, z]| (x + w * (y + h * z)) as i32).into();
+	let large = ZArray3D::<i32, 16>::new_with_constructor(w, h, d, |(x, y, z)| (x + w * (y + h * z)) as i32);
+	assert_eq!(small.iter().count(), w * h * d);
+	assert_eq!(large.iter().count(), w * h * d);
+	for item in reference.iter() {
+		assert_eq!(small.get(item.x, item.y, item.z).unwrap(), item.value);
+		assert_eq!(large.get(item.x, item.y, item.z).unwrap(), item.value);
+	}
+	assert_eq!(small.iter_region(3..11, 2..6, 5..9).count(), 8 * 4 * 4);
+	assert_eq!(large.iter_region(3..11, 2..6, 5..9).map(|item| *item.value as i64).sum::<i64>(),
+		reference.iter_region(3..11, 2..6, 5..9).map(|item| *item.value as i64).sum::<i64>());
+	let mut grid = ZArray2D::<u64, 4>::new(10, 6, 0u64);
+	grid.fill(3, 1, 9, 5, 7).unwrap();
+	assert_eq!(grid.iter().filter(|item| *item.value == 7).count(), 6 * 4);
+	assert_eq!(*grid.wrapped_get(-1, -1), 0);
+	assert_eq!(*grid.wrapped_get(-2, -2), 7);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde_round_trip(){
+	use core::num::NonZeroU8;
+	use zarray::AxisTopology;
+	use zarray::znd::ZArrayND;
+	let array = ZArray2D::<_>::new_with_constructor(3, 2, |(x, y)| (x + 3 * y) as u8);
+	let json = serde_json::to_string(&array).unwrap();
+	assert_eq!(json, r#"{"dims":[3,2],"data":[0,1,2,3,4,5],"topology":["Bounded","Bounded"]}"#);
+	let copy: ZArray2D<u8> = serde_json::from_str(&json).unwrap();
+	assert_eq!(copy.dimensions(), array.dimensions());
+	assert_eq!(copy.iter_raster().map(|item| *item.value).collect::<Vec<u8>>(), vec![0, 1, 2, 3, 4, 5]);
+	let array = init_with_count_3d(11, 3, 9);
+	let copy: ZArray3D<i32> = serde_json::from_str(&serde_json::to_string(&array).unwrap()).unwrap();
+	for item in array.iter() {
+		assert_eq!(copy.get(item.x, item.y, item.z).unwrap(), item.value);
+	}
+	// the wire format does not depend on the patch size
+	let small: ZArray3D<i32, 4> = serde_json::from_str(&serde_json::to_string(&array).unwrap()).unwrap();
+	assert_eq!(small.iter_raster().map(|item| *item.value).collect::<Vec<i32>>(),
+		array.iter_raster().map(|item| *item.value).collect::<Vec<i32>>());
+	let nd: ZArrayND<i32, 3> = serde_json::from_str(&serde_json::to_string(&array).unwrap()).unwrap();
+	assert_eq!(nd.dimensions(), [11, 3, 9]);
+	// the topology is part of the wire format
+	let array = array.with_topology(AxisTopology::Wrap, AxisTopology::Clamp, AxisTopology::Bounded);
+	let copy: ZArray3D<i32> = serde_json::from_str(&serde_json::to_string(&array).unwrap()).unwrap();
+	assert_eq!(copy.topology(), (AxisTopology::Wrap, AxisTopology::Clamp, AxisTopology::Bounded));
+	assert_eq!(copy, array);
+	// values do not need a default value
+	let array = ZArray2D::new_with_constructor(3, 5, |(x, y)| NonZeroU8::new((1 + x + 3 * y) as u8).unwrap());
+	let copy: ZArray2D<NonZeroU8> = serde_json::from_str(&serde_json::to_string(&array).unwrap()).unwrap();
+	assert_eq!(copy, array);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde_validation(){
+	// wrong number of values for the dimensions
+	assert!(serde_json::from_str::<ZArray2D<u8>>(r#"{"dims":[3,2],"data":[0,1,2,3,4]}"#).is_err());
+	assert!(serde_json::from_str::<ZArray2D<u8>>(r#"{"dims":[3,2],"data":[0,1,2,3,4,5,6]}"#).is_err());
+	// wrong number of dimensions
+	assert!(serde_json::from_str::<ZArray2D<u8>>(r#"{"dims":[6],"data":[0,1,2,3,4,5]}"#).is_err());
+	assert!(serde_json::from_str::<ZArray3D<u8>>(r#"{"dims":[3,2],"data":[0,1,2,3,4,5]}"#).is_err());
+	// missing and unknown fields
+	assert!(serde_json::from_str::<ZArray2D<u8>>(r#"{"dims":[3,2]}"#).is_err());
+	assert!(serde_json::from_str::<ZArray2D<u8>>(r#"{"dims":[3,2],"pwidth":1,"data":[0,1,2,3,4,5]}"#).is_err());
+	// overflowing dimensions
+	assert!(serde_json::from_str::<ZArray3D<u8>>(
+		r#"{"dims":[18446744073709551615,18446744073709551615,2],"data":[]}"#).is_err());
+	assert!(serde_json::from_str::<ZArray2D<u8>>(r#"{"dims":[18446744073709551615,0],"data":[]}"#).is_err());
+	// the topology is optional, but must have one entry per axis
+	let array: ZArray2D<u8> = serde_json::from_str(r#"{"dims":[3,2],"data":[0,1,2,3,4,5]}"#).unwrap();
+	assert_eq!(array.topology(), (zarray::AxisTopology::Bounded, zarray::AxisTopology::Bounded));
+	assert!(serde_json::from_str::<ZArray2D<u8>>(
+		r#"{"dims":[3,2],"data":[0,1,2,3,4,5],"topology":["Wrap"]}"#).is_err());
+	assert!(serde_json::from_str::<ZArray2D<u8>>(
+		r#"{"dims":[3,2],"data":[0,1,2,3,4,5],"topology":["Wrap","Round"]}"#).is_err());
+	// sequence form (as used by non-self-describing formats)
+	let array: ZArray2D<u8> = serde_json::from_str(r#"[[2,2],[1,2,3,4]]"#).unwrap();
+	assert_eq!(*array.get(1, 1).unwrap(), 4);
+	assert_eq!(*array.get(0, 1).unwrap(), 3);
+}
+
+#[test]
+fn test_error_coords(){
+	use zarray::BuildError;
+	use zarray::znd::ZArrayND;
+	let array = ZArray3D::<_>::new(4, 5, 6, 0u8);
+	let e = array.get(4, 1, 2).unwrap_err();
+	assert_eq!(e.coord(), &[4, 1, 2]);
+	assert_eq!(e.bounds(), &[4, 5, 6]);
+	assert_eq!(e.to_string(), "Error: could not access coordinate (4, 1, 2) because it is out of range for size (4, 5, 6)");
+	let result: Result<ZArrayND<u8, 2>, BuildError> = ZArrayND::from_iter_coords([2, 1], [([1, 0], 1u8), ([1, 0], 2u8)]);
+	match result {
+		Err(BuildError::DuplicateCoord(coord)) => assert_eq!(coord.as_slice(), &[1, 0]),
+		_ => panic!("expected a DuplicateCoord error")
+	}
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn test_rayon_parallel(){
+	use rayon::prelude::*;
+	use zarray::znd::ZArrayND;
+	let (w, h) = (300, 201);
+	let mut array = ZArray2D::<_>::new(w, h, 0u64);
+	array.par_transform(|(x, y), _| (x + w * y) as u64);
+	assert_eq!(array.par_iter().count(), w * h);
+	assert_eq!(array.par_iter().map(|item| *item.value).sum::<u64>(), ((w * h) * (w * h - 1) / 2) as u64);
+	array.par_iter_mut().filter(|item| item.x == 7).for_each(|item| *item.value = 0);
+	assert_eq!(*array.get(7, 200).unwrap(), 0);
+	assert_eq!(*array.get(8, 200).unwrap(), 8 + 200 * 300);
+	assert_eq!(array.par_coords(), array.coords());
+	let mut array = init_with_count_3d(17, 9, 20);
+	let serial: Vec<(usize, usize, usize, i32)> = array.iter().map(|item| (item.x, item.y, item.z, *item.value)).collect();
+	let parallel: Vec<(usize, usize, usize, i32)> = array.par_iter().map(|item| (item.x, item.y, item.z, *item.value)).collect();
+	assert_eq!(parallel, serial);
+	array.par_transform(|(x, y, z), v| v + (x * y * z) as i32);
+	assert_eq!(*array.get(16, 8, 19).unwrap(), (16 + 17 * (8 + 9 * 19) + 16 * 8 * 19) as i32);
+	array.par_iter_mut().for_each(|item| *item.value = -1);
+	assert!(array.iter().all(|item| *item.value == -1));
+	assert_eq!(array.par_coords(), array.coords());
+	let mut nd: ZArrayND<u8, 4, 4> = ZArrayND::new([5, 6, 7, 3], 1u8);
+	nd.par_transform(|[x, _, _, t], v| v + (x + t) as u8);
+	assert_eq!(nd.par_iter().map(|item| *item.value as usize).sum::<usize>(), nd.iter().map(|item| *item.value as usize).sum::<usize>());
+	assert_eq!(nd.par_coords(), nd.coords());
+}
+
+#[test]
+fn test_stencil_matches_sample(){
+	use zarray::EdgeMode;
+	use zarray::znd::ZArrayND;
+	let (w, h, d) = (19, 10, 13);
+	let array = init_with_count_3d(w, h, d);
+	let small: ZArray3D<i32, 4> = ZArrayND::new_with_constructor([w, h, d], |[x, y, z]| (x + w * (y + h * z)) as i32).into();
+	let radius = 2isize;
+	for edge_mode in [EdgeMode::Error, EdgeMode::Default(-7), EdgeMode::Wrap, EdgeMode::Clamp, EdgeMode::Mirror, EdgeMode::Reflect] {
+		// sum of the neighborhood, counting the missing neighbors
+		let mut sums = ZArray3D::new(w, h, d, (0i64, 0usize));
+		array.stencil(&mut sums, radius as usize, &edge_mode, |view| {
+			let mut count = 0;
+			let mut sum = 0i64;
+			for dz in -radius..=radius { for dy in -radius..=radius { for dx in -radius..=radius {
+				match view.get([dx, dy, dz]) {
+					Some(v) => sum += *v as i64,
+					None => count += 1
+				}
+			} } }
+			assert_eq!(view.get([radius + 1, 0, 0]), None);
+			(sum, count)
+		});
+		let mut small_sums: ZArray3D<(i64, usize), 4> = ZArrayND::new([w, h, d], (0i64, 0usize)).into();
+		small.stencil(&mut small_sums, radius as usize, &edge_mode, |view| {
+			(view.neighbors().map(|(_, v)| *v as i64).sum::<i64>() + *view.center() as i64, 0)
+		});
+		for item in sums.iter() {
+			let (x, y, z) = (item.x as isize, item.y as isize, item.z as isize);
+			let mut sum = 0i64;
+			let mut count = 0;
+			for dz in -radius..=radius { for dy in -radius..=radius { for dx in -radius..=radius {
+				match array.sample(x + dx, y + dy, z + dz, &edge_mode) {
+					Ok(v) => sum += *v as i64,
+					Err(_) => count += 1
+				}
+			} } }
+			assert_eq!(*item.value, (sum, count));
+			assert_eq!(small_sums.get(item.x, item.y, item.z).unwrap().0, sum);
+		}
+	}
+	// dense kernel
+	let array = init_with_count_2d(21, 12);
+	let mut output = ZArray2D::new(21, 12, 0i64);
+	let kernel = [0i64, 1, 0, 2, 3, 4, 0, 5, 0];
+	array.convolve(&mut output, &kernel, &EdgeMode::Wrap);
+	for item in output.iter() {
+		let (x, y) = (item.x as isize, item.y as isize);
+		let expected = *array.wrapped_get(x, y - 1) as i64 + 2 * *array.wrapped_get(x - 1, y) as i64
+			+ 3 * *array.wrapped_get(x, y) as i64 + 4 * *array.wrapped_get(x + 1, y) as i64
+			+ 5 * *array.wrapped_get(x, y + 1) as i64;
+		assert_eq!(*item.value, expected);
+	}
+}
+
+#[test]
+fn test_stencil_performance(){
+	use std::time::Instant;
+	use zarray::EdgeMode;
+	let (w, h) = (512, 512);
+	let radius = 2isize;
+	let src = init_with_count_2d(w, h);
+	// per-cell look-ups
+	let mut reference = ZArray2D::<_>::new(w, h, 0i64);
+	let t0 = Instant::now();
+	for y in 0..h as isize {
+		for x in 0..w as isize {
+			let mut sum = 0i64;
+			for dy in -radius..=radius { for dx in -radius..=radius {
+				sum += *src.bounded_get(x + dx, y + dy).unwrap_or(&0) as i64;
+			} }
+			reference.set(x as usize, y as usize, sum).unwrap();
+		}
+	}
+	let ref_time = t0.elapsed().as_secs_f64() * 1e6;
+	println!("ZArray2D {}x{} per-cell bounded_get sum of neighbors in radius {} performance: {} micros",
+		w, h, radius, ref_time as i64);
+	// patch-by-patch convolution
+	let mut output = ZArray2D::new(w, h, 0i64);
+	let t0 = Instant::now();
+	src.convolve(&mut output, &[1i64; 25], &EdgeMode::Default(0));
+	let my_time = t0.elapsed().as_secs_f64() * 1e6;
+	println!("ZArray2D {}x{} convolve sum of neighbors in radius {} performance: {} micros",
+		w, h, radius, my_time as i64);
+	println!("Performance improved by {}%", (100. * (ref_time / my_time - 1.)) as i32);
+	for item in output.iter() {
+		assert_eq!(item.value, reference.get(item.x, item.y).unwrap());
+	}
+}
+
+#[test]
+fn test_cellular_automaton_life(){
+	use zarray::EdgeMode;
+	use zarray::automaton::{CellularAutomaton, LifeRule};
+	let rule: LifeRule = "B3/S23".parse().unwrap();
+	assert_eq!(rule, LifeRule::parse("23/3").unwrap());
+	// blinker oscillates with period 2, checked against a reference implementation of the rule
+	let mut map = ZArray2D::<_>::new(7, 5, 0u8);
+	for x in 2..5 {
+		map.set(x, 2, 1).unwrap();
+	}
+	let mut life = CellularAutomaton::new(map.clone());
+	life.step_rule(&rule, &EdgeMode::Default(0));
+	for y in 0..5 {
+		for x in 0..7 {
+			let expected = if x == 3 && (1..4).contains(&y) { 1 } else { 0 };
+			assert_eq!(*life.current().get(x, y).unwrap(), expected);
+		}
+	}
+	life.step_rule(&rule, &EdgeMode::Default(0));
+	assert_eq!(life.generation(), 2);
+	for item in map.iter() {
+		assert_eq!(item.value, life.current().get(item.x, item.y).unwrap());
+	}
+	// custom rule closure: every cell becomes the number of live neighbors
+	let mut counter = CellularAutomaton::new(map);
+	counter.step(1, &EdgeMode::Wrap, |view| view.neighbors().filter(|(_, v)| **v == 1).count() as u8);
+	assert_eq!(*counter.current().get(3, 1).unwrap(), 3);
+	assert_eq!(*counter.current().get(3, 2).unwrap(), 2);
+}
+
+#[test]
+fn test_life_rule_parsing(){
+	use zarray::EdgeMode;
+	use zarray::automaton::{CellularAutomaton, LifeRule, RuleNeighborhood};
+	let brain = LifeRule::parse("B2/S/C3").unwrap();
+	assert_eq!(brain.states(), 3);
+	assert_eq!(brain.next_state(0, 2), 1);
+	assert_eq!(brain.next_state(1, 2), 2);
+	assert_eq!(brain.next_state(2, 2), 0);
+	let rule = LifeRule::parse("2,6,9/4,6-8/10/M").unwrap();
+	assert!(rule.is_survival(9) && !rule.is_survival(5) && rule.is_birth(7) && !rule.is_birth(9));
+	assert_eq!(LifeRule::parse("4/4/5/VN").unwrap().neighborhood(), RuleNeighborhood::VonNeumann);
+	for bad in ["", "B3", "B3/S2x", "4/4/1/M", "4/4/5/Q", "8-4/4/5/M", "200/4/5/M", "B3/S23/C3/X/Y",
+			"\u{e9}3/S23", "B3/\u{e9}23", "B\u{e9}/S23", "B3/S23/C\u{e9}", "\u{1f600}", "B3/S23/256", "B3/S23/C1"] {
+		assert!(LifeRule::parse(bad).is_err(), "'{}' should not parse", bad);
+	}
+	// the last dying state goes back to dead, even with the maximum number of states
+	let rule = LifeRule::parse("B3/S23/255").unwrap();
+	assert_eq!(rule.next_state(254, 0), 0);
+	assert_eq!(rule.next_state(255, 0), 0);
+	assert_eq!(rule.next_state(253, 0), 254);
+	// 3D rule: a single cell with 4 live neighbors is born, then decays through the dying states
+	let rule = LifeRule::parse("4/4/5/M").unwrap();
+	let mut map = ZArray3D::<_>::new(5, 5, 5, 0u8);
+	for (x, y, z) in [(1, 1, 1), (3, 1, 1), (1, 3, 1), (1, 1, 3)] {
+		map.set(x, y, z, 1).unwrap();
+	}
+	let mut cells = CellularAutomaton::new(map);
+	cells.step_rule(&rule, &EdgeMode::Default(0));
+	assert_eq!(*cells.current().get(2, 2, 2).unwrap(), 1);
+	assert_eq!(*cells.current().get(1, 1, 1).unwrap(), 2);
+	cells.step_rule(&rule, &EdgeMode::Default(0));
+	assert_eq!(*cells.current().get(1, 1, 1).unwrap(), 3);
+}
+
+#[test]
+fn test_views_2d(){
+	let (w, h) = (37, 29);
+	let mut array = ZArray2D::<_>::new_with_constructor(w, h, |(x, y)| x + w * y);
+	// immutable view of an unaligned region, in local coordinates
+	let view = array.view(5..30, 3..=20);
+	assert_eq!(view.dimensions(), (25, 18));
+	assert_eq!(view.origin(), (5, 3));
+	assert_eq!(*view.get(0, 0).unwrap(), 5 + w * 3);
+	assert!(view.get(25, 0).is_err());
+	assert_eq!(view.bounded_get(-1, 0), None);
+	assert_eq!(*view.wrapped_get(-1, 18), *array.get(29, 3).unwrap());
+	assert_eq!(view.iter().count(), 25 * 18);
+	for item in view.iter() {
+		assert_eq!(*item.value, item.x + 5 + w * (item.y + 3));
+	}
+	let inner = view.view(10.., ..2);
+	assert_eq!(inner.origin(), (15, 3));
+	assert_eq!(inner.dimensions(), (15, 2));
+	// views are clipped to the array
+	assert_eq!(array.view(30..100, 20..100).dimensions(), (7, 9));
+	assert_eq!(array.view(40..50, 0..1).iter().count(), 0);
+	// mutable view
+	let mut tile = array.view_mut(8..24, 8..24);
+	tile.fill(0, 0, 16, 16, 0).unwrap();
+	assert!(tile.fill(0, 0, 17, 1, 0).is_err());
+	tile.set(1, 2, 7).unwrap();
+	tile.wrapped_set(-1, -1, 9);
+	tile.bounded_set(16, 0, 99);
+	for item in tile.iter_mut() {
+		*item.value += item.x;
+	}
+	for y in 0..h {
+		for x in 0..w {
+			let expected = if (8..24).contains(&x) && (8..24).contains(&y) {
+				(x - 8) + match (x - 8, y - 8) { (1, 2) => 7, (15, 15) => 9, _ => 0 }
+			} else {
+				x + w * y
+			};
+			assert_eq!(*array.get(x, y).unwrap(), expected, "at ({}, {})", x, y);
+		}
+	}
+}
+
+#[test]
+fn test_views_split_and_threads(){
+	let (w, h) = (50, 41);
+	let mut array = ZArray2D::<_>::new(w, h, 0usize);
+	{
+		let (left, right) = array.split_at_x_mut(13);
+		assert_eq!((left.dimensions(), right.dimensions()), ((13, 41), (37, 41)));
+		let (top, bottom) = right.split_at_y_mut(20);
+		assert_eq!((top.origin(), bottom.origin()), ((13, 0), (13, 20)));
+		std::thread::scope(|scope| {
+			for (i, mut view) in [left, top, bottom].into_iter().enumerate() {
+				scope.spawn(move || {
+					for item in view.iter_mut() {
+						*item.value = i + 1;
+					}
+				});
+			}
+		});
+	}
+	for item in array.iter() {
+		let expected = if item.x < 13 { 1 } else if item.y < 20 { 2 } else { 3 };
+		assert_eq!(*item.value, expected);
+	}
+	// tiles of a 3D array written in parallel
+	let (xs, ys, zs) = (19, 10, 21);
+	let mut array3d = ZArray3D::<_>::new(xs, ys, zs, 0usize);
+	let chunks = array3d.chunks_mut(8, 5, 6);
+	assert_eq!(chunks.len(), 3 * 2 * 4);
+	std::thread::scope(|scope| {
+		for mut chunk in chunks {
+			scope.spawn(move || {
+				let (ox, oy, oz) = chunk.origin();
+				for item in chunk.iter_mut() {
+					*item.value = (item.x + ox) + xs * ((item.y + oy) + ys * (item.z + oz));
+				}
+			});
+		}
+	});
+	for item in array3d.iter() {
+		assert_eq!(*item.value, item.x + xs * (item.y + ys * item.z));
+	}
+	let (first, second) = array3d.split_at_z_mut(21);
+	assert_eq!((first.dimensions(), second.dimensions()), ((19, 10, 21), (19, 10, 0)));
+	assert_eq!(*first.as_view().view(1..2, 2..3, 3..4).get(0, 0, 0).unwrap(), 1 + xs * (2 + ys * 3));
+}
+
+#[test]
+fn test_zarray3d_slices(){
+	use zarray::BuildError;
+	let (xs, ys, zs) = (13, 9, 19);
+	let mut array = init_with_count_3d(xs, ys, zs);
+	let xy = array.slice_xy(10).unwrap();
+	assert_eq!(xy.dimensions(), (xs, ys));
+	for item in xy.iter() {
+		assert_eq!(item.value, array.get(item.x, item.y, 10).unwrap());
+	}
+	let xz = array.slice_xz(8).unwrap();
+	assert_eq!(xz.dimensions(), (xs, zs));
+	for item in xz.iter() {
+		assert_eq!(item.value, array.get(item.x, 8, item.y).unwrap());
+	}
+	let yz = array.slice_yz(0).unwrap();
+	assert_eq!(yz.dimensions(), (ys, zs));
+	for item in yz.iter() {
+		assert_eq!(item.value, array.get(0, item.x, item.y).unwrap());
+	}
+	assert!(array.slice_xy(zs).is_err());
+	assert!(array.slice_yz(xs).is_err());
+	// overwrite the YZ plane at x = 3, then restore the XZ plane at y = 8 that crosses it
+	let mut plane = ZArray2D::new(ys, zs, -1);
+	for item in plane.iter_mut() {
+		*item.value = -(item.x as i32) - 100 * item.y as i32;
+	}
+	let before = array.clone();
+	array.set_slice_yz(3, &plane).unwrap();
+	array.set_slice_xz(8, &xz).unwrap();
+	for z in 0..zs { for y in 0..ys { for x in 0..xs {
+		let expected = if x == 3 && y != 8 {
+			-(y as i32) - 100 * z as i32
+		} else {
+			*before.get(x, y, z).unwrap()
+		};
+		assert_eq!(*array.get(x, y, z).unwrap(), expected);
+	} } }
+	assert_eq!(array.set_slice_xy(zs, &xy), Err(BuildError::OutOfBounds(array.get(0, 0, zs).unwrap_err())));
+	match array.set_slice_xy(0, &xz) {
+		Err(BuildError::DimensionMismatch{expected, actual}) => {
+			assert_eq!((expected.as_slice(), actual.as_slice()), (&[xs, ys][..], &[xs, zs][..]));
+		},
+		other => panic!("expected a dimension mismatch, got {:?}", other)
+	}
+}
+
+#[test]
+fn test_resize_crop_pad_2d(){
+	let (w, h) = (21, 13);
+	let original = init_with_count_2d(w, h);
+	let check = |array: &ZArray2D<i32>, source: &ZArray2D<i32>, dx: isize, dy: isize, fill: i32| {
+		for item in array.iter() {
+			let (x, y) = (item.x as isize - dx, item.y as isize - dy);
+			let expected = source.bounded_get(x, y).copied().unwrap_or(fill);
+			assert_eq!(*item.value, expected, "at ({}, {})", item.x, item.y);
+		}
+	};
+	let mut array = original.clone();
+	array.resize(30, 9, -1);
+	assert_eq!(array.dimensions(), (30, 9));
+	check(&array, &original, 0, 0, -1);
+	// values that were dropped by shrinking do not come back
+	let shrunk = array.clone();
+	array.resize(5, 40, -2);
+	check(&array, &shrunk, 0, 0, -2);
+	// aligned and unaligned crops
+	let mut array = original.clone();
+	array.crop(8, 0, 10, 13).unwrap();
+	assert_eq!(array.dimensions(), (10, 13));
+	check(&array, &original, -8, 0, 0);
+	let mut array = original.clone();
+	array.crop(3, 5, 17, 8).unwrap();
+	check(&array, &original, -3, -5, 0);
+	assert!(array.crop(1, 0, 17, 1).is_err());
+	assert_eq!(array.dimensions(), (17, 8));
+	array.crop(17, 8, 0, 0).unwrap();
+	assert_eq!(array.iter().count(), 0);
+	// aligned and unaligned padding
+	let mut array = original.clone();
+	array.pad(16, 3, 8, 0, -5);
+	assert_eq!(array.dimensions(), (16 + w + 3, 8 + h));
+	check(&array, &original, 16, 8, -5);
+	let mut array = original.clone();
+	array.pad(1, 2, 3, 4, -6);
+	check(&array, &original, 1, 3, -6);
+	assert_eq!(*array.get(0, 0).unwrap(), -6);
+	// equality and hashing only look at the values in bounds
+	let hash = |array: &ZArray2D<i32>| {
+		use std::hash::{Hash, Hasher};
+		let mut hasher = std::collections::hash_map::DefaultHasher::new();
+		array.hash(&mut hasher);
+		hasher.finish()
+	};
+	let mut array = ZArray2D::from_row_major(10, 10, &(0..100).collect::<Vec<i32>>()).unwrap();
+	array.crop(0, 0, 5, 5).unwrap();
+	let expected = ZArray2D::from_row_major(5, 5, &(0..25).map(|i| (i % 5) + 10 * (i / 5)).collect::<Vec<i32>>()).unwrap();
+	assert_eq!(array, expected);
+	assert_eq!(hash(&array), hash(&expected));
+	let mut array = ZArray2D::new(9, 9, 0);
+	array.set(7, 7, 1).unwrap();
+	array.crop(0, 0, 5, 5).unwrap();
+	assert_eq!(array, ZArray2D::new(5, 5, 0));
+	assert_eq!(hash(&array), hash(&ZArray2D::new(5, 5, 0)));
+	array.set(4, 4, 1).unwrap();
+	assert_ne!(array, ZArray2D::new(5, 5, 0));
+}
+
+#[test]
+#[should_panic(expected = "padded array size overflows")]
+fn test_pad_2d_overflow(){
+	let mut array = ZArray2D::<_>::new(5, 5, 0u8);
+	array.pad(usize::MAX - 4, 0, 0, 0, 1);
+}
+
+#[test]
+#[should_panic(expected = "is too large")]
+fn test_resize_3d_overflow(){
+	let mut array = ZArray3D::<_>::new(5, 5, 5, 0u8);
+	array.resize(usize::MAX - 2, 1, 1, 1);
+}
+
+#[test]
+fn test_resize_crop_pad_3d(){
+	let (xs, ys, zs) = (10, 11, 17);
+	let original = init_with_count_3d(xs, ys, zs);
+	let mut array = original.clone();
+	array.pad(8, 0, 2, 1, 0, 3, -1);
+	array.crop(8, 2, 0, xs, ys, zs).unwrap();
+	for item in array.iter() {
+		assert_eq!(item.value, original.get(item.x, item.y, item.z).unwrap());
+	}
+	array.resize(4, 20, 9, -2);
+	for item in array.iter() {
+		let expected = original.bounded_get(item.x as isize, item.y as isize, item.z as isize).copied().unwrap_or(-2);
+		assert_eq!(*item.value, expected);
+	}
+	assert!(array.crop(0, 0, 9, 1, 1, 1).is_err());
+}
+
+#[test]
+fn test_row_major_conversions(){
+	use zarray::BuildError;
+	let (w, h) = (19, 11);
+	let mut prng = StdRng::seed_from_u64(20240229u64);
+	let ref_map: Vec<Vec<u8>> = (0..h).map(|_| (0..w).map(|_| prng.gen()).collect()).collect();
+	let map = ZArray2D::<u8>::try_from(ref_map.clone()).unwrap();
+	for y in 0..h {
+		for x in 0..w {
+			assert_eq!(ref_map[y][x], *map.get(x, y).unwrap());
+		}
+	}
+	assert_eq!(map.to_vec_of_vecs(), ref_map);
+	let flat: Vec<u8> = ref_map.iter().flatten().copied().collect();
+	assert_eq!(map.to_row_major(), flat);
+	let copy = ZArray2D::<_>::from_row_major(w, h, &flat).unwrap();
+	assert_eq!(copy.to_vec_of_vecs(), ref_map);
+	assert_eq!(ZArray2D::<_>::from_row_major(w, h, &flat[1..]).unwrap_err(),
+		BuildError::SizeMismatch{expected: w * h, actual: w * h - 1});
+	let mut ragged = ref_map.clone();
+	ragged[4].pop();
+	match ZArray2D::<u8>::try_from(ragged) {
+		Err(BuildError::Ragged{index, expected, actual}) => assert_eq!((index.as_slice(), expected, actual), (&[4][..], w, w - 1)),
+		other => panic!("expected a ragged error, got {:?}", other)
+	}
+	assert_eq!(ZArray2D::<u8>::try_from(Vec::new()).unwrap().dimensions(), (0, 0));
+	// 3D
+	let (xs, ys, zs) = (9, 17, 5);
+	let array = init_with_count_3d(xs, ys, zs);
+	let expected: Vec<i32> = (0..(xs * ys * zs) as i32).collect();
+	assert_eq!(array.to_row_major(), expected);
+	let planes = array.to_vec_of_vecs();
+	assert_eq!(planes[3][16][8], *array.get(8, 16, 3).unwrap());
+	let copy = ZArray3D::<i32>::try_from(planes.clone()).unwrap();
+	assert_eq!(ZArray3D::<_>::from_row_major(xs, ys, zs, &expected).unwrap().to_row_major(), copy.to_row_major());
+	let mut ragged = planes.clone();
+	ragged[2][7].push(0);
+	match ZArray3D::<i32>::try_from(ragged) {
+		Err(BuildError::Ragged{index, ..}) => assert_eq!(index.as_slice(), &[7, 2]),
+		other => panic!("expected a ragged error, got {:?}", other)
+	}
+	let mut ragged = planes;
+	ragged[1].pop();
+	assert!(matches!(ZArray3D::<i32>::try_from(ragged), Err(BuildError::Ragged{expected: 17, actual: 16, ..})));
+}
+
+#[cfg(feature = "ndarray")]
+#[test]
+fn test_ndarray_conversions() {
+	use ndarray::{s, Array2, Array3};
+	let (w, h) = (21, 13);
+	let map = init_with_count_2d(w, h);
+	let array = Array2::from(&map);
+	assert_eq!(array.dim(), (h, w));
+	for y in 0..h { for x in 0..w {
+		assert_eq!(array[[y, x]], *map.get(x, y).unwrap());
+	} }
+	let copy = ZArray2D::<i32>::from(array.clone());
+	assert_eq!(copy.to_row_major(), map.to_row_major());
+	// non-standard layouts are copied in logical order
+	let transposed = ZArray2D::<i32>::from(&array.t());
+	assert_eq!(transposed.dimensions(), (h, w));
+	assert_eq!(*transposed.get(4, 9).unwrap(), array[[4, 9]]);
+	let window = ZArray2D::<i32, 4>::from(&array.slice(s![2..7, 3..11]));
+	assert_eq!(window.dimensions(), (8, 5));
+	assert_eq!(*window.get(7, 4).unwrap(), array[[6, 10]]);
+	// 3D
+	let (xs, ys, zs) = (9, 17, 5);
+	let volume = init_with_count_3d(xs, ys, zs);
+	let array = Array3::from(&volume);
+	assert_eq!(array.dim(), (zs, ys, xs));
+	assert_eq!(array[[3, 16, 8]], *volume.get(8, 16, 3).unwrap());
+	let copy = ZArray3D::<i32>::from(array);
+	assert_eq!(copy.dimensions(), (xs, ys, zs));
+	assert_eq!(copy.to_row_major(), volume.to_row_major());
+	assert_eq!(Array3::from(copy), Array3::from(volume));
+}
+
+#[cfg(feature = "image")]
+#[test]
+fn test_image_conversions() {
+	use image::{ImageBuffer, Luma, Rgba};
+	let dir = std::env::temp_dir().join(format!("zarray_image_test_{}", std::process::id()));
+	std::fs::create_dir_all(&dir).unwrap();
+	// 2D
+	let (w, h) = (37, 21);
+	let mut map = ZArray2D::<_>::new(w, h, [0u8, 0u8, 0u8, 255u8]);
+	for y in 0..h { for x in 0..w {
+		map.set(x, y, [x as u8, y as u8, (x * y) as u8, 255u8]).unwrap();
+	} }
+	let image = ImageBuffer::<Rgba<u8>, Vec<u8>>::from(&map);
+	assert_eq!(image.dimensions(), (w as u32, h as u32));
+	assert_eq!(image.get_pixel(30, 20), &Rgba([30u8, 20u8, 88u8, 255u8]));
+	assert_eq!(ZArray2D::<[u8; 4]>::from(&image).to_row_major(), map.to_row_major());
+	let path = dir.join("rgba.png");
+	map.save_png(&path).unwrap();
+	assert_eq!(ZArray2D::<[u8; 4]>::load_png(&path).unwrap().to_row_major(), map.to_row_major());
+	let gray = ZArray2D::<u16>::load_png(&path).unwrap();
+	assert_eq!(gray.dimensions(), (w, h));
+	let depth = ZArray2D::<_>::new_with_constructor(w, h, |(x, y)| (x * 1000 + y) as u16);
+	depth.save_png(dir.join("depth.png")).unwrap();
+	let loaded = ZArray2D::<u16>::load_png(dir.join("depth.png")).unwrap();
+	assert_eq!(*loaded.get(36, 20).unwrap(), 36020u16);
+	// 3D
+	let volume = ZArray3D::<_>::new_with_constructor(9, 7, 12, |(x, y, z)| (x + 10 * y + 100 * z) as u16);
+	let stack = volume.to_image_stack();
+	assert_eq!(stack.len(), 12);
+	assert_eq!(stack[11].get_pixel(8, 6), &Luma([1168u16]));
+	let paths = volume.save_png_stack(&dir, "slice_").unwrap();
+	assert_eq!(paths[3].file_name().unwrap(), "slice_03.png");
+	let loaded = ZArray3D::<u16>::load_png_stack(&paths).unwrap();
+	assert_eq!(loaded.dimensions(), (9, 7, 12));
+	assert_eq!(loaded.to_row_major(), volume.to_row_major());
+	let mut mixed = stack.clone();
+	mixed.push(ImageBuffer::new(3, 3));
+	assert!(matches!(ZArray3D::<u16>::from_image_stack(&mixed), Err(zarray::BuildError::DimensionMismatch{..})));
+	let mut mixed = vec![ImageBuffer::new(9, 6)];
+	mixed.extend(stack.iter().cloned());
+	assert!(matches!(ZArray3D::<u16>::from_image_stack(&mixed), Err(zarray::BuildError::DimensionMismatch{..})));
+	// empty arrays and stacks
+	let stack = ZArray3D::<_>::new(0, 5, 3, 0u8).to_image_stack();
+	assert_eq!(stack.len(), 3);
+	assert!(stack.iter().all(|image| image.dimensions() == (0, 5)));
+	assert_eq!(ZArray3D::<u8>::from_image_stack(&stack).unwrap().dimensions(), (0, 5, 3));
+	assert!(ZArray3D::<_>::new(4, 5, 0, 0u8).to_image_stack().is_empty());
+	assert_eq!(ZArray3D::<u8>::from_image_stack::<Vec<u8>>(&[]).unwrap().dimensions(), (0, 0, 0));
+	std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_sparse_arrays() {
+	use zarray::sparse::{ZSparseArray2D, ZSparseArray3D};
+	// 2D against a dense reference
+	let (w, h) = (53, 41);
+	let mut sparse = ZSparseArray2D::new(w, h, 0u8);
+	let (mut ref_map, _) = seed_arrays_u8(w, h);
+	let mut prng = StdRng::seed_from_u64(20240605u64);
+	for _ in 0..300 {
+		let x = prng.gen_range(0..w);
+		let y = prng.gen_range(0..h);
+		let v = if prng.gen_bool(0.5) { 0u8 } else { prng.gen() };
+		sparse.set(x, y, v).unwrap();
+		ref_map[y][x] = v;
+	}
+	for y in 0..h { for x in 0..w {
+		assert_eq!(*sparse.get(x, y).unwrap(), ref_map[y][x]);
+	} }
+	assert_eq!(sparse.iter().count(), w * h);
+	assert!(sparse.iter().all(|item| *item.value == ref_map[item.y][item.x]));
+	let nonzero = ref_map.iter().flatten().filter(|v| **v != 0).count();
+	assert_eq!(sparse.iter_non_background().filter(|item| *item.value != 0).count(), nonzero);
+	assert!(sparse.get(w, 0).is_err());
+	assert_eq!(sparse.bounded_get(-1, 0), None);
+	sparse.wrapped_set(-1, -1, 7u8);
+	assert_eq!(*sparse.get(w - 1, h - 1).unwrap(), 7u8);
+	assert_eq!(*sparse.wrapped_get(-1, -1), 7u8);
+	let dense = sparse.to_dense();
+	assert_eq!(*dense.get(w - 1, h - 1).unwrap(), 7u8);
+	let copy = ZSparseArray2D::from_dense(&dense, 0u8);
+	assert_eq!(copy.allocated_patch_count(), sparse.allocated_patch_count());
+	// patches are allocated on the first non-background write and freed when cleared
+	sparse.fill(0, 0, w, h, 0u8).unwrap();
+	assert_eq!(sparse.allocated_patch_count(), 0);
+	assert_eq!(sparse.iter_non_background().count(), 0);
+	sparse.set(9, 9, 0u8).unwrap();
+	assert_eq!(sparse.allocated_patch_count(), 0);
+	sparse.bounded_fill(-4, -4, 4, 4, 1u8);
+	assert_eq!(sparse.allocated_patch_count(), 1);
+	assert_eq!(sparse.iter_non_background().count(), 64);
+	sparse.bounded_set(3, 3, 0u8);
+	assert_eq!(sparse.allocated_patch_count(), 1);
+	sparse.bounded_fill(0, 0, 4, 4, 0u8);
+	assert_eq!(sparse.allocated_patch_count(), 0);
+	// 3D
+	let mut world = ZSparseArray3D::new(100, 60, 100, 0u16);
+	assert_eq!(world.dimensions(), (100, 60, 100));
+	world.fill(10, 0, 10, 20, 5, 30, 3u16).unwrap();
+	assert_eq!(world.allocated_patch_count(), 2 * 3); // 2 patches in X, 1 in Y, 3 in Z
+	assert_eq!(*world.get(15, 4, 25).unwrap(), 3u16);
+	assert_eq!(*world.get(15, 5, 25).unwrap(), 0u16);
+	assert_eq!(world.iter_non_background().filter(|item| *item.value == 3u16).count(), 10 * 5 * 20);
+	let dense = world.to_dense();
+	assert_eq!(dense.iter().filter(|item| *item.value == 3u16).count(), 10 * 5 * 20);
+	let copy = ZSparseArray3D::from_dense(&dense, 0u16);
+	assert_eq!(copy.allocated_patch_count(), world.allocated_patch_count());
+	world.bounded_fill(-10, -10, -10, 200, 200, 200, 0u16);
+	assert_eq!(world.allocated_patch_count(), 0);
+	// patches that become uniform in any value are freed, including edge patches
+	world.fill(8, 8, 8, 16, 16, 16, 5u16).unwrap();
+	assert_eq!(world.allocated_patch_count(), 0);
+	assert_eq!(world.iter_non_background().filter(|item| *item.value == 5u16).count(), 8 * 8 * 8);
+	assert_eq!(world.iter_non_background().count(), 8 * 8 * 8);
+	world.set(8, 8, 8, 1u16).unwrap();
+	assert_eq!(world.allocated_patch_count(), 1);
+	world.set(8, 8, 8, 5u16).unwrap();
+	assert_eq!(world.allocated_patch_count(), 0);
+	world.fill(96, 56, 96, 100, 60, 100, 9u16).unwrap();
+	assert_eq!(world.allocated_patch_count(), 0);
+	assert_eq!(*world.get(99, 59, 99).unwrap(), 9u16);
+	assert_eq!(world.to_dense().iter().filter(|item| *item.value == 9u16).count(), 4 * 4 * 4);
+	// wrapped fill across the corner of the array
+	world.wrapped_fill(-2, -2, -2, 2, 2, 2, 7u16);
+	assert_eq!(world.iter().filter(|item| *item.value == 7u16).count(), 4 * 4 * 4);
+	assert_eq!(*world.get(99, 0, 98).unwrap(), 7u16);
+	let mut sparse = ZSparseArray2D::new(10, 10, 0u8);
+	sparse.wrapped_fill(8, 8, 12, 12, 3u8);
+	assert_eq!(*sparse.get(1, 9).unwrap(), 3u8);
+	assert_eq!(sparse.iter().filter(|item| *item.value == 3u8).count(), 16);
+	// fully covered patches are made uniform without allocating them
+	sparse.wrapped_fill(-2, 0, 18, 8, 4u8);
+	assert_eq!(sparse.allocated_patch_count(), 1);
+	assert_eq!(sparse.iter().filter(|item| *item.value == 4u8).count(), 10 * 8);
+	assert_eq!(sparse.iter_non_background().count(), 10 * 10);
+	sparse.wrapped_fill(0, 0, 10, 10, 0u8);
+	assert_eq!(sparse.iter_non_background().count(), 0);
+	assert!(sparse.fill(2, 2, 4, 11, 1u8).is_err());
+	assert_eq!(sparse.iter_non_background().count(), 0);
+	// random writes of a few values against a dense reference
+	let mut prng = StdRng::seed_from_u64(20240606u64);
+	let mut sparse = ZSparseArray2D::new(13, 11, 0u8);
+	let mut dense = ZArray2D::new(13, 11, 0u8);
+	for _ in 0..5000 {
+		let (x, y, v) = (prng.gen_range(0..13), prng.gen_range(0..11), prng.gen_range(0..3u8));
+		sparse.set(x, y, v).unwrap();
+		dense.set(x, y, v).unwrap();
+		if prng.gen_bool(0.01) {
+			sparse.fill(0, 0, 13, 11, v).unwrap();
+			dense.fill(0, 0, 13, 11, v).unwrap();
+			assert_eq!(sparse.allocated_patch_count(), 0);
+		}
+	}
+	assert_eq!(sparse.to_dense(), dense);
+	let mixed_patches = [(0, 0), (8, 0), (0, 8), (8, 8)].iter().filter(|(px, py)| {
+		let first = *dense.get(*px, *py).unwrap();
+		dense.iter_region(*px..*px + 8, *py..*py + 8).any(|item| *item.value != first)
+	}).count();
+	assert_eq!(sparse.allocated_patch_count(), mixed_patches);
+	assert_eq!(ZSparseArray2D::from_dense(&dense, 0u8).allocated_patch_count(), mixed_patches);
+}
+
+#[test]
+fn test_infinite_grids() {
+	use std::sync::{Arc, Mutex};
+	use zarray::infinite::{ZInfiniteGrid2D, ZInfiniteGrid3D};
+	// 2D with negative coordinates
+	let mut grid = ZInfiniteGrid2D::new(0i64);
+	assert_eq!(grid.bounds(), None);
+	assert_eq!(grid.get(0, 0), None);
+	for y in -30..30isize { for x in -45..17isize {
+		grid.set(x, y, (x * 1000 + y) as i64);
+	} }
+	for y in -30..30isize { for x in -45..17isize {
+		assert_eq!(grid.get(x, y), Some(&((x * 1000 + y) as i64)));
+	} }
+	assert_eq!(grid.bounds(), Some(((-48, -32), (24, 32))));
+	assert_eq!(grid.chunk_count(), 9 * 8);
+	assert_eq!(grid.iter().count(), 9 * 8 * 64);
+	let region: Vec<(isize, isize)> = grid.iter_region(-10..-3, 2..=4).map(|item| (item.x, item.y)).collect();
+	assert_eq!(region.len(), 7 * 3);
+	assert!(grid.iter_region(-10..-3, 2..=4).all(|item| *item.value == (item.x * 1000 + item.y) as i64));
+	assert_eq!(grid.iter_region(.., ..).count(), grid.iter().count());
+	assert_eq!(grid.iter_region(100..200, ..).count(), 0);
+	assert_eq!(grid.iter_region(-10..-3, 40..50).count(), 0);
+	assert_eq!(grid.iter_region(-10..-3, -40..-33).count(), 0);
+	assert_eq!(grid.iter_region(-10..-3, -40..-30).count(), 7 * 2);
+	assert_eq!(grid.iter_region(-10..-3, ..).count(), 7 * 64);
+	*grid.get_mut(-1000, 5) += 3;
+	assert_eq!(grid.get(-1000, 5), Some(&3));
+	assert_eq!(grid.get(-999, 5), Some(&0));
+	// load and unload hooks
+	let saved = Arc::new(Mutex::new(Vec::new()));
+	let sink = saved.clone();
+	let mut world = ZInfiniteGrid3D::new(0u8)
+		.with_load_hook(|chunk| {
+			for item in chunk.iter_mut() {
+				*item.value = if item.coord[1] < 0 { 1u8 } else { 0u8 };
+			}
+		})
+		.with_unload_hook(move |chunk| sink.lock().unwrap().push(chunk.chunk_coord()));
+	assert_eq!(world.load_region(-16, -16, -16, 16, 16, 16), 4 * 4 * 4);
+	assert_eq!(world.load_region(-8, -8, -8, 8, 8, 8), 0);
+	assert_eq!(world.get(3, -1, 7), Some(&1u8));
+	assert_eq!(world.get(3, 0, 7), Some(&0u8));
+	assert!(!world.is_loaded(16, 0, 0));
+	world.set(16, -4, 0, 9u8);
+	assert_eq!(world.get(16, -3, 0), Some(&1u8));
+	assert_eq!(world.get(16, -4, 0), Some(&9u8));
+	assert_eq!(world.iter_region(0..4, -2..2, 0..1).filter(|item| *item.value == 1u8).count(), 4 * 2);
+	assert_eq!(world.unload_outside(-8, -8, -8, 8, 8, 8), 4 * 4 * 4 + 1 - 2 * 2 * 2);
+	assert_eq!(world.chunk_count(), 8);
+	assert!(saved.lock().unwrap().contains(&[2, -1, 0]));
+	assert_eq!(world.unload_all(), 8);
+	assert_eq!(saved.lock().unwrap().len(), 4 * 4 * 4 + 1);
+	// chunk lengths other than the default
+	let mut small = ZInfiniteGrid2D::<u8, 4>::new_with_fill(1u8);
+	small.set(-1, 5, 2u8);
+	assert_eq!(small.bounds(), Some(((-4, 4), (0, 8))));
+	assert_eq!(small.iter().filter(|item| *item.value == 1u8).count(), 15);
+	let mut large = ZInfiniteGrid3D::<u8, 16>::new_with_fill(0u8);
+	assert_eq!(large.load_region(0, 0, 0, 17, 1, 1), 2);
+	assert_eq!(large.iter().count(), 2 * 16 * 16 * 16);
+}
+
+#[test]
+fn test_snapshots_and_undo_history() {
+	use zarray::history::UndoHistory;
+	// a snapshot is not affected by changes to the array
+	let mut arr = ZArray3D::new(20, 20, 20, 0u16);
+	arr.bounded_fill(0, 0, 0, 4, 4, 4, 1u16);
+	let first = arr.snapshot();
+	assert_eq!(first.dimensions(), [20, 20, 20]);
+	arr.set(2, 2, 2, 5).unwrap();
+	for item in arr.iter_mut() {
+		*item.value += 1;
+	}
+	let second = arr.snapshot_since(&first);
+	*arr.get_mut(19, 19, 19).unwrap() = 9;
+	let first_values: ZArray3D<u16> = first.to_array().into();
+	assert_eq!(first_values.iter().map(|item| *item.value as usize).sum::<usize>(), 4 * 4 * 4);
+	assert_eq!(*ZArray3D::from(second.to_array()).get(2, 2, 2).unwrap(), 6);
+	// restoring a snapshot only needs the patches that changed, and works on another array
+	let third = arr.snapshot_since(&second);
+	arr.restore(&first);
+	assert_eq!(arr, first_values);
+	let mut other = ZArray3D::new(4, 4, 4, 0u16);
+	other.restore(&third);
+	assert_eq!(*other.get(19, 19, 19).unwrap(), 9);
+	assert_eq!(*other.get(2, 2, 2).unwrap(), 6);
+	// values that are neither Copy nor Sync
+	let mut words = ZArray2D::<_>::new_with_constructor(9, 9, |_| core::cell::RefCell::new(String::from("a")));
+	let words_snapshot = words.snapshot();
+	words.get_mut(8, 8).unwrap().borrow_mut().push('b');
+	assert_eq!(*words.get(8, 8).unwrap().borrow(), "ab");
+	words.restore(&words_snapshot);
+	assert!(words.iter().all(|item| *item.value.borrow() == "a"));
+	// the arrays are Send for values that are Send but not Sync
+	fn is_send<S: Send>() {}
+	is_send::<ZArray2D<core::cell::Cell<i32>>>();
+	is_send::<ZArray3D<core::cell::Cell<i32>>>();
+	is_send::<zarray::znd::ZArrayND<core::cell::Cell<i32>, 4>>();
+	// undo and redo
+	let mut history = UndoHistory::with_limit(ZArray2D::<_>::new(16, 16, 0u8), 2);
+	assert!(!history.undo());
+	history.edit().set(1, 1, 1).unwrap();
+	history.edit().set(2, 2, 2).unwrap();
+	history.edit().set(3, 3, 3).unwrap();
+	assert_eq!(history.undo_count(), 2);
+	assert!(history.undo());
+	assert!(history.undo());
+	assert!(!history.undo());
+	assert_eq!(*history.current().get(1, 1).unwrap(), 1);
+	assert_eq!(*history.current().get(2, 2).unwrap(), 0);
+	assert_eq!(history.redo_count(), 2);
+	assert!(history.redo());
+	assert_eq!(*history.current().get(2, 2).unwrap(), 2);
+	assert_eq!(*history.current().get(3, 3).unwrap(), 0);
+	// a new edit discards the redo steps
+	history.edit().set(4, 4, 4).unwrap();
+	assert!(!history.redo());
+	assert!(history.undo());
+	assert_eq!(*history.current().get(4, 4).unwrap(), 0);
+	assert_eq!(*history.current().get(2, 2).unwrap(), 2);
+	assert_eq!(*history.into_current().get(1, 1).unwrap(), 1);
+}